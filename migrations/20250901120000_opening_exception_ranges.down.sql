@@ -0,0 +1,9 @@
+DROP TABLE IF EXISTS `opening_holidays`;
+DELETE FROM opening_exceptions
+WHERE end_date <> start_date;
+ALTER TABLE opening_exceptions
+    DROP CHECK chk_opening_exceptions_range,
+    DROP COLUMN note,
+    DROP COLUMN end_date;
+ALTER TABLE opening_exceptions
+    RENAME COLUMN start_date TO date;
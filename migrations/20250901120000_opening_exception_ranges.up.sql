@@ -0,0 +1,45 @@
+ALTER TABLE opening_exceptions
+    RENAME COLUMN date TO start_date;
+ALTER TABLE opening_exceptions
+    ADD COLUMN end_date DATE NULL AFTER start_date,
+    ADD COLUMN note VARCHAR(255) NULL;
+UPDATE opening_exceptions
+SET end_date = start_date;
+ALTER TABLE opening_exceptions
+    MODIFY end_date DATE NOT NULL,
+    ADD CONSTRAINT chk_opening_exceptions_range CHECK (start_date <= end_date);
+CREATE TABLE IF NOT EXISTS opening_holidays (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL,
+    month TINYINT UNSIGNED NULL,
+    day TINYINT UNSIGNED NULL,
+    movable VARCHAR(32) NULL,
+    is_closed TINYINT(1) NOT NULL DEFAULT 1,
+    opens_at TIME NULL,
+    closes_at TIME NULL,
+    CHECK (
+        (
+            movable IS NULL
+            AND month BETWEEN 1 AND 12
+            AND day BETWEEN 1 AND 31
+        )
+        OR (
+            movable IS NOT NULL
+            AND month IS NULL
+            AND day IS NULL
+        )
+    ),
+    CHECK (
+        (
+            is_closed = 1
+            AND opens_at IS NULL
+            AND closes_at IS NULL
+        )
+        OR (
+            is_closed = 0
+            AND opens_at IS NOT NULL
+            AND closes_at IS NOT NULL
+            AND opens_at < closes_at
+        )
+    )
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS `opening_schedule_hours`;
+DROP TABLE IF EXISTS `opening_schedules`;
@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS opening_schedules (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    valid_from DATE NOT NULL,
+    valid_to DATE NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+    CHECK (valid_from <= valid_to)
+);
+CREATE TABLE IF NOT EXISTS opening_schedule_hours (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    schedule_id INT UNSIGNED NOT NULL,
+    weekday TINYINT UNSIGNED NOT NULL,
+    opens_at TIME NOT NULL,
+    closes_at TIME NOT NULL,
+    UNIQUE KEY uq_schedule_weekday (schedule_id, weekday),
+    CONSTRAINT fk_schedule_hours_schedule FOREIGN KEY (schedule_id) REFERENCES opening_schedules(id) ON DELETE CASCADE,
+    CHECK (
+        weekday BETWEEN 1 AND 7
+    ),
+    CHECK (opens_at < closes_at)
+);
@@ -0,0 +1,5 @@
+ALTER TABLE bookings
+    DROP FOREIGN KEY fk_bookings_customer,
+    DROP COLUMN customer_id;
+DROP TABLE IF EXISTS `customer_tokens`;
+DROP TABLE IF EXISTS `customers`;
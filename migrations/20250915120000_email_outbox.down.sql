@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS `email_outbox`;
+ALTER TABLE bookings
+    DROP COLUMN locale,
+    DROP COLUMN cancelled_at,
+    DROP COLUMN status;
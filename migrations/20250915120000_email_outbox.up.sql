@@ -0,0 +1,18 @@
+ALTER TABLE bookings
+    ADD COLUMN status VARCHAR(32) NOT NULL DEFAULT 'confirmed' AFTER ends_at_utc,
+    ADD COLUMN cancelled_at DATETIME(6) NULL AFTER status,
+    ADD COLUMN locale VARCHAR(8) NOT NULL DEFAULT 'fi' AFTER customer_notes;
+CREATE TABLE IF NOT EXISTS email_outbox (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    recipient VARCHAR(255) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    attachments MEDIUMTEXT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts INT UNSIGNED NOT NULL DEFAULT 0,
+    next_attempt_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    last_error TEXT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    sent_at DATETIME(6) NULL,
+    KEY idx_email_outbox_due (status, next_attempt_at)
+);
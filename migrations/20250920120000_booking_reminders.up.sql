@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS booking_reminders (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    booking_id INT UNSIGNED NOT NULL,
+    offset_minutes INT UNSIGNED NOT NULL,
+    channel VARCHAR(8) NOT NULL,
+    sent_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    UNIQUE KEY uq_booking_reminders (booking_id, offset_minutes, channel),
+    CONSTRAINT fk_booking_reminders_booking FOREIGN KEY (booking_id) REFERENCES bookings(id) ON DELETE CASCADE
+);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS calendar_feed_tokens (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL,
+    calendar_id INT UNSIGNED NULL,
+    scope VARCHAR(16) NOT NULL,
+    token_hash CHAR(64) NOT NULL UNIQUE,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_calendar_feed_tokens_calendar FOREIGN KEY (calendar_id) REFERENCES calendars(id) ON DELETE CASCADE,
+    CONSTRAINT chk_calendar_feed_tokens_scope CHECK (scope IN ('full', 'redacted'))
+);
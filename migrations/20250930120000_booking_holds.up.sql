@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS booking_holds (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    calendar_id INT UNSIGNED NOT NULL,
+    starts_at_utc DATETIME(6) NOT NULL,
+    ends_at_utc DATETIME(6) NOT NULL,
+    token_hash CHAR(64) NOT NULL UNIQUE,
+    expires_at DATETIME(6) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    KEY idx_booking_holds_slot (calendar_id, starts_at_utc),
+    KEY idx_booking_holds_expires (expires_at),
+    CONSTRAINT fk_booking_holds_calendar FOREIGN KEY (calendar_id) REFERENCES calendars(id) ON DELETE CASCADE
+);
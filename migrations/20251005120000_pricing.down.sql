@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS pricing_rules;
+ALTER TABLE bookings DROP COLUMN price_cents;
+ALTER TABLE customers DROP COLUMN category;
+ALTER TABLE calendars DROP COLUMN table_type;
@@ -0,0 +1,22 @@
+ALTER TABLE calendars
+    ADD COLUMN table_type VARCHAR(32) NULL AFTER name;
+ALTER TABLE customers
+    ADD COLUMN category VARCHAR(32) NULL AFTER phone;
+ALTER TABLE bookings
+    ADD COLUMN price_cents INT UNSIGNED NULL AFTER locale;
+CREATE TABLE IF NOT EXISTS pricing_rules (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    name VARCHAR(255) NOT NULL,
+    calendar_id INT UNSIGNED NULL,
+    table_type VARCHAR(32) NULL,
+    weekday TINYINT UNSIGNED NULL,
+    starts_at TIME NOT NULL,
+    ends_at TIME NOT NULL,
+    customer_category VARCHAR(32) NULL,
+    hourly_rate_cents INT UNSIGNED NOT NULL,
+    priority INT NOT NULL DEFAULT 0,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_pricing_rules_calendar FOREIGN KEY (calendar_id) REFERENCES calendars(id) ON DELETE CASCADE,
+    CONSTRAINT chk_pricing_rules_weekday CHECK (weekday IS NULL OR weekday BETWEEN 1 AND 7)
+);
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS payments;
+ALTER TABLE bookings
+    DROP KEY idx_bookings_unpaid,
+    DROP COLUMN payment_expires_at;
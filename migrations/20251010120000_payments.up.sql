@@ -0,0 +1,21 @@
+ALTER TABLE bookings
+    ADD COLUMN payment_expires_at DATETIME(6) NULL AFTER cancelled_at,
+    ADD KEY idx_bookings_unpaid (status, payment_expires_at);
+CREATE TABLE IF NOT EXISTS payments (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    booking_id INT UNSIGNED NOT NULL,
+    provider VARCHAR(32) NOT NULL,
+    provider_payment_id VARCHAR(255) NOT NULL UNIQUE,
+    charge_reference VARCHAR(255) NULL,
+    amount_cents INT UNSIGNED NOT NULL,
+    currency CHAR(3) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    checkout_url TEXT NOT NULL,
+    expires_at DATETIME(6) NOT NULL,
+    refund_reference VARCHAR(255) NULL,
+    refunded_at DATETIME(6) NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+    KEY idx_payments_booking (booking_id),
+    CONSTRAINT fk_payments_booking FOREIGN KEY (booking_id) REFERENCES bookings(id) ON DELETE CASCADE
+);
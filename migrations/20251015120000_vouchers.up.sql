@@ -0,0 +1,39 @@
+ALTER TABLE bookings
+    ADD COLUMN discount_cents INT UNSIGNED NULL AFTER price_cents;
+CREATE TABLE IF NOT EXISTS vouchers (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    code VARCHAR(64) NOT NULL UNIQUE,
+    kind VARCHAR(16) NOT NULL,
+    description VARCHAR(255) NULL,
+    percent_off TINYINT UNSIGNED NULL,
+    amount_cents INT UNSIGNED NULL,
+    balance_cents INT UNSIGNED NULL,
+    valid_from DATETIME(6) NULL,
+    valid_until DATETIME(6) NULL,
+    max_uses INT UNSIGNED NULL,
+    times_used INT UNSIGNED NOT NULL DEFAULT 0,
+    calendar_id INT UNSIGNED NULL,
+    table_type VARCHAR(32) NULL,
+    weekday TINYINT UNSIGNED NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_vouchers_calendar FOREIGN KEY (calendar_id) REFERENCES calendars(id) ON DELETE CASCADE,
+    CONSTRAINT chk_vouchers_kind CHECK (
+        (kind = 'percentage' AND percent_off BETWEEN 1 AND 100)
+        OR (kind = 'fixed' AND amount_cents IS NOT NULL)
+        OR (kind = 'gift_card' AND amount_cents IS NOT NULL AND balance_cents IS NOT NULL)
+    ),
+    CONSTRAINT chk_vouchers_weekday CHECK (weekday IS NULL OR weekday BETWEEN 1 AND 7)
+);
+CREATE TABLE IF NOT EXISTS voucher_redemptions (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    voucher_id INT UNSIGNED NOT NULL,
+    booking_id INT UNSIGNED NOT NULL,
+    amount_cents INT UNSIGNED NOT NULL,
+    reversed_at DATETIME(6) NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    KEY idx_voucher_redemptions_booking (booking_id),
+    CONSTRAINT fk_voucher_redemptions_voucher FOREIGN KEY (voucher_id) REFERENCES vouchers(id),
+    CONSTRAINT fk_voucher_redemptions_booking FOREIGN KEY (booking_id) REFERENCES bookings(id) ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS booking_policies (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    calendar_id INT UNSIGNED NULL UNIQUE,
+    min_duration_minutes INT UNSIGNED NULL,
+    max_duration_minutes INT UNSIGNED NULL,
+    duration_step_minutes INT UNSIGNED NULL,
+    start_alignment_minutes INT UNSIGNED NULL,
+    min_lead_minutes INT UNSIGNED NULL,
+    max_advance_days INT UNSIGNED NULL,
+    buffer_minutes INT UNSIGNED NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_booking_policies_calendar FOREIGN KEY (calendar_id) REFERENCES calendars(id) ON DELETE CASCADE,
+    CONSTRAINT chk_booking_policies_step CHECK (duration_step_minutes IS NULL OR duration_step_minutes > 0),
+    CONSTRAINT chk_booking_policies_alignment CHECK (start_alignment_minutes IS NULL OR start_alignment_minutes > 0)
+);
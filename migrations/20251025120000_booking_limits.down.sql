@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS customer_blocks;
+ALTER TABLE bookings
+    DROP KEY idx_bookings_phone_key,
+    DROP KEY idx_bookings_email_key,
+    DROP COLUMN phone_key,
+    DROP COLUMN email_key;
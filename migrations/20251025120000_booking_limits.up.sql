@@ -0,0 +1,18 @@
+ALTER TABLE bookings
+    ADD COLUMN email_key VARCHAR(255) AS (LOWER(TRIM(customer_email))) STORED,
+    ADD COLUMN phone_key VARCHAR(64) AS (
+        REPLACE(REPLACE(REPLACE(REPLACE(REPLACE(customer_phone, ' ', ''), '-', ''), '(', ''), ')', ''), '.', '')
+    ) STORED,
+    ADD KEY idx_bookings_email_key (email_key, starts_at_utc),
+    ADD KEY idx_bookings_phone_key (phone_key, starts_at_utc);
+CREATE TABLE IF NOT EXISTS customer_blocks (
+    id INT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    kind VARCHAR(8) NOT NULL,
+    value VARCHAR(255) NOT NULL,
+    reason VARCHAR(255) NULL,
+    created_by VARCHAR(255) NOT NULL,
+    expires_at DATETIME(6) NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    UNIQUE KEY uq_customer_blocks_value (kind, value),
+    CONSTRAINT chk_customer_blocks_kind CHECK (kind IN ('email', 'phone'))
+);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGINT UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+    actor VARCHAR(255) NULL,
+    action VARCHAR(64) NOT NULL,
+    entity_type VARCHAR(64) NOT NULL,
+    entity_id VARCHAR(64) NULL,
+    ip_address VARCHAR(45) NULL,
+    before_state MEDIUMTEXT NULL,
+    after_state MEDIUMTEXT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    KEY idx_audit_log_created (created_at),
+    KEY idx_audit_log_entity (entity_type, entity_id),
+    KEY idx_audit_log_actor (actor, created_at)
+);
@@ -0,0 +1,8 @@
+ALTER TABLE bookings
+    DROP COLUMN version;
+ALTER TABLE contact_info
+    DROP COLUMN version;
+ALTER TABLE notices
+    DROP COLUMN version;
+ALTER TABLE calendars
+    DROP COLUMN version;
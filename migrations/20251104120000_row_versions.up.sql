@@ -0,0 +1,8 @@
+ALTER TABLE calendars
+    ADD COLUMN version INT UNSIGNED NOT NULL DEFAULT 1;
+ALTER TABLE notices
+    ADD COLUMN version INT UNSIGNED NOT NULL DEFAULT 1;
+ALTER TABLE contact_info
+    ADD COLUMN version INT UNSIGNED NOT NULL DEFAULT 1;
+ALTER TABLE bookings
+    ADD COLUMN version INT UNSIGNED NOT NULL DEFAULT 1;
@@ -0,0 +1,5 @@
+ALTER TABLE bookings
+    ADD KEY fk_bookings_calendar (calendar_id),
+    DROP KEY idx_bookings_calendar_start,
+    DROP KEY idx_bookings_start,
+    DROP KEY idx_bookings_created;
@@ -0,0 +1,4 @@
+ALTER TABLE bookings
+    ADD KEY idx_bookings_calendar_start (calendar_id, starts_at_utc),
+    ADD KEY idx_bookings_start (starts_at_utc),
+    ADD KEY idx_bookings_created (created_at);
@@ -0,0 +1,3 @@
+CREATE TABLE IF NOT EXISTS customer_identity_locks (
+    identity_key VARCHAR(320) PRIMARY KEY
+);
@@ -0,0 +1,3 @@
+ALTER TABLE booking_holds
+    DROP KEY idx_booking_holds_client_ip,
+    DROP COLUMN client_ip;
@@ -0,0 +1,3 @@
+ALTER TABLE booking_holds
+    ADD COLUMN client_ip VARCHAR(45) NULL,
+    ADD KEY idx_booking_holds_client_ip (client_ip, expires_at);
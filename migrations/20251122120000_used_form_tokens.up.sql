@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS used_form_tokens (
+    token_hash CHAR(64) PRIMARY KEY,
+    expires_at DATETIME(6) NOT NULL,
+    KEY idx_used_form_tokens_expires (expires_at)
+);
@@ -0,0 +1,158 @@
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    http::{header, request::Parts},
+};
+
+use crate::{
+    error::AppError,
+    features::{audit::model::AuditActor, customers::model::CustomerRow},
+    infrastructure::{client_ip::ClientIp, security::hash_token},
+    state::AppState,
+};
+
+/// Raw `Authorization: Bearer <token>` value.
+pub struct SessionToken(pub String);
+
+impl<S: Send + Sync> FromRequestParts<S> for SessionToken {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        bearer_token(parts)
+            .map(|token| SessionToken(token.to_string()))
+            .ok_or(AppError::Unauthorized("Missing bearer token"))
+    }
+}
+
+/// The logged-in customer. Use `Option<CurrentCustomer>` on routes that
+/// also serve guests.
+pub struct CurrentCustomer(pub CustomerRow);
+
+impl FromRequestParts<AppState> for CurrentCustomer {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let token = bearer_token(parts).ok_or(AppError::Unauthorized("Missing bearer token"))?;
+        let customer = state.customers.authenticate(token).await?;
+        Ok(CurrentCustomer(customer))
+    }
+}
+
+impl OptionalFromRequestParts<AppState> for CurrentCustomer {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        match bearer_token(parts) {
+            Some(token) => Ok(Some(CurrentCustomer(
+                state.customers.authenticate(token).await?,
+            ))),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Staff member authenticated with one of the `ADMIN_API_TOKENS`.
+pub struct AdminUser {
+    pub name: String,
+}
+
+impl FromRequestParts<AppState> for AdminUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let token = bearer_token(parts).ok_or(AppError::Unauthorized("Missing bearer token"))?;
+        let token_hash = hash_token(token);
+        state
+            .config
+            .admin_tokens
+            .iter()
+            .find(|admin| admin.token_hash == token_hash)
+            .map(|admin| AdminUser {
+                name: admin.name.clone(),
+            })
+            .ok_or(AppError::Unauthorized("Invalid admin token"))
+    }
+}
+
+/// Staff member or signed-in customer, for routes where customers may act
+/// on their own records.
+pub enum StaffOrCustomer {
+    Staff(AdminUser),
+    Customer(CustomerRow),
+}
+
+impl StaffOrCustomer {
+    /// Staff may access every record, customers only those with their id.
+    pub fn may_access(&self, customer_id: Option<u32>) -> bool {
+        match self {
+            StaffOrCustomer::Staff(_) => true,
+            StaffOrCustomer::Customer(customer) => customer_id == Some(customer.id),
+        }
+    }
+}
+
+impl FromRequestParts<AppState> for StaffOrCustomer {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        if let Ok(admin) =
+            <AdminUser as FromRequestParts<AppState>>::from_request_parts(parts, state).await
+        {
+            return Ok(StaffOrCustomer::Staff(admin));
+        }
+        let CurrentCustomer(customer) =
+            <CurrentCustomer as FromRequestParts<AppState>>::from_request_parts(parts, state)
+                .await?;
+        Ok(StaffOrCustomer::Customer(customer))
+    }
+}
+
+/// Never rejects: routes that need a signed-in user say so with their own
+/// extractor, this only records who it was.
+impl FromRequestParts<AppState> for AuditActor {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Ok(ClientIp(ip_address)) = ClientIp::from_request_parts(parts, state).await;
+        let name = match <AdminUser as FromRequestParts<AppState>>::from_request_parts(parts, state)
+            .await
+        {
+            Ok(admin) => Some(admin.name),
+            Err(_) => match bearer_token(parts) {
+                Some(token) => state
+                    .customers
+                    .authenticate(token)
+                    .await
+                    .ok()
+                    .map(|customer| customer.email),
+                None => None,
+            },
+        };
+        Ok(AuditActor { name, ip_address })
+    }
+}
+
+fn bearer_token(parts: &Parts) -> Option<&str> {
+    parts
+        .headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
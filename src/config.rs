@@ -1,10 +1,130 @@
+use chrono::{NaiveDate, NaiveTime};
+use chrono_tz::Tz;
 use dotenvy::dotenv;
-use std::env;
+use std::{env, net::IpAddr};
+
+use crate::infrastructure::{rate_limit::Quota, security::hash_token};
 
 #[derive(Clone, Debug)]
 pub struct AppConfig {
     pub database_url: String,
     pub port: u16,
+    /// Time zone the opening hours are expressed in.
+    pub venue_timezone: Tz,
+    /// Base URL of the public site, used in links sent by email.
+    pub frontend_url: String,
+    /// Outgoing mail server; emails are only logged when unset.
+    pub smtp: Option<SmtpConfig>,
+    pub outbox_poll_interval_secs: u64,
+    /// Minutes before a booking starts at which reminders are sent.
+    pub reminder_offsets_minutes: Vec<u32>,
+    pub reminder_poll_interval_secs: u64,
+    /// How long `POST /api/v1/bookings/holds` keeps a slot reserved.
+    pub hold_ttl_secs: u64,
+    /// Unexpired holds one client IP may have at once; 0 turns it off.
+    pub max_holds_per_ip: u32,
+    /// How long a response to a POST with an `Idempotency-Key` is replayed.
+    pub idempotency_ttl_secs: u64,
+    pub pricing_minimum_charge_cents: u32,
+    /// Booking prices are rounded to a multiple of this; 0 disables it.
+    pub pricing_rounding_cents: u32,
+    /// Bearer tokens accepted on staff-only routes.
+    pub admin_tokens: Vec<AdminToken>,
+    pub booking_limits: BookingLimitsConfig,
+    pub rate_limits: RateLimitsConfig,
+    pub bot_protection: BotProtectionConfig,
+    /// Reverse proxies whose `X-Forwarded-For` header is believed.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Last day the unversioned `/api/...` paths are served.
+    pub legacy_api_sunset: NaiveDate,
+    /// Online prepayment; bookings never require payment when unset.
+    pub payments: Option<PaymentsConfig>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AdminToken {
+    /// Who the token belongs to.
+    pub name: String,
+    pub token_hash: String,
+}
+
+/// Per-customer limits, counted by email and phone. 0 turns a limit off.
+#[derive(Clone, Debug)]
+pub struct BookingLimitsConfig {
+    pub max_upcoming_bookings: u32,
+    pub max_hours_per_day: u32,
+    /// This many no-shows within `no_show_window_days` pause online
+    /// booking for `no_show_cooldown_days` after the latest one.
+    pub no_show_limit: u32,
+    pub no_show_window_days: u32,
+    pub no_show_cooldown_days: u32,
+}
+
+/// Requests allowed per client IP; `None` turns a group's limit off.
+#[derive(Clone, Debug)]
+pub struct RateLimitsConfig {
+    /// Every route.
+    pub default: Option<Quota>,
+    /// Creating bookings and holds.
+    pub bookings: Option<Quota>,
+    /// Customer sign-up, login and account changes.
+    pub auth: Option<Quota>,
+}
+
+/// Spam checks on bookings made without an account.
+#[derive(Clone, Debug)]
+pub struct BotProtectionConfig {
+    /// Signs form tokens. Random per process when unset, which invalidates
+    /// open forms on restart and doesn't work across several instances.
+    pub form_token_secret: String,
+    /// Bookings sent sooner than this after fetching the form token are
+    /// treated as automated.
+    pub min_fill_secs: u32,
+    pub form_token_max_age_secs: u32,
+    /// Challenge widget; only the honeypot and form token are checked when
+    /// unset.
+    pub challenge: Option<ChallengeConfig>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ChallengeConfig {
+    /// `fake`, or `hcaptcha` / `turnstile` when built with the `captcha`
+    /// feature.
+    pub provider: String,
+    /// Secret key; for `fake`, the one response value that passes.
+    pub secret: String,
+    /// Overrides the provider's verification endpoint.
+    pub verify_url: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PaymentsConfig {
+    /// `fake`, or `stripe` when built with the `stripe` feature.
+    pub provider: String,
+    pub webhook_secret: String,
+    pub stripe_secret_key: Option<String>,
+    /// Days (1 = Monday … 7 = Sunday) on which a deposit is required for
+    /// bookings starting at or after `deposit_from`, venue time.
+    pub deposit_weekdays: Vec<u8>,
+    pub deposit_from: NaiveTime,
+    /// Share of the booking price taken as deposit.
+    pub deposit_percent: u32,
+    /// Unpaid bookings are released after this.
+    pub payment_timeout_minutes: u32,
+    /// Deposits are refunded when a booking is cancelled at least this
+    /// long before it starts.
+    pub refund_cutoff_hours: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    /// STARTTLS; disable for local catchers such as MailHog.
+    pub tls: bool,
 }
 
 impl AppConfig {
@@ -16,6 +136,207 @@ impl AppConfig {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(3000),
+            venue_timezone: env::var("VENUE_TIMEZONE")
+                .ok()
+                .map(|s| {
+                    s.parse()
+                        .expect("VENUE_TIMEZONE is not a valid IANA time zone")
+                })
+                .unwrap_or(chrono_tz::Europe::Helsinki),
+            frontend_url: env::var("FRONTEND_URL")
+                .unwrap_or_else(|_| "http://localhost:3000".to_string()),
+            smtp: env::var("SMTP_HOST").ok().map(|host| SmtpConfig {
+                host,
+                port: env::var("SMTP_PORT")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(587),
+                username: env::var("SMTP_USERNAME").ok(),
+                password: env::var("SMTP_PASSWORD").ok(),
+                from: env::var("SMTP_FROM").expect("SMTP_FROM not set"),
+                tls: env::var("SMTP_TLS").map(|s| s != "false").unwrap_or(true),
+            }),
+            outbox_poll_interval_secs: env::var("OUTBOX_POLL_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+            reminder_offsets_minutes: env::var("REMINDER_OFFSETS")
+                .map(|s| {
+                    s.split(',')
+                        .filter(|part| !part.trim().is_empty())
+                        .map(|part| {
+                            parse_offset(part.trim())
+                                .expect("REMINDER_OFFSETS must look like 24h,2h,30m")
+                        })
+                        .collect()
+                })
+                .unwrap_or_else(|_| vec![24 * 60, 2 * 60]),
+            reminder_poll_interval_secs: env::var("REMINDER_POLL_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(60),
+            hold_ttl_secs: env::var("HOLD_TTL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5 * 60),
+            max_holds_per_ip: env::var("MAX_HOLDS_PER_IP")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(2),
+            idempotency_ttl_secs: env::var("IDEMPOTENCY_TTL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(24 * 60 * 60),
+            pricing_minimum_charge_cents: env::var("PRICING_MINIMUM_CHARGE_CENTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            pricing_rounding_cents: env::var("PRICING_ROUNDING_CENTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            admin_tokens: env::var("ADMIN_API_TOKENS")
+                .map(|s| {
+                    s.split(',')
+                        .filter(|entry| !entry.trim().is_empty())
+                        .map(|entry| {
+                            let (name, token) = entry
+                                .trim()
+                                .split_once(':')
+                                .expect("ADMIN_API_TOKENS must look like name:token,name:token");
+                            AdminToken {
+                                name: name.to_string(),
+                                token_hash: hash_token(token),
+                            }
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            booking_limits: BookingLimitsConfig {
+                max_upcoming_bookings: env::var("MAX_UPCOMING_BOOKINGS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(3),
+                max_hours_per_day: env::var("MAX_BOOKING_HOURS_PER_DAY")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(4),
+                no_show_limit: env::var("NO_SHOW_LIMIT")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(2),
+                no_show_window_days: env::var("NO_SHOW_WINDOW_DAYS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(180),
+                no_show_cooldown_days: env::var("NO_SHOW_COOLDOWN_DAYS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(30),
+            },
+            rate_limits: RateLimitsConfig {
+                default: rate_limit_quota("RATE_LIMIT_DEFAULT", "300/min"),
+                bookings: rate_limit_quota("RATE_LIMIT_BOOKINGS", "10/min"),
+                auth: rate_limit_quota("RATE_LIMIT_AUTH", "5/min"),
+            },
+            bot_protection: BotProtectionConfig {
+                form_token_secret: env::var("FORM_TOKEN_SECRET")
+                    .expect("FORM_TOKEN_SECRET not set"),
+                min_fill_secs: env::var("FORM_MIN_FILL_SECS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(3),
+                form_token_max_age_secs: env::var("FORM_TOKEN_MAX_AGE_SECS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(2 * 60 * 60),
+                challenge: env::var("CHALLENGE_PROVIDER")
+                    .ok()
+                    .map(|provider| ChallengeConfig {
+                        provider,
+                        secret: env::var("CHALLENGE_SECRET").expect("CHALLENGE_SECRET not set"),
+                        verify_url: env::var("CHALLENGE_VERIFY_URL").ok(),
+                    }),
+            },
+            trusted_proxies: env::var("TRUSTED_PROXIES")
+                .map(|s| {
+                    s.split(',')
+                        .filter(|part| !part.trim().is_empty())
+                        .map(|part| {
+                            part.trim()
+                                .parse()
+                                .expect("TRUSTED_PROXIES must be IP addresses")
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            legacy_api_sunset: env::var("LEGACY_API_SUNSET")
+                .ok()
+                .map(|s| {
+                    NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+                        .expect("LEGACY_API_SUNSET must look like 2027-04-30")
+                })
+                .unwrap_or(NaiveDate::from_ymd_opt(2027, 4, 30).unwrap()),
+            payments: env::var("PAYMENT_PROVIDER")
+                .ok()
+                .map(|provider| PaymentsConfig {
+                    provider,
+                    webhook_secret: env::var("PAYMENT_WEBHOOK_SECRET")
+                        .expect("PAYMENT_WEBHOOK_SECRET not set"),
+                    stripe_secret_key: env::var("STRIPE_SECRET_KEY").ok(),
+                    deposit_weekdays: env::var("DEPOSIT_WEEKDAYS")
+                        .map(|s| {
+                            s.split(',')
+                                .filter(|part| !part.trim().is_empty())
+                                .map(|part| {
+                                    part.trim()
+                                        .parse()
+                                        .ok()
+                                        .filter(|day| (1..=7).contains(day))
+                                        .expect("DEPOSIT_WEEKDAYS must be numbers 1-7")
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_else(|_| vec![5, 6]),
+                    deposit_from: env::var("DEPOSIT_FROM")
+                        .ok()
+                        .map(|s| {
+                            NaiveTime::parse_from_str(&s, "%H:%M")
+                                .expect("DEPOSIT_FROM must look like 17:00")
+                        })
+                        .unwrap_or(NaiveTime::from_hms_opt(17, 0, 0).unwrap()),
+                    deposit_percent: env::var("DEPOSIT_PERCENT")
+                        .map(|s| {
+                            s.trim()
+                                .parse()
+                                .ok()
+                                .filter(|percent| (0..=100).contains(percent))
+                                .expect("DEPOSIT_PERCENT must be a number 0-100")
+                        })
+                        .unwrap_or(30),
+                    payment_timeout_minutes: env::var("PAYMENT_TIMEOUT_MINUTES")
+                        .ok()
+                        .and_then(|s| s.parse().ok())
+                        .unwrap_or(30),
+                    refund_cutoff_hours: env::var("REFUND_CUTOFF_HOURS")
+                        .ok()
+                        .and_then(|s| s.parse().ok())
+                        .unwrap_or(24),
+                }),
         }
     }
 }
+
+fn rate_limit_quota(name: &str, default: &str) -> Option<Quota> {
+    let value = env::var(name).unwrap_or_else(|_| default.to_string());
+    Quota::parse(&value).unwrap_or_else(|e| panic!("{name}: {e}"))
+}
+
+/// `24h` or `30m`, in minutes.
+fn parse_offset(s: &str) -> Option<u32> {
+    if let Some(hours) = s.strip_suffix('h') {
+        hours.parse::<u32>().ok()?.checked_mul(60)
+    } else {
+        s.strip_suffix('m')?.parse().ok()
+    }
+}
@@ -15,6 +15,27 @@ pub enum AppError {
     NotFound(&'static str),
     #[error("{0}")]
     BadRequest(&'static str),
+    #[error("{0}")]
+    Unauthorized(&'static str),
+    #[error("{0}")]
+    Forbidden(&'static str),
+    #[error("{0}")]
+    Internal(&'static str),
+    /// `If-Match` doesn't name the current version.
+    #[error("{0}")]
+    PreconditionFailed(&'static str),
+    /// An update was sent without `If-Match`.
+    #[error("{0}")]
+    PreconditionRequired(&'static str),
+    /// Refused by an anti-abuse rule; `code` tells clients which one.
+    #[error("{message}")]
+    Rejected {
+        code: &'static str,
+        message: &'static str,
+    },
+    /// Several independent validation failures, reported together.
+    #[error("Validation failed")]
+    Validation(Vec<String>),
 }
 
 impl IntoResponse for AppError {
@@ -24,6 +45,27 @@ impl IntoResponse for AppError {
             AppError::Conflict(_) => (StatusCode::CONFLICT, self.to_string()),
             AppError::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
             AppError::BadRequest(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, self.to_string()),
+            AppError::Forbidden(_) => (StatusCode::FORBIDDEN, self.to_string()),
+            AppError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+            AppError::PreconditionFailed(_) => (StatusCode::PRECONDITION_FAILED, self.to_string()),
+            AppError::PreconditionRequired(_) => {
+                (StatusCode::PRECONDITION_REQUIRED, self.to_string())
+            }
+            AppError::Rejected { code, message } => {
+                return (
+                    StatusCode::FORBIDDEN,
+                    Json(serde_json::json!({ "error": message, "code": code })),
+                )
+                    .into_response();
+            }
+            AppError::Validation(details) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": self.to_string(), "details": details })),
+                )
+                    .into_response();
+            }
         };
         (status, Json(serde_json::json!({ "error": msg }))).into_response()
     }
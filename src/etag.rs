@@ -0,0 +1,96 @@
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    http::{HeaderValue, header, request::Parts},
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+
+/// Strong ETag for a row's `version` column.
+pub fn etag(version: u32) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{version}\"")).unwrap()
+}
+
+/// Adds the `ETag` of the resource's `version` to a response.
+pub struct WithETag<T> {
+    pub version: u32,
+    pub body: T,
+}
+
+impl<T: IntoResponse> IntoResponse for WithETag<T> {
+    fn into_response(self) -> Response {
+        let mut res = self.body.into_response();
+        res.headers_mut().insert(header::ETAG, etag(self.version));
+        res
+    }
+}
+
+/// `If-Match` on PUT, PATCH and DELETE of versioned resources. Required so
+/// an edit can't silently overwrite one the client hasn't seen; requests
+/// without it get 428.
+pub struct IfMatch {
+    /// `None` for `*`. Weak or malformed tags are left out, so they never
+    /// match.
+    versions: Option<Vec<u32>>,
+}
+
+impl IfMatch {
+    /// 412 unless `version` is one of the listed ones.
+    pub fn check(&self, version: u32) -> Result<(), AppError> {
+        match &self.versions {
+            Some(versions) if !versions.contains(&version) => Err(AppError::PreconditionFailed(
+                "The resource has been changed since it was fetched",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn parse(parts: &Parts) -> Option<Result<Self, AppError>> {
+        let values: Vec<_> = parts.headers.get_all(header::IF_MATCH).iter().collect();
+        if values.is_empty() {
+            return None;
+        }
+        let mut versions = Vec::new();
+        for value in values {
+            let Ok(value) = value.to_str() else {
+                return Some(Err(AppError::BadRequest("Invalid If-Match header")));
+            };
+            for tag in value.split(',').map(str::trim) {
+                if tag == "*" {
+                    return Some(Ok(IfMatch { versions: None }));
+                }
+                if let Some(version) = tag
+                    .strip_prefix('"')
+                    .and_then(|tag| tag.strip_suffix('"'))
+                    .and_then(|tag| tag.parse().ok())
+                {
+                    versions.push(version);
+                }
+            }
+        }
+        Some(Ok(IfMatch {
+            versions: Some(versions),
+        }))
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for IfMatch {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        IfMatch::parse(parts).unwrap_or(Err(AppError::PreconditionRequired(
+            "If-Match header is required",
+        )))
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for IfMatch {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        IfMatch::parse(parts).transpose()
+    }
+}
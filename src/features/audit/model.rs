@@ -0,0 +1,48 @@
+use std::net::IpAddr;
+
+use chrono::NaiveDateTime;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AuditEntryRow {
+    pub id: u64,
+    /// Admin name, customer email, or `None` for the system.
+    pub actor: Option<String>,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: Option<String>,
+    pub ip_address: Option<String>,
+    /// JSON snapshots.
+    pub before_state: Option<String>,
+    pub after_state: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewAuditEntry {
+    pub actor: Option<String>,
+    /// Dotted name such as `booking.rejected`.
+    pub action: &'static str,
+    pub entity_type: &'static str,
+    pub entity_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+/// Who made a change and from where. Staff are named after their admin
+/// token and customers by email; `name` is `None` for anonymous requests.
+#[derive(Debug, Clone, Default)]
+pub struct AuditActor {
+    pub name: Option<String>,
+    pub ip_address: Option<IpAddr>,
+}
+
+/// Narrows `GET /api/v1/admin/audit`; unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub actor: Option<String>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+}
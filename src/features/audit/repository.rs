@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use sqlx::{MySql, Pool};
+
+use super::model::{AuditEntryRow, AuditFilter, NewAuditEntry};
+
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    async fn insert(&self, entry: &NewAuditEntry) -> sqlx::Result<()>;
+    /// Newest first. `to` is exclusive.
+    async fn list(&self, filter: &AuditFilter, limit: u32) -> sqlx::Result<Vec<AuditEntryRow>>;
+}
+
+pub type DynamicAuditRepository = std::sync::Arc<dyn AuditRepository>;
+
+#[derive(Clone)]
+pub struct MySqlAuditRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlAuditRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuditRepository for MySqlAuditRepository {
+    async fn insert(&self, entry: &NewAuditEntry) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO audit_log (
+                actor, action, entity_type, entity_id, ip_address, before_state, after_state
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            entry.actor,
+            entry.action,
+            entry.entity_type,
+            entry.entity_id,
+            entry.ip_address,
+            entry.before.as_ref().map(|value| value.to_string()),
+            entry.after.as_ref().map(|value| value.to_string())
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list(&self, filter: &AuditFilter, limit: u32) -> sqlx::Result<Vec<AuditEntryRow>> {
+        sqlx::query_as!(
+            AuditEntryRow,
+            r#"
+            SELECT
+                id, actor, action, entity_type, entity_id, ip_address,
+                before_state, after_state, created_at
+            FROM audit_log
+            WHERE (? IS NULL OR entity_type = ?)
+              AND (? IS NULL OR entity_id = ?)
+              AND (? IS NULL OR actor = ?)
+              AND (? IS NULL OR created_at >= ?)
+              AND (? IS NULL OR created_at < ?)
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+            filter.entity_type,
+            filter.entity_type,
+            filter.entity_id,
+            filter.entity_id,
+            filter.actor,
+            filter.actor,
+            filter.from,
+            filter.from,
+            filter.to,
+            filter.to,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,62 @@
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    routing::get,
+};
+use utoipa::OpenApi;
+
+use super::{
+    data_transfer_objects::{AuditEntryResponse, AuditQuery},
+    model::{AuditEntryRow, AuditFilter},
+};
+use crate::{auth::AdminUser, error::AppError, state::AppState};
+
+#[derive(OpenApi)]
+#[openapi(paths(list))]
+pub struct ApiDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/v1/admin/audit", get(list))
+}
+
+/// Newest first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit",
+    tag = "audit",
+    security(("admin" = [])),
+    params(AuditQuery),
+    responses((status = 200, body = Vec<AuditEntryResponse>))
+)]
+async fn list(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditEntryResponse>>, AppError> {
+    let filter = AuditFilter {
+        entity_type: query.entity_type,
+        entity_id: query.entity_id,
+        actor: query.actor,
+        from: query.from.map(|from| from.naive_utc()),
+        to: query.to.map(|to| to.naive_utc()),
+    };
+    let rows = state.audit.list(filter, query.limit).await?;
+    Ok(Json(rows.into_iter().map(row_to_response).collect()))
+}
+
+fn row_to_response(row: AuditEntryRow) -> AuditEntryResponse {
+    // Snapshots are written by `AuditRepository::insert` from JSON values,
+    // so they always parse.
+    let parse = |state: Option<String>| state.and_then(|s| serde_json::from_str(&s).ok());
+    AuditEntryResponse {
+        id: row.id,
+        actor: row.actor,
+        action: row.action,
+        entity_type: row.entity_type,
+        entity_id: row.entity_id,
+        ip_address: row.ip_address,
+        before: parse(row.before_state),
+        after: parse(row.after_state),
+        created_at: row.created_at.and_utc(),
+    }
+}
@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+use super::{
+    model::{AuditActor, AuditEntryRow, AuditFilter, NewAuditEntry},
+    repository::DynamicAuditRepository,
+};
+use crate::error::AppError;
+
+/// Most entries a single listing returns.
+const MAX_LIMIT: u32 = 500;
+
+#[derive(Clone)]
+pub struct AuditService {
+    repository: DynamicAuditRepository,
+}
+
+impl AuditService {
+    pub fn new(repository: DynamicAuditRepository) -> Self {
+        Self { repository }
+    }
+
+    pub async fn record(&self, entry: NewAuditEntry) -> Result<(), AppError> {
+        Ok(self.repository.insert(&entry).await?)
+    }
+
+    /// Records a change that has already been made. The request fails when
+    /// the entry can't be written, so no change goes unaccounted for.
+    pub async fn record_change(
+        &self,
+        actor: &AuditActor,
+        action: &'static str,
+        entity_type: &'static str,
+        entity_id: Option<String>,
+        before: Option<serde_json::Value>,
+        after: Option<serde_json::Value>,
+    ) -> Result<(), AppError> {
+        let entry = NewAuditEntry {
+            actor: actor.name.clone(),
+            action,
+            entity_type,
+            entity_id,
+            ip_address: actor.ip_address.map(|ip| ip.to_string()),
+            before,
+            after,
+        };
+        self.repository.insert(&entry).await.map_err(|error| {
+            tracing::error!(%error, action, "writing audit log entry failed");
+            AppError::Database(error)
+        })
+    }
+
+    pub async fn list(
+        &self,
+        filter: AuditFilter,
+        limit: Option<u32>,
+    ) -> Result<Vec<AuditEntryRow>, AppError> {
+        if let (Some(from), Some(to)) = (filter.from, filter.to)
+            && from >= to
+        {
+            return Err(AppError::BadRequest("from must be before to"));
+        }
+        let limit = limit.unwrap_or(100).clamp(1, MAX_LIMIT);
+        Ok(self.repository.list(&filter, limit).await?)
+    }
+}
+
+/// JSON snapshot of a row for the `before`/`after` of an entry.
+pub fn snapshot<T: Serialize>(value: &T) -> Option<serde_json::Value> {
+    serde_json::to_value(value).ok()
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::model::BlockKind;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateBlockRequest {
+    pub kind: BlockKind,
+    pub value: String,
+    pub reason: Option<String>,
+    /// Left out to block for good.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CustomerBlockResponse {
+    pub id: u32,
+    pub kind: BlockKind,
+    pub value: String,
+    pub reason: Option<String>,
+    pub created_by: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,176 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    MySql,
+    encode::IsNull,
+    error::BoxDynError,
+    mysql::{MySqlTypeInfo, MySqlValueRef},
+};
+use utoipa::ToSchema;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CustomerBlockRow {
+    pub id: u32,
+    pub kind: BlockKind,
+    /// Normalized with `email_key` or `phone_key`.
+    pub value: String,
+    pub reason: Option<String>,
+    /// Admin who added the block.
+    pub created_by: String,
+    /// `None` blocks for good.
+    pub expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockKind {
+    Email,
+    Phone,
+}
+
+impl BlockKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BlockKind::Email => "email",
+            BlockKind::Phone => "phone",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "email" => Some(BlockKind::Email),
+            "phone" => Some(BlockKind::Phone),
+            _ => None,
+        }
+    }
+}
+
+// Stored as VARCHAR, like `BookingStatus`.
+impl sqlx::Type<MySql> for BlockKind {
+    fn type_info() -> MySqlTypeInfo {
+        <str as sqlx::Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        <str as sqlx::Type<MySql>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, MySql> for BlockKind {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let s = <&str as sqlx::Decode<MySql>>::decode(value)?;
+        BlockKind::parse(s).ok_or_else(|| format!("unknown block kind {s:?}").into())
+    }
+}
+
+impl sqlx::Encode<'_, MySql> for BlockKind {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, BoxDynError> {
+        <&str as sqlx::Encode<MySql>>::encode(self.as_str(), buf)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewCustomerBlock {
+    pub kind: BlockKind,
+    pub value: String,
+    pub reason: Option<String>,
+    pub created_by: String,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// How bookings are counted against one person. Matches the generated
+/// `email_key` and `phone_key` columns on `bookings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomerIdentity {
+    pub email_key: String,
+    pub phone_key: String,
+}
+
+impl CustomerIdentity {
+    pub fn new(email: &str, phone: &str) -> Self {
+        Self {
+            email_key: email_key(email),
+            phone_key: phone_key(phone),
+        }
+    }
+}
+
+/// Per-identity limits, counted inside the transaction that writes the
+/// booking once the identity is locked, so two bookings made at the same
+/// time can't both get in under them. Zero means no limit.
+#[derive(Debug, Clone)]
+pub struct IdentityQuota {
+    pub identity: CustomerIdentity,
+    pub max_upcoming_bookings: u32,
+    pub max_minutes_per_day: u32,
+    /// Every venue-time day the booking touches; two when it runs past
+    /// midnight.
+    pub days: Vec<QuotaDay>,
+}
+
+/// A venue-time day, in UTC, and the booking's minutes falling on it.
+#[derive(Debug, Clone)]
+pub struct QuotaDay {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub requested_minutes: u32,
+}
+
+pub fn email_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Drops the separators people type into phone numbers.
+pub fn phone_key(phone: &str) -> String {
+    phone
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '(' | ')' | '.'))
+        .collect()
+}
+
+/// Why a booking was refused by an anti-abuse rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookingRejection {
+    Blocked,
+    TooManyUpcomingBookings,
+    DailyHoursExceeded,
+    NoShowCooldown,
+}
+
+impl BookingRejection {
+    pub fn code(self) -> &'static str {
+        match self {
+            BookingRejection::Blocked => "customer_blocked",
+            BookingRejection::TooManyUpcomingBookings => "too_many_upcoming_bookings",
+            BookingRejection::DailyHoursExceeded => "daily_hours_exceeded",
+            BookingRejection::NoShowCooldown => "no_show_cooldown",
+        }
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            BookingRejection::Blocked => "Bookings can't be made with these contact details",
+            BookingRejection::TooManyUpcomingBookings => {
+                "You already have the maximum number of upcoming bookings"
+            }
+            BookingRejection::DailyHoursExceeded => {
+                "This booking would exceed the daily limit of booked hours"
+            }
+            BookingRejection::NoShowCooldown => {
+                "Online booking is paused after repeated no-shows; please contact the venue"
+            }
+        }
+    }
+}
+
+impl From<BookingRejection> for AppError {
+    fn from(rejection: BookingRejection) -> Self {
+        AppError::Rejected {
+            code: rejection.code(),
+            message: rejection.message(),
+        }
+    }
+}
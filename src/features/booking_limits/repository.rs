@@ -0,0 +1,232 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::{MySql, MySqlConnection, Pool};
+
+use super::model::{
+    BlockKind, BookingRejection, CustomerBlockRow, CustomerIdentity, IdentityQuota,
+    NewCustomerBlock,
+};
+
+#[async_trait]
+pub trait BookingLimitsRepository: Send + Sync {
+    async fn list_blocks(&self) -> sqlx::Result<Vec<CustomerBlockRow>>;
+    async fn get_block(&self, id: u32) -> sqlx::Result<Option<CustomerBlockRow>>;
+    /// `None` when the value is already blocked.
+    async fn insert_block(&self, block: &NewCustomerBlock) -> sqlx::Result<Option<u32>>;
+    async fn delete_block(&self, id: u32) -> sqlx::Result<bool>;
+    async fn is_blocked(
+        &self,
+        identity: &CustomerIdentity,
+        now: NaiveDateTime,
+    ) -> sqlx::Result<bool>;
+    /// Start times of the identity's no-shows since `since`, latest first.
+    async fn no_shows_since(
+        &self,
+        identity: &CustomerIdentity,
+        since: NaiveDateTime,
+    ) -> sqlx::Result<Vec<NaiveDateTime>>;
+}
+
+pub type DynamicBookingLimitsRepository = std::sync::Arc<dyn BookingLimitsRepository>;
+
+#[derive(Clone)]
+pub struct MySqlBookingLimitsRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlBookingLimitsRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BookingLimitsRepository for MySqlBookingLimitsRepository {
+    async fn list_blocks(&self) -> sqlx::Result<Vec<CustomerBlockRow>> {
+        sqlx::query_as!(
+            CustomerBlockRow,
+            r#"
+            SELECT id, kind as `kind: BlockKind`, value, reason, created_by, expires_at, created_at
+            FROM customer_blocks
+            ORDER BY id DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_block(&self, id: u32) -> sqlx::Result<Option<CustomerBlockRow>> {
+        sqlx::query_as!(
+            CustomerBlockRow,
+            r#"
+            SELECT id, kind as `kind: BlockKind`, value, reason, created_by, expires_at, created_at
+            FROM customer_blocks
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn insert_block(&self, block: &NewCustomerBlock) -> sqlx::Result<Option<u32>> {
+        let result = sqlx::query!(
+            r#"INSERT IGNORE INTO customer_blocks (kind, value, reason, created_by, expires_at) VALUES (?, ?, ?, ?, ?)"#,
+            block.kind,
+            block.value,
+            block.reason,
+            block.created_by,
+            block.expires_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok((result.rows_affected() > 0).then(|| result.last_insert_id() as u32))
+    }
+
+    async fn delete_block(&self, id: u32) -> sqlx::Result<bool> {
+        let result = sqlx::query!(r#"DELETE FROM customer_blocks WHERE id = ?"#, id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn is_blocked(
+        &self,
+        identity: &CustomerIdentity,
+        now: NaiveDateTime,
+    ) -> sqlx::Result<bool> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as `count: i64`
+            FROM customer_blocks
+            WHERE ((kind = 'email' AND value = ?) OR (kind = 'phone' AND value = ?))
+              AND (expires_at IS NULL OR expires_at > ?)
+            "#,
+            identity.email_key,
+            identity.phone_key,
+            now
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    async fn no_shows_since(
+        &self,
+        identity: &CustomerIdentity,
+        since: NaiveDateTime,
+    ) -> sqlx::Result<Vec<NaiveDateTime>> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT starts_at_utc
+            FROM bookings
+            WHERE (email_key = ? OR phone_key = ?)
+              AND status = 'no_show'
+              AND starts_at_utc >= ?
+            ORDER BY starts_at_utc DESC
+            "#,
+            identity.email_key,
+            identity.phone_key,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+/// Locks the identity until the transaction ends, then tells which limit of
+/// `quota` the booking would break. `except_booking_id` is left out of the
+/// counts, for a booking being moved.
+pub async fn check_quota(
+    conn: &mut MySqlConnection,
+    quota: &IdentityQuota,
+    now: NaiveDateTime,
+    except_booking_id: Option<u32>,
+) -> sqlx::Result<Option<BookingRejection>> {
+    let identity = &quota.identity;
+    lock_identity(conn, identity).await?;
+
+    if quota.max_upcoming_bookings > 0 {
+        let upcoming = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as `count: i64`
+            FROM bookings
+            WHERE (email_key = ? OR phone_key = ?)
+              AND status IN ('confirmed', 'pending_payment')
+              AND starts_at_utc > ?
+              AND (? IS NULL OR id <> ?)
+            "#,
+            identity.email_key,
+            identity.phone_key,
+            now,
+            except_booking_id,
+            except_booking_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        if upcoming as u32 >= quota.max_upcoming_bookings {
+            return Ok(Some(BookingRejection::TooManyUpcomingBookings));
+        }
+    }
+
+    if quota.max_minutes_per_day > 0 {
+        for day in &quota.days {
+            let booked = sqlx::query_scalar!(
+                r#"
+                SELECT CAST(COALESCE(SUM(TIMESTAMPDIFF(
+                    MINUTE, GREATEST(starts_at_utc, ?), LEAST(ends_at_utc, ?)
+                )), 0) AS SIGNED) as `minutes: i64`
+                FROM bookings
+                WHERE (email_key = ? OR phone_key = ?)
+                  AND status IN ('confirmed', 'pending_payment')
+                  AND starts_at_utc < ? AND ends_at_utc > ?
+                  AND (? IS NULL OR id <> ?)
+                "#,
+                day.start,
+                day.end,
+                identity.email_key,
+                identity.phone_key,
+                day.end,
+                day.start,
+                except_booking_id,
+                except_booking_id
+            )
+            .fetch_one(&mut *conn)
+            .await?;
+            if booked as u32 + day.requested_minutes > quota.max_minutes_per_day {
+                return Ok(Some(BookingRejection::DailyHoursExceeded));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Takes a row lock per email and phone, in a fixed order so two
+/// transactions locking the same keys can't deadlock.
+async fn lock_identity(
+    conn: &mut MySqlConnection,
+    identity: &CustomerIdentity,
+) -> sqlx::Result<()> {
+    let mut keys = [
+        format!("email:{}", identity.email_key),
+        format!("phone:{}", identity.phone_key),
+    ];
+    keys.sort();
+    for key in keys {
+        lock_key(conn, &key).await?;
+    }
+    Ok(())
+}
+
+/// Row lock on `key` held until the transaction ends, for counting what
+/// one email, phone or client IP has against a limit.
+pub async fn lock_key(conn: &mut MySqlConnection, key: &str) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"INSERT INTO customer_identity_locks (identity_key) VALUES (?) ON DUPLICATE KEY UPDATE identity_key = identity_key"#,
+        key
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
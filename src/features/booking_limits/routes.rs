@@ -0,0 +1,94 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing::{delete, get},
+};
+use utoipa::OpenApi;
+
+use super::{
+    data_transfer_objects::{CreateBlockRequest, CustomerBlockResponse},
+    model::CustomerBlockRow,
+};
+use crate::{
+    auth::AdminUser,
+    error::AppError,
+    response::{Created, NoContent},
+    state::AppState,
+};
+
+#[derive(OpenApi)]
+#[openapi(paths(list, create, remove))]
+pub struct ApiDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/admin/blocklist", get(list).post(create))
+        .route("/api/v1/admin/blocklist/{id}", delete(remove))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/blocklist",
+    tag = "booking limits",
+    security(("admin" = [])),
+    responses((status = 200, body = Vec<CustomerBlockResponse>))
+)]
+async fn list(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<CustomerBlockResponse>>, AppError> {
+    let rows = state.booking_limits.list_blocks().await?;
+    Ok(Json(rows.into_iter().map(row_to_response).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/blocklist",
+    tag = "booking limits",
+    security(("admin" = [])),
+    request_body = CreateBlockRequest,
+    responses((status = 201, body = CustomerBlockResponse))
+)]
+async fn create(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Json(body): Json<CreateBlockRequest>,
+) -> Result<Created<CustomerBlockResponse>, AppError> {
+    let row = state.booking_limits.add_block(body, &admin.name).await?;
+    tracing::info!(admin = %admin.name, id = row.id, "customer blocked");
+
+    Ok(Created {
+        location: format!("/api/v1/admin/blocklist/{}", row.id),
+        body: row_to_response(row),
+    })
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/blocklist/{id}",
+    tag = "booking limits",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    responses((status = 204))
+)]
+async fn remove(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<NoContent, AppError> {
+    state.booking_limits.remove_block(id).await?;
+    tracing::info!(admin = %admin.name, id, "customer unblocked");
+    Ok(NoContent)
+}
+
+fn row_to_response(row: CustomerBlockRow) -> CustomerBlockResponse {
+    CustomerBlockResponse {
+        id: row.id,
+        kind: row.kind,
+        value: row.value,
+        reason: row.reason,
+        created_by: row.created_by,
+        expires_at: row.expires_at.map(|at| at.and_utc()),
+        created_at: row.created_at.and_utc(),
+    }
+}
@@ -0,0 +1,223 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde_json::json;
+
+use super::{
+    data_transfer_objects::CreateBlockRequest,
+    model::{
+        BlockKind, BookingRejection, CustomerBlockRow, CustomerIdentity, IdentityQuota,
+        NewCustomerBlock, QuotaDay, email_key, phone_key,
+    },
+    repository::DynamicBookingLimitsRepository,
+};
+use crate::{
+    config::BookingLimitsConfig,
+    error::AppError,
+    features::audit::{model::NewAuditEntry, service::AuditService},
+};
+
+#[derive(Clone)]
+pub struct BookingLimitsService {
+    repository: DynamicBookingLimitsRepository,
+    audit: AuditService,
+    limits: BookingLimitsConfig,
+    timezone: Tz,
+}
+
+impl BookingLimitsService {
+    pub fn new(
+        repository: DynamicBookingLimitsRepository,
+        audit: AuditService,
+        limits: BookingLimitsConfig,
+        timezone: Tz,
+    ) -> Self {
+        Self {
+            repository,
+            audit,
+            limits,
+            timezone,
+        }
+    }
+
+    /// Refuses the booking when the contact details are blocked or in a
+    /// no-show cooldown. The returned quota must then be checked by the
+    /// write, and a refusal it reports passed to `refuse`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn check(
+        &self,
+        calendar_id: u32,
+        email: &str,
+        phone: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        now: DateTime<Utc>,
+        client_ip: Option<IpAddr>,
+    ) -> Result<IdentityQuota, AppError> {
+        let identity = CustomerIdentity::new(email, phone);
+        if let Some(rejection) = self.find_rejection(&identity, now).await? {
+            return Err(self
+                .refuse(calendar_id, &identity, start, end, rejection, client_ip)
+                .await);
+        }
+        Ok(self.quota(identity, start, end))
+    }
+
+    /// Writes the refusal to the audit log and returns the error to reply
+    /// with.
+    pub async fn refuse(
+        &self,
+        calendar_id: u32,
+        identity: &CustomerIdentity,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        rejection: BookingRejection,
+        client_ip: Option<IpAddr>,
+    ) -> AppError {
+        tracing::info!(code = rejection.code(), calendar_id, "booking rejected");
+        let recorded = self
+            .audit
+            .record(NewAuditEntry {
+                actor: Some(identity.email_key.clone()),
+                action: "booking.rejected",
+                entity_type: "booking",
+                entity_id: None,
+                ip_address: client_ip.map(|ip| ip.to_string()),
+                before: None,
+                after: Some(json!({
+                    "code": rejection.code(),
+                    "calendar_id": calendar_id,
+                    "start": start,
+                    "end": end,
+                    "email": identity.email_key,
+                    "phone": identity.phone_key,
+                })),
+            })
+            .await;
+        match recorded {
+            Ok(()) => rejection.into(),
+            Err(error) => error,
+        }
+    }
+
+    pub async fn list_blocks(&self) -> Result<Vec<CustomerBlockRow>, AppError> {
+        Ok(self.repository.list_blocks().await?)
+    }
+
+    pub async fn add_block(
+        &self,
+        request: CreateBlockRequest,
+        admin: &str,
+    ) -> Result<CustomerBlockRow, AppError> {
+        let value = match request.kind {
+            BlockKind::Email => email_key(&request.value),
+            BlockKind::Phone => phone_key(&request.value),
+        };
+        if value.is_empty() {
+            return Err(AppError::BadRequest("value is required"));
+        }
+
+        let id = self
+            .repository
+            .insert_block(&NewCustomerBlock {
+                kind: request.kind,
+                value,
+                reason: request
+                    .reason
+                    .map(|r| r.trim().to_string())
+                    .filter(|r| !r.is_empty()),
+                created_by: admin.to_string(),
+                expires_at: request.expires_at.map(|at| at.naive_utc()),
+            })
+            .await?
+            .ok_or(AppError::Conflict("This value is already blocked"))?;
+        self.repository
+            .get_block(id)
+            .await?
+            .ok_or(AppError::NotFound("Failed to fetch newly created block"))
+    }
+
+    pub async fn remove_block(&self, id: u32) -> Result<(), AppError> {
+        if !self.repository.delete_block(id).await? {
+            return Err(AppError::NotFound("Block not found"));
+        }
+        Ok(())
+    }
+
+    async fn find_rejection(
+        &self,
+        identity: &CustomerIdentity,
+        now: DateTime<Utc>,
+    ) -> Result<Option<BookingRejection>, AppError> {
+        let limits = &self.limits;
+
+        if self
+            .repository
+            .is_blocked(identity, now.naive_utc())
+            .await?
+        {
+            return Ok(Some(BookingRejection::Blocked));
+        }
+
+        if limits.no_show_limit > 0 {
+            let since = now - Duration::days(limits.no_show_window_days.into());
+            let no_shows = self
+                .repository
+                .no_shows_since(identity, since.naive_utc())
+                .await?;
+            if let Some(latest) = no_shows.first()
+                && no_shows.len() >= limits.no_show_limit as usize
+                && now.naive_utc() < *latest + Duration::days(limits.no_show_cooldown_days.into())
+            {
+                return Ok(Some(BookingRejection::NoShowCooldown));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn quota(
+        &self,
+        identity: CustomerIdentity,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> IdentityQuota {
+        // Hours are counted on each venue-time day the booking touches.
+        let mut days = Vec::new();
+        let mut day = start.with_timezone(&self.timezone).date_naive();
+        loop {
+            let day_start = self.day_start(day);
+            let Some(next) = day.succ_opt() else { break };
+            let day_end = self.day_start(next);
+            days.push(QuotaDay {
+                start: day_start.naive_utc(),
+                end: day_end.naive_utc(),
+                requested_minutes: (end.min(day_end) - start.max(day_start))
+                    .num_minutes()
+                    .max(0) as u32,
+            });
+            if day_end >= end {
+                break;
+            }
+            day = next;
+        }
+        IdentityQuota {
+            identity,
+            max_upcoming_bookings: self.limits.max_upcoming_bookings,
+            max_minutes_per_day: self.limits.max_hours_per_day * 60,
+            days,
+        }
+    }
+
+    /// First instant of a venue-time day, also on days a DST change skips
+    /// midnight.
+    fn day_start(&self, day: NaiveDate) -> DateTime<Utc> {
+        let midnight = day.and_hms_opt(0, 0, 0).unwrap();
+        self.timezone
+            .from_local_datetime(&midnight)
+            .earliest()
+            .unwrap_or_else(|| self.timezone.from_utc_datetime(&midnight))
+            .with_timezone(&Utc)
+    }
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Fields left out inherit from the venue-wide policy (for a calendar) or
+/// the built-in defaults (for the venue).
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BookingPolicySettingsBody {
+    pub min_duration_minutes: Option<u32>,
+    pub max_duration_minutes: Option<u32>,
+    pub duration_step_minutes: Option<u32>,
+    pub start_alignment_minutes: Option<u32>,
+    pub min_lead_minutes: Option<u32>,
+    pub max_advance_days: Option<u32>,
+    pub buffer_minutes: Option<u32>,
+}
+
+/// Policy in effect, with the bookable window as of now for date pickers.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BookingPolicyResponse {
+    pub calendar_id: Option<u32>,
+    pub min_duration_minutes: u32,
+    pub max_duration_minutes: u32,
+    pub duration_step_minutes: u32,
+    pub start_alignment_minutes: u32,
+    pub min_lead_minutes: u32,
+    pub max_advance_days: u32,
+    pub buffer_minutes: u32,
+    pub earliest_start: DateTime<Utc>,
+    pub latest_start: DateTime<Utc>,
+}
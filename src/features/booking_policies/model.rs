@@ -0,0 +1,233 @@
+use chrono::{DateTime, Duration, Timelike, Utc};
+use chrono_tz::Tz;
+
+/// Policy fields as stored. `None` inherits from the venue-wide policy, and
+/// for that from `BookingPolicy::DEFAULT`.
+#[derive(Debug, Clone, Default, sqlx::FromRow)]
+pub struct BookingPolicySettings {
+    pub min_duration_minutes: Option<u32>,
+    pub max_duration_minutes: Option<u32>,
+    pub duration_step_minutes: Option<u32>,
+    pub start_alignment_minutes: Option<u32>,
+    pub min_lead_minutes: Option<u32>,
+    pub max_advance_days: Option<u32>,
+    pub buffer_minutes: Option<u32>,
+}
+
+/// Rules a booking's slot has to follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookingPolicy {
+    pub min_duration_minutes: u32,
+    pub max_duration_minutes: u32,
+    /// Durations are multiples of this.
+    pub duration_step_minutes: u32,
+    /// Starts fall on multiples of this from midnight, venue time.
+    pub start_alignment_minutes: u32,
+    /// How long before its start a slot can still be booked.
+    pub min_lead_minutes: u32,
+    /// How far ahead slots can be booked.
+    pub max_advance_days: u32,
+    /// Free time kept before and after every booking.
+    pub buffer_minutes: u32,
+}
+
+impl BookingPolicy {
+    pub const DEFAULT: BookingPolicy = BookingPolicy {
+        min_duration_minutes: 30,
+        max_duration_minutes: 4 * 60,
+        duration_step_minutes: 30,
+        start_alignment_minutes: 30,
+        min_lead_minutes: 15,
+        max_advance_days: 90,
+        buffer_minutes: 0,
+    };
+
+    /// Calendar settings over venue settings over the defaults.
+    pub fn resolve(
+        venue: Option<&BookingPolicySettings>,
+        calendar: Option<&BookingPolicySettings>,
+    ) -> Self {
+        let pick = |field: fn(&BookingPolicySettings) -> Option<u32>, default: u32| {
+            calendar
+                .and_then(field)
+                .or_else(|| venue.and_then(field))
+                .unwrap_or(default)
+        };
+        let default = Self::DEFAULT;
+        Self {
+            min_duration_minutes: pick(|s| s.min_duration_minutes, default.min_duration_minutes),
+            max_duration_minutes: pick(|s| s.max_duration_minutes, default.max_duration_minutes),
+            duration_step_minutes: pick(|s| s.duration_step_minutes, default.duration_step_minutes),
+            start_alignment_minutes: pick(
+                |s| s.start_alignment_minutes,
+                default.start_alignment_minutes,
+            ),
+            min_lead_minutes: pick(|s| s.min_lead_minutes, default.min_lead_minutes),
+            max_advance_days: pick(|s| s.max_advance_days, default.max_advance_days),
+            buffer_minutes: pick(|s| s.buffer_minutes, default.buffer_minutes),
+        }
+    }
+
+    pub fn earliest_start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now + Duration::minutes(self.min_lead_minutes.into())
+    }
+
+    pub fn latest_start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now + Duration::days(self.max_advance_days.into())
+    }
+
+    /// Every rule the slot breaks, as messages for the customer.
+    pub fn violations(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        now: DateTime<Utc>,
+        timezone: Tz,
+    ) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        let duration = end - start;
+        let minutes = duration.num_minutes();
+        if duration.num_seconds() % 60 != 0 {
+            violations.push("the duration must be whole minutes".to_string());
+        }
+        if minutes < self.min_duration_minutes.into() {
+            violations.push(format!(
+                "bookings must be at least {} minutes long",
+                self.min_duration_minutes
+            ));
+        }
+        if minutes > self.max_duration_minutes.into() {
+            violations.push(format!(
+                "bookings can be at most {} minutes long",
+                self.max_duration_minutes
+            ));
+        }
+        if minutes % i64::from(self.duration_step_minutes.max(1)) != 0 {
+            violations.push(format!(
+                "the duration must be a multiple of {} minutes",
+                self.duration_step_minutes
+            ));
+        }
+
+        let seconds_from_midnight = start.with_timezone(&timezone).num_seconds_from_midnight();
+        if seconds_from_midnight % (self.start_alignment_minutes.max(1) * 60) != 0 {
+            violations.push(format!(
+                "start times must be in {}-minute steps from midnight",
+                self.start_alignment_minutes
+            ));
+        }
+
+        if start < self.earliest_start(now) {
+            violations.push(format!(
+                "bookings must be made at least {} minutes in advance",
+                self.min_lead_minutes
+            ));
+        }
+        if start > self.latest_start(now) {
+            violations.push(format!(
+                "bookings can be made at most {} days in advance",
+                self.max_advance_days
+            ));
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, day, hour, minute, 0).unwrap()
+    }
+
+    fn violations(start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<String> {
+        BookingPolicy::DEFAULT.violations(start, end, at(1, 8, 0), chrono_tz::UTC)
+    }
+
+    #[test]
+    fn slot_following_every_rule_passes() {
+        assert!(violations(at(2, 10, 0), at(2, 11, 30)).is_empty());
+    }
+
+    #[test]
+    fn duration_limits_and_steps() {
+        assert_eq!(
+            violations(at(2, 10, 0), at(2, 10, 15)),
+            [
+                "bookings must be at least 30 minutes long",
+                "the duration must be a multiple of 30 minutes",
+            ]
+        );
+        assert_eq!(
+            violations(at(2, 10, 0), at(2, 14, 30)),
+            ["bookings can be at most 240 minutes long"]
+        );
+        assert_eq!(
+            violations(at(2, 10, 0), at(2, 10, 0) + Duration::seconds(30 * 60 + 20)),
+            ["the duration must be whole minutes"]
+        );
+    }
+
+    #[test]
+    fn start_is_aligned_in_venue_time() {
+        let policy = BookingPolicy {
+            start_alignment_minutes: 60,
+            ..BookingPolicy::DEFAULT
+        };
+        let (start, end) = (at(2, 10, 0), at(2, 11, 0));
+
+        assert!(
+            policy
+                .violations(start, end, at(1, 8, 0), chrono_tz::UTC)
+                .is_empty()
+        );
+        // 15:30 in Kolkata.
+        assert_eq!(
+            policy.violations(start, end, at(1, 8, 0), chrono_tz::Asia::Kolkata),
+            ["start times must be in 60-minute steps from midnight"]
+        );
+    }
+
+    #[test]
+    fn lead_time_and_advance_window() {
+        let now = at(1, 8, 0);
+
+        assert_eq!(
+            violations(at(1, 8, 0), at(1, 9, 0)),
+            ["bookings must be made at least 15 minutes in advance"]
+        );
+        assert!(violations(now + Duration::minutes(30), now + Duration::minutes(60)).is_empty());
+        let too_far = now + Duration::days(91);
+        assert_eq!(
+            violations(too_far, too_far + Duration::minutes(60)),
+            ["bookings can be made at most 90 days in advance"]
+        );
+    }
+
+    #[test]
+    fn calendar_settings_override_venue_settings_and_defaults() {
+        let venue = BookingPolicySettings {
+            min_duration_minutes: Some(60),
+            buffer_minutes: Some(10),
+            ..Default::default()
+        };
+        let calendar = BookingPolicySettings {
+            buffer_minutes: Some(15),
+            ..Default::default()
+        };
+
+        let policy = BookingPolicy::resolve(Some(&venue), Some(&calendar));
+
+        assert_eq!(policy.min_duration_minutes, 60);
+        assert_eq!(policy.buffer_minutes, 15);
+        assert_eq!(
+            policy.max_duration_minutes,
+            BookingPolicy::DEFAULT.max_duration_minutes
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use sqlx::{MySql, Pool};
+
+use super::model::BookingPolicySettings;
+
+/// Policies are keyed by calendar; `None` is the venue-wide policy.
+#[async_trait]
+pub trait BookingPoliciesRepository: Send + Sync {
+    async fn get(&self, calendar_id: Option<u32>) -> sqlx::Result<Option<BookingPolicySettings>>;
+    async fn upsert(
+        &self,
+        calendar_id: Option<u32>,
+        settings: &BookingPolicySettings,
+    ) -> sqlx::Result<()>;
+    async fn delete(&self, calendar_id: Option<u32>) -> sqlx::Result<bool>;
+}
+
+pub type DynamicBookingPoliciesRepository = std::sync::Arc<dyn BookingPoliciesRepository>;
+
+#[derive(Clone)]
+pub struct MySqlBookingPoliciesRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlBookingPoliciesRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BookingPoliciesRepository for MySqlBookingPoliciesRepository {
+    async fn get(&self, calendar_id: Option<u32>) -> sqlx::Result<Option<BookingPolicySettings>> {
+        sqlx::query_as!(
+            BookingPolicySettings,
+            r#"
+            SELECT
+                min_duration_minutes, max_duration_minutes, duration_step_minutes,
+                start_alignment_minutes, min_lead_minutes, max_advance_days, buffer_minutes
+            FROM booking_policies
+            WHERE calendar_id <=> ?
+            "#,
+            calendar_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    // The unique key doesn't stop several NULL calendar ids, so the venue
+    // row is looked up and locked instead of relying on ON DUPLICATE KEY.
+    async fn upsert(
+        &self,
+        calendar_id: Option<u32>,
+        settings: &BookingPolicySettings,
+    ) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let id = sqlx::query_scalar!(
+            r#"SELECT id FROM booking_policies WHERE calendar_id <=> ? FOR UPDATE"#,
+            calendar_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        match id {
+            Some(id) => {
+                sqlx::query!(
+                    r#"
+                    UPDATE booking_policies SET
+                        min_duration_minutes = ?, max_duration_minutes = ?,
+                        duration_step_minutes = ?, start_alignment_minutes = ?,
+                        min_lead_minutes = ?, max_advance_days = ?, buffer_minutes = ?
+                    WHERE id = ?
+                    "#,
+                    settings.min_duration_minutes,
+                    settings.max_duration_minutes,
+                    settings.duration_step_minutes,
+                    settings.start_alignment_minutes,
+                    settings.min_lead_minutes,
+                    settings.max_advance_days,
+                    settings.buffer_minutes,
+                    id
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO booking_policies (
+                        calendar_id, min_duration_minutes, max_duration_minutes,
+                        duration_step_minutes, start_alignment_minutes,
+                        min_lead_minutes, max_advance_days, buffer_minutes
+                    ) VALUES (?,?,?,?,?,?,?,?)
+                    "#,
+                    calendar_id,
+                    settings.min_duration_minutes,
+                    settings.max_duration_minutes,
+                    settings.duration_step_minutes,
+                    settings.start_alignment_minutes,
+                    settings.min_lead_minutes,
+                    settings.max_advance_days,
+                    settings.buffer_minutes
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await
+    }
+
+    async fn delete(&self, calendar_id: Option<u32>) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"DELETE FROM booking_policies WHERE calendar_id <=> ?"#,
+            calendar_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
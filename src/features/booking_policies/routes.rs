@@ -0,0 +1,206 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing::get,
+};
+use chrono::Utc;
+use utoipa::OpenApi;
+
+use super::{
+    data_transfer_objects::{BookingPolicyResponse, BookingPolicySettingsBody},
+    model::{BookingPolicy, BookingPolicySettings},
+};
+use crate::{auth::AdminUser, error::AppError, response::NoContent, state::AppState};
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    venue_policy,
+    calendar_policy,
+    venue_settings,
+    update_venue_settings,
+    calendar_settings,
+    update_calendar_settings,
+    reset_calendar_settings
+))]
+pub struct ApiDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/booking-policy", get(venue_policy))
+        .route(
+            "/api/v1/calendars/{id}/booking-policy",
+            get(calendar_policy),
+        )
+        .route(
+            "/api/v1/admin/booking-policy",
+            get(venue_settings).put(update_venue_settings),
+        )
+        .route(
+            "/api/v1/admin/calendars/{id}/booking-policy",
+            get(calendar_settings)
+                .put(update_calendar_settings)
+                .delete(reset_calendar_settings),
+        )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/booking-policy",
+    tag = "booking policies",
+    responses((status = 200, body = BookingPolicyResponse))
+)]
+async fn venue_policy(
+    State(state): State<AppState>,
+) -> Result<Json<BookingPolicyResponse>, AppError> {
+    let policy = state.booking_policies.effective(None).await?;
+    Ok(Json(policy_to_response(None, policy)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/calendars/{id}/booking-policy",
+    tag = "booking policies",
+    params(("id" = u32, Path, description = "Calendar id")),
+    responses((status = 200, body = BookingPolicyResponse))
+)]
+async fn calendar_policy(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<Json<BookingPolicyResponse>, AppError> {
+    let policy = state.booking_policies.effective(Some(id)).await?;
+    Ok(Json(policy_to_response(Some(id), policy)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/booking-policy",
+    tag = "booking policies",
+    security(("admin" = [])),
+    responses((status = 200, body = BookingPolicySettingsBody))
+)]
+async fn venue_settings(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+) -> Result<Json<BookingPolicySettingsBody>, AppError> {
+    let settings = state.booking_policies.settings(None).await?;
+    Ok(Json(settings_to_body(settings)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/booking-policy",
+    tag = "booking policies",
+    security(("admin" = [])),
+    request_body = BookingPolicySettingsBody,
+    responses((status = 200, body = BookingPolicySettingsBody))
+)]
+async fn update_venue_settings(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Json(body): Json<BookingPolicySettingsBody>,
+) -> Result<Json<BookingPolicySettingsBody>, AppError> {
+    let settings = state
+        .booking_policies
+        .update(None, body_to_settings(body))
+        .await?;
+    tracing::info!(admin = %admin.name, "venue booking policy updated");
+    Ok(Json(settings_to_body(settings)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/calendars/{id}/booking-policy",
+    tag = "booking policies",
+    security(("admin" = [])),
+    params(("id" = u32, Path, description = "Calendar id")),
+    responses((status = 200, body = BookingPolicySettingsBody))
+)]
+async fn calendar_settings(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<Json<BookingPolicySettingsBody>, AppError> {
+    let settings = state.booking_policies.settings(Some(id)).await?;
+    Ok(Json(settings_to_body(settings)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/calendars/{id}/booking-policy",
+    tag = "booking policies",
+    security(("admin" = [])),
+    params(("id" = u32, Path, description = "Calendar id")),
+    request_body = BookingPolicySettingsBody,
+    responses((status = 200, body = BookingPolicySettingsBody))
+)]
+async fn update_calendar_settings(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+    Json(body): Json<BookingPolicySettingsBody>,
+) -> Result<Json<BookingPolicySettingsBody>, AppError> {
+    let settings = state
+        .booking_policies
+        .update(Some(id), body_to_settings(body))
+        .await?;
+    tracing::info!(admin = %admin.name, calendar_id = id, "calendar booking policy updated");
+    Ok(Json(settings_to_body(settings)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/calendars/{id}/booking-policy",
+    tag = "booking policies",
+    security(("admin" = [])),
+    params(("id" = u32, Path, description = "Calendar id")),
+    responses((status = 204))
+)]
+async fn reset_calendar_settings(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<NoContent, AppError> {
+    state.booking_policies.reset(id).await?;
+    tracing::info!(admin = %admin.name, calendar_id = id, "calendar booking policy reset");
+    Ok(NoContent)
+}
+
+fn policy_to_response(calendar_id: Option<u32>, policy: BookingPolicy) -> BookingPolicyResponse {
+    let now = Utc::now();
+    BookingPolicyResponse {
+        calendar_id,
+        min_duration_minutes: policy.min_duration_minutes,
+        max_duration_minutes: policy.max_duration_minutes,
+        duration_step_minutes: policy.duration_step_minutes,
+        start_alignment_minutes: policy.start_alignment_minutes,
+        min_lead_minutes: policy.min_lead_minutes,
+        max_advance_days: policy.max_advance_days,
+        buffer_minutes: policy.buffer_minutes,
+        earliest_start: policy.earliest_start(now),
+        latest_start: policy.latest_start(now),
+    }
+}
+
+fn settings_to_body(settings: BookingPolicySettings) -> BookingPolicySettingsBody {
+    BookingPolicySettingsBody {
+        min_duration_minutes: settings.min_duration_minutes,
+        max_duration_minutes: settings.max_duration_minutes,
+        duration_step_minutes: settings.duration_step_minutes,
+        start_alignment_minutes: settings.start_alignment_minutes,
+        min_lead_minutes: settings.min_lead_minutes,
+        max_advance_days: settings.max_advance_days,
+        buffer_minutes: settings.buffer_minutes,
+    }
+}
+
+fn body_to_settings(body: BookingPolicySettingsBody) -> BookingPolicySettings {
+    BookingPolicySettings {
+        min_duration_minutes: body.min_duration_minutes,
+        max_duration_minutes: body.max_duration_minutes,
+        duration_step_minutes: body.duration_step_minutes,
+        start_alignment_minutes: body.start_alignment_minutes,
+        min_lead_minutes: body.min_lead_minutes,
+        max_advance_days: body.max_advance_days,
+        buffer_minutes: body.buffer_minutes,
+    }
+}
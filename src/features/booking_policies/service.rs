@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use super::{
+    model::{BookingPolicy, BookingPolicySettings},
+    repository::DynamicBookingPoliciesRepository,
+};
+use crate::{error::AppError, features::calendars::repository::DynamicCalendarsRepository};
+
+#[derive(Clone)]
+pub struct BookingPoliciesService {
+    repository: DynamicBookingPoliciesRepository,
+    calendars: DynamicCalendarsRepository,
+    timezone: Tz,
+}
+
+impl BookingPoliciesService {
+    pub fn new(
+        repository: DynamicBookingPoliciesRepository,
+        calendars: DynamicCalendarsRepository,
+        timezone: Tz,
+    ) -> Self {
+        Self {
+            repository,
+            calendars,
+            timezone,
+        }
+    }
+
+    /// Policy in effect for a calendar, or venue-wide for `None`.
+    pub async fn effective(&self, calendar_id: Option<u32>) -> Result<BookingPolicy, AppError> {
+        let venue = self.repository.get(None).await?;
+        let calendar = match calendar_id {
+            Some(id) => {
+                self.ensure_calendar(id).await?;
+                self.repository.get(Some(id)).await?
+            }
+            None => None,
+        };
+        Ok(BookingPolicy::resolve(venue.as_ref(), calendar.as_ref()))
+    }
+
+    /// Fails with every rule the slot breaks. Returns the policy so callers
+    /// can keep its buffer around the booking.
+    pub async fn enforce(
+        &self,
+        calendar_id: u32,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<BookingPolicy, AppError> {
+        let policy = self.effective(Some(calendar_id)).await?;
+        let violations = policy.violations(start, end, now, self.timezone);
+        if !violations.is_empty() {
+            return Err(AppError::Validation(violations));
+        }
+        Ok(policy)
+    }
+
+    /// The stored overrides, empty when there are none.
+    pub async fn settings(
+        &self,
+        calendar_id: Option<u32>,
+    ) -> Result<BookingPolicySettings, AppError> {
+        if let Some(id) = calendar_id {
+            self.ensure_calendar(id).await?;
+        }
+        Ok(self.repository.get(calendar_id).await?.unwrap_or_default())
+    }
+
+    pub async fn update(
+        &self,
+        calendar_id: Option<u32>,
+        settings: BookingPolicySettings,
+    ) -> Result<BookingPolicySettings, AppError> {
+        let mut errors = Vec::new();
+        if settings.duration_step_minutes == Some(0) {
+            errors.push("duration_step_minutes must be positive".to_string());
+        }
+        if settings.start_alignment_minutes == Some(0) {
+            errors.push("start_alignment_minutes must be positive".to_string());
+        }
+        if settings.max_duration_minutes == Some(0) {
+            errors.push("max_duration_minutes must be positive".to_string());
+        }
+        if !errors.is_empty() {
+            return Err(AppError::Validation(errors));
+        }
+
+        let resolved = match calendar_id {
+            Some(id) => {
+                self.ensure_calendar(id).await?;
+                let venue = self.repository.get(None).await?;
+                BookingPolicy::resolve(venue.as_ref(), Some(&settings))
+            }
+            None => BookingPolicy::resolve(Some(&settings), None),
+        };
+        if resolved.min_duration_minutes > resolved.max_duration_minutes {
+            return Err(AppError::BadRequest(
+                "min_duration_minutes can't exceed max_duration_minutes",
+            ));
+        }
+
+        self.repository.upsert(calendar_id, &settings).await?;
+        Ok(settings)
+    }
+
+    /// Drops a calendar's overrides so it follows the venue-wide policy.
+    pub async fn reset(&self, calendar_id: u32) -> Result<(), AppError> {
+        self.ensure_calendar(calendar_id).await?;
+        self.repository.delete(Some(calendar_id)).await?;
+        Ok(())
+    }
+
+    async fn ensure_calendar(&self, calendar_id: u32) -> Result<(), AppError> {
+        self.calendars
+            .get_by_id(calendar_id)
+            .await?
+            .ok_or(AppError::NotFound("Calendar not found"))?;
+        Ok(())
+    }
+}
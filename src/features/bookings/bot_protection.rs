@@ -0,0 +1,168 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use super::form_tokens::DynamicUsedFormTokens;
+use crate::{
+    config::BotProtectionConfig,
+    error::AppError,
+    infrastructure::{
+        challenge::{self, DynamicChallengeVerifier},
+        security::hash_token,
+    },
+};
+
+const FAILED: AppError = AppError::Rejected {
+    code: "bot_check_failed",
+    message: "The booking form could not be verified",
+};
+
+/// Spam checks for bookings made without an account: an empty honeypot
+/// field, a signed form token old enough that a person could have filled in
+/// the form, and the challenge widget when one is configured. Form tokens are
+/// bound to the client IP they were issued to and can be used once.
+#[derive(Clone)]
+pub struct BotProtection {
+    verifier: Option<DynamicChallengeVerifier>,
+    used_tokens: DynamicUsedFormTokens,
+    secret: String,
+    min_fill: Duration,
+    max_age: Duration,
+}
+
+/// The anti-spam fields of a booking form.
+pub struct FormCheck<'a> {
+    pub honeypot: Option<&'a str>,
+    pub form_token: Option<&'a str>,
+    pub challenge_response: Option<&'a str>,
+}
+
+impl BotProtection {
+    pub fn new(config: &BotProtectionConfig, used_tokens: DynamicUsedFormTokens) -> Self {
+        Self {
+            verifier: config.challenge.as_ref().map(challenge::from_config),
+            used_tokens,
+            secret: config.form_token_secret.clone(),
+            min_fill: Duration::seconds(config.min_fill_secs.into()),
+            max_age: Duration::seconds(config.form_token_max_age_secs.into()),
+        }
+    }
+
+    pub fn challenge_required(&self) -> bool {
+        self.verifier.is_some()
+    }
+
+    /// `{issued_at}.{signature}`, with `issued_at` in Unix seconds. The
+    /// signature covers `client_ip`, so the token only works from there.
+    pub fn issue_form_token(
+        &self,
+        now: DateTime<Utc>,
+        client_ip: Option<IpAddr>,
+    ) -> (String, DateTime<Utc>) {
+        let issued_at = now.timestamp();
+        let token = format!(
+            "{issued_at}.{}",
+            hex::encode(self.sign(issued_at, client_ip))
+        );
+        (token, now + self.max_age)
+    }
+
+    pub async fn check(
+        &self,
+        form: FormCheck<'_>,
+        client_ip: Option<IpAddr>,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        if form.honeypot.is_some_and(|value| !value.trim().is_empty()) {
+            tracing::info!(ip = ?client_ip, "booking rejected: honeypot filled");
+            return Err(FAILED);
+        }
+
+        let form_token = form
+            .form_token
+            .ok_or(AppError::BadRequest("Missing form token"))?;
+        let issued_at = self
+            .verify_form_token(form_token, client_ip)
+            .ok_or_else(|| {
+                tracing::info!(ip = ?client_ip, "booking rejected: invalid form token");
+                FAILED
+            })?;
+        let age = now - issued_at;
+        if age > self.max_age {
+            return Err(AppError::Rejected {
+                code: "form_token_expired",
+                message: "The booking form has expired, reload it and try again",
+            });
+        }
+        if age < self.min_fill {
+            tracing::info!(ip = ?client_ip, age_secs = age.num_seconds(), "booking rejected: form filled too fast");
+            return Err(FAILED);
+        }
+        let first_use = self
+            .used_tokens
+            .mark_used(
+                &hash_token(form_token),
+                (issued_at + self.max_age).naive_utc(),
+            )
+            .await?;
+        if !first_use {
+            tracing::info!(ip = ?client_ip, "booking rejected: form token reused");
+            return Err(FAILED);
+        }
+
+        if let Some(verifier) = &self.verifier {
+            let response = form
+                .challenge_response
+                .filter(|response| !response.is_empty())
+                .ok_or(AppError::Rejected {
+                    code: "challenge_failed",
+                    message: "Complete the challenge to book",
+                })?;
+            let passed = verifier
+                .verify(response, client_ip)
+                .await
+                .map_err(|error| {
+                    tracing::error!(%error, "verifying challenge response failed");
+                    AppError::Internal("Could not verify the challenge")
+                })?;
+            if !passed {
+                return Err(AppError::Rejected {
+                    code: "challenge_failed",
+                    message: "Complete the challenge to book",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_form_token(&self, token: &str, client_ip: Option<IpAddr>) -> Option<DateTime<Utc>> {
+        let (issued_at, signature) = token.split_once('.')?;
+        let issued_at: i64 = issued_at.parse().ok()?;
+        let signature = hex::decode(signature).ok()?;
+        self.mac(issued_at, client_ip)
+            .verify_slice(&signature)
+            .ok()?;
+        DateTime::from_timestamp(issued_at, 0)
+    }
+
+    fn sign(&self, issued_at: i64, client_ip: Option<IpAddr>) -> Vec<u8> {
+        self.mac(issued_at, client_ip)
+            .finalize()
+            .into_bytes()
+            .to_vec()
+    }
+
+    fn mac(&self, issued_at: i64, client_ip: Option<IpAddr>) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(b"booking-form.");
+        mac.update(issued_at.to_string().as_bytes());
+        mac.update(b".");
+        if let Some(ip) = client_ip {
+            mac.update(ip.to_string().as_bytes());
+        }
+        mac
+    }
+}
@@ -1,7 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Serialize)]
+use super::model::BookingStatus;
+use crate::features::payments::data_transfer_objects::PaymentResponse;
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct BookingResponse {
     pub id: u32,
     pub calendar_id: u32,
@@ -11,15 +15,138 @@ pub struct BookingResponse {
     pub notes: Option<String>,
     pub start: DateTime<Utc>,
     pub end: DateTime<Utc>,
+    pub status: BookingStatus,
+    pub price_cents: Option<u32>,
+    pub discount_cents: Option<u32>,
+    /// Deposit to pay, only on a newly created `pending_payment` booking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment: Option<PaymentResponse>,
 }
 
-#[derive(Debug, Deserialize)]
+/// `name`, `email` and `phone` may be left out when logged in; they default
+/// to the customer's account details.
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateBookingRequest {
     pub calendar_id: u32,
-    pub name: String,
-    pub email: String,
-    pub phone: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
     pub notes: Option<String>,
     pub start: DateTime<Utc>,
     pub end: DateTime<Utc>,
+    pub locale: Option<String>, // "fi" (default) or "en", used for emails
+    /// Token from `POST /api/v1/bookings/holds` for the same slot.
+    pub hold_token: Option<String>,
+    /// Discount code or gift card, redeemed with the booking.
+    pub voucher_code: Option<String>,
+    /// From `GET /api/v1/bookings/form-token`; required without an account,
+    /// unless booking with `hold_token`.
+    pub form_token: Option<String>,
+    /// Challenge widget response, when the challenge is enabled.
+    pub challenge_response: Option<String>,
+    /// Honeypot; hidden from people, so only bots fill it in.
+    pub website: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FormTokenResponse {
+    pub token: String,
+    /// The booking or hold must be sent before this.
+    pub expires_at: DateTime<Utc>,
+    /// Earliest the booking or hold can be sent after fetching the token.
+    pub min_fill_secs: u32,
+    pub challenge_required: bool,
+}
+
+/// Without an account the form token and challenge are checked here
+/// instead of on the booking made with the hold.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateHoldRequest {
+    pub calendar_id: u32,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// From `GET /api/v1/bookings/form-token`; required without an account.
+    pub form_token: Option<String>,
+    /// Challenge widget response, when the challenge is enabled.
+    pub challenge_response: Option<String>,
+    /// Honeypot; hidden from people, so only bots fill it in.
+    pub website: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HoldResponse {
+    pub token: String,
+    pub calendar_id: u32,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RescheduleBookingRequest {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Query of `GET /api/v1/bookings`. Lists are comma-separated.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BookingSearchQuery {
+    /// One calendar id or several, e.g. `1,2`.
+    pub calendar_id: Option<String>,
+    /// Bookings overlapping `from`..`to`.
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// e.g. `confirmed,pending_payment`.
+    pub status: Option<String>,
+    /// Part of the customer's name or email.
+    pub customer: Option<String>,
+    pub created_from: Option<DateTime<Utc>>,
+    /// Exclusive.
+    pub created_to: Option<DateTime<Utc>>,
+    /// `starts_at` (default) or `created_at`; `-starts_at` for descending.
+    pub sort: Option<String>,
+    /// `next_cursor` of the previous page, sent with the same filters and sort.
+    pub cursor: Option<String>,
+    /// Page size, 50 by default and at most 200.
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BookingPageResponse {
+    pub items: Vec<BookingResponse>,
+    /// Fetches the next page; `null` on the last one.
+    pub next_cursor: Option<String>,
+}
+
+/// Query of `GET /api/v1/calendars/{id}/occupancy`, at most 62 days.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OccupancyQuery {
+    /// Now by default.
+    pub from: Option<DateTime<Utc>>,
+    /// A week after `from` by default.
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Public view of a calendar's bookings: only when it is taken, never by
+/// whom, so it must not grow any booking or customer fields.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OccupancyResponse {
+    pub calendar_id: u32,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub busy: Vec<BusyIntervalResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BusyIntervalResponse {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MyBookingsResponse {
+    pub upcoming: Vec<BookingResponse>,
+    pub past: Vec<BookingResponse>,
 }
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use sqlx::{MySql, Pool};
+
+/// Form tokens that were already spent, kept until they expire so each one
+/// books at most once.
+#[async_trait]
+pub trait UsedFormTokens: Send + Sync {
+    /// Records `token_hash` as used. Returns false when it already was.
+    async fn mark_used(&self, token_hash: &str, expires_at: NaiveDateTime) -> sqlx::Result<bool>;
+    async fn delete_expired(&self, now: NaiveDateTime) -> sqlx::Result<u64>;
+}
+
+pub type DynamicUsedFormTokens = std::sync::Arc<dyn UsedFormTokens>;
+
+#[derive(Clone)]
+pub struct MySqlUsedFormTokens {
+    pool: Pool<MySql>,
+}
+
+impl MySqlUsedFormTokens {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UsedFormTokens for MySqlUsedFormTokens {
+    async fn mark_used(&self, token_hash: &str, expires_at: NaiveDateTime) -> sqlx::Result<bool> {
+        let inserted = sqlx::query!(
+            "INSERT IGNORE INTO used_form_tokens (token_hash, expires_at) VALUES (?, ?)",
+            token_hash,
+            expires_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(inserted.rows_affected() > 0)
+    }
+
+    async fn delete_expired(&self, now: NaiveDateTime) -> sqlx::Result<u64> {
+        let result = sqlx::query!("DELETE FROM used_form_tokens WHERE expires_at <= ?", now)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// Forgets used tokens once they have expired, every `interval`. Expired
+/// tokens are refused on their age alone.
+pub fn spawn_sweeper(
+    store: DynamicUsedFormTokens,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            match store.delete_expired(Utc::now().naive_utc()).await {
+                Ok(0) => {}
+                Ok(count) => tracing::debug!(count, "expired form tokens removed"),
+                Err(error) => tracing::error!(%error, "sweeping used form tokens failed"),
+            }
+        }
+    })
+}
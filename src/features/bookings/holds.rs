@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use chrono::Utc;
+
+use super::service::BookingsService;
+
+/// Removes expired holds every `interval`.
+pub fn spawn_sweeper(service: BookingsService, interval: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            match service.expire_holds(Utc::now()).await {
+                Ok(0) => {}
+                Ok(count) => tracing::debug!(count, "expired booking holds removed"),
+                Err(error) => tracing::error!(%error, "sweeping booking holds failed"),
+            }
+        }
+    })
+}
@@ -0,0 +1,108 @@
+use chrono::NaiveDateTime;
+
+/// One VEVENT; times are UTC.
+pub struct IcsEvent {
+    pub uid: String,
+    pub sequence: u32,
+    pub stamp: NaiveDateTime,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub summary: String,
+    pub description: Option<String>,
+    pub status: EventStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventStatus {
+    Confirmed,
+    /// Not certain to take place, e.g. while a deposit is unpaid.
+    Tentative,
+    Cancelled,
+}
+
+impl EventStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            EventStatus::Confirmed => "CONFIRMED",
+            EventStatus::Tentative => "TENTATIVE",
+            EventStatus::Cancelled => "CANCELLED",
+        }
+    }
+}
+
+/// Stable across edits and cancellations so calendar clients update the
+/// existing entry instead of adding a new one.
+pub fn booking_uid(booking_id: u32) -> String {
+    format!("booking-{booking_id}@kukkilan-biljardi")
+}
+
+/// Every change to a booking bumps `updated_at`, so its timestamp makes a
+/// SEQUENCE that only grows and lets clients replace older copies.
+pub fn booking_sequence(updated_at: NaiveDateTime) -> u32 {
+    updated_at.and_utc().timestamp().try_into().unwrap_or(0)
+}
+
+/// Renders an RFC 5545 VCALENDAR. It carries no iTIP METHOD, as bookings
+/// have no organizer to reply to: clients import the events and recognise
+/// later copies by UID and SEQUENCE.
+pub fn render_calendar(name: Option<&str>, events: &[IcsEvent]) -> String {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//Kukkilan Biljardi//Bookings//FI");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    if let Some(name) = name {
+        push_line(&mut out, &format!("X-WR-CALNAME:{}", escape_text(name)));
+    }
+
+    for event in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", event.uid));
+        push_line(&mut out, &format!("SEQUENCE:{}", event.sequence));
+        push_line(&mut out, &format!("DTSTAMP:{}", format_utc(event.stamp)));
+        push_line(&mut out, &format!("DTSTART:{}", format_utc(event.start)));
+        push_line(&mut out, &format!("DTEND:{}", format_utc(event.end)));
+        push_line(
+            &mut out,
+            &format!("SUMMARY:{}", escape_text(&event.summary)),
+        );
+        if let Some(description) = &event.description {
+            push_line(
+                &mut out,
+                &format!("DESCRIPTION:{}", escape_text(description)),
+            );
+        }
+        push_line(&mut out, &format!("STATUS:{}", event.status.as_str()));
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn format_utc(t: NaiveDateTime) -> String {
+    t.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Content lines are folded at 75 octets (RFC 5545 §3.1) without splitting
+/// a UTF-8 character, and end in CRLF.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
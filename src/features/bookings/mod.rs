@@ -1,7 +1,11 @@
+pub mod bot_protection;
 pub mod data_transfer_objects;
+pub mod form_tokens;
+pub mod holds;
+pub mod ics;
 pub mod model;
 pub mod repository;
 pub mod routes;
 pub mod service;
 
-pub use routes::routes;
+pub use routes::{ApiDoc, routes};
@@ -1,16 +1,341 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime};
 use serde::{Deserialize, Serialize};
+use sqlx::{
+    MySql,
+    encode::IsNull,
+    error::BoxDynError,
+    mysql::{MySqlTypeInfo, MySqlValueRef},
+};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::{
+    error::AppError,
+    features::{
+        booking_limits::model::{BookingRejection, IdentityQuota},
+        vouchers::model::AppliedVoucher,
+    },
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct BookingRow {
     pub id: u32,
     pub calendar_id: u32,
+    pub customer_id: Option<u32>,
     pub starts_at_utc: NaiveDateTime,
     pub ends_at_utc: NaiveDateTime,
+    pub status: BookingStatus,
+    pub cancelled_at: Option<NaiveDateTime>,
     pub customer_name: String,
     pub customer_email: String,
     pub customer_phone: String,
     pub customer_notes: Option<String>,
+    pub locale: String,
+    /// Computed when the booking was made; `None` when no pricing rule
+    /// covered the slot.
+    pub price_cents: Option<u32>,
+    /// Taken off `price_cents` by a voucher.
+    pub discount_cents: Option<u32>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// Bumped by every update; the booking's ETag.
+    pub version: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BookingStatus {
+    /// Holds the slot until the deposit is paid or the payment times out.
+    PendingPayment,
+    Confirmed,
+    Cancelled,
+    /// Marked by staff when the customer didn't turn up.
+    NoShow,
+}
+
+impl BookingStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BookingStatus::PendingPayment => "pending_payment",
+            BookingStatus::Confirmed => "confirmed",
+            BookingStatus::Cancelled => "cancelled",
+            BookingStatus::NoShow => "no_show",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending_payment" => Some(BookingStatus::PendingPayment),
+            "confirmed" => Some(BookingStatus::Confirmed),
+            "cancelled" => Some(BookingStatus::Cancelled),
+            "no_show" => Some(BookingStatus::NoShow),
+            _ => None,
+        }
+    }
+}
+
+// Stored as VARCHAR, so it goes through `str` on the way in and out.
+impl sqlx::Type<MySql> for BookingStatus {
+    fn type_info() -> MySqlTypeInfo {
+        <str as sqlx::Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        <str as sqlx::Type<MySql>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, MySql> for BookingStatus {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let s = <&str as sqlx::Decode<MySql>>::decode(value)?;
+        BookingStatus::parse(s).ok_or_else(|| format!("unknown booking status {s:?}").into())
+    }
+}
+
+impl sqlx::Encode<'_, MySql> for BookingStatus {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, BoxDynError> {
+        <&str as sqlx::Encode<MySql>>::encode(self.as_str(), buf)
+    }
+}
+
+/// Narrows the booking search; empty and unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct BookingFilter {
+    pub calendar_ids: Vec<u32>,
+    /// Bookings overlapping `from`..`to`.
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+    pub statuses: Vec<BookingStatus>,
+    /// Part of the customer's name or email.
+    pub customer: Option<String>,
+    /// `created_to` is exclusive.
+    pub created_from: Option<NaiveDateTime>,
+    pub created_to: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BookingSortKey {
+    #[default]
+    StartsAt,
+    CreatedAt,
+}
+
+/// Ties are broken by id, so every booking has one place in the order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BookingSort {
+    pub key: BookingSortKey,
+    pub descending: bool,
+}
+
+impl BookingSort {
+    /// `starts_at` or `created_at`, prefixed with `-` for descending.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (descending, key) = match s.strip_prefix('-') {
+            Some(key) => (true, key),
+            None => (false, s),
+        };
+        let key = match key {
+            "starts_at" => BookingSortKey::StartsAt,
+            "created_at" => BookingSortKey::CreatedAt,
+            _ => return None,
+        };
+        Some(Self { key, descending })
+    }
+
+    pub fn column(self) -> &'static str {
+        match self.key {
+            BookingSortKey::StartsAt => "starts_at_utc",
+            BookingSortKey::CreatedAt => "created_at",
+        }
+    }
+}
+
+/// Position after the last booking of a page: its sort value and id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookingCursor {
+    pub sort: BookingSort,
+    pub value: NaiveDateTime,
+    pub id: u32,
+}
+
+impl BookingCursor {
+    pub fn after(sort: BookingSort, row: &BookingRow) -> Self {
+        let value = match sort.key {
+            BookingSortKey::StartsAt => row.starts_at_utc,
+            BookingSortKey::CreatedAt => row.created_at,
+        };
+        Self {
+            sort,
+            value,
+            id: row.id,
+        }
+    }
+
+    /// Opaque to clients; they only send it back.
+    pub fn encode(&self) -> String {
+        let key = match self.sort.key {
+            BookingSortKey::StartsAt => 's',
+            BookingSortKey::CreatedAt => 'c',
+        };
+        let direction = if self.sort.descending { '-' } else { '+' };
+        hex::encode(format!(
+            "{key}{direction}{}.{}",
+            self.value.and_utc().timestamp_micros(),
+            self.id
+        ))
+    }
+
+    pub fn decode(s: &str) -> Option<Self> {
+        let decoded = String::from_utf8(hex::decode(s).ok()?).ok()?;
+        let mut chars = decoded.chars();
+        let key = match chars.next()? {
+            's' => BookingSortKey::StartsAt,
+            'c' => BookingSortKey::CreatedAt,
+            _ => return None,
+        };
+        let descending = match chars.next()? {
+            '-' => true,
+            '+' => false,
+            _ => return None,
+        };
+        let (micros, id) = chars.as_str().split_once('.')?;
+        Some(Self {
+            sort: BookingSort { key, descending },
+            value: DateTime::from_timestamp_micros(micros.parse().ok()?)?.naive_utc(),
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+/// Booking ready to be inserted; customer details are already resolved from
+/// the request and, when logged in, the customer's account.
+#[derive(Debug, Clone)]
+pub struct NewBooking {
+    pub calendar_id: u32,
+    pub customer_id: Option<u32>,
+    pub starts_at_utc: NaiveDateTime,
+    pub ends_at_utc: NaiveDateTime,
+    pub customer_name: String,
+    pub customer_email: String,
+    pub customer_phone: String,
+    pub customer_notes: Option<String>,
+    pub locale: String,
+    pub price_cents: Option<u32>,
+    /// Redeemed together with the insert.
+    pub voucher: Option<AppliedVoucher>,
+    /// Free time required around the booking, from the booking policy.
+    pub buffer_minutes: u32,
+    /// Checked together with the insert.
+    pub quota: IdentityQuota,
+    pub status: BookingStatus,
+    /// Set for `PendingPayment` bookings.
+    pub payment_expires_at: Option<NaiveDateTime>,
+}
+
+/// Time a calendar is taken by a booking or a hold, without whose it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusyInterval {
+    pub starts_at_utc: NaiveDateTime,
+    pub ends_at_utc: NaiveDateTime,
+}
+
+/// Slot reserved while the customer fills in the booking form.
+#[derive(Debug, Clone, Serialize)]
+pub struct BookingHoldRow {
+    pub calendar_id: u32,
+    pub starts_at_utc: NaiveDateTime,
+    pub ends_at_utc: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+}
+
+/// Why a write that takes a slot did not go through.
+#[derive(Debug, Error)]
+pub enum SlotError {
+    #[error("slot is already taken")]
+    Taken,
+    #[error("hold is missing, expired or for another slot")]
+    InvalidHold,
+    #[error("voucher was used up before the booking was written")]
+    VoucherUnavailable,
+    /// Over a per-customer limit once counted in the transaction.
+    #[error("refused by a per-customer limit")]
+    Rejected(BookingRejection),
+    #[error("client already holds the maximum number of slots")]
+    TooManyHolds,
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+impl From<SlotError> for AppError {
+    fn from(error: SlotError) -> Self {
+        match error {
+            SlotError::Taken => AppError::Conflict("The slot is already booked or held"),
+            SlotError::InvalidHold => {
+                AppError::Conflict("The hold has expired or does not match the booking")
+            }
+            SlotError::VoucherUnavailable => AppError::Conflict("The voucher has been used up"),
+            SlotError::Rejected(rejection) => rejection.into(),
+            SlotError::TooManyHolds => AppError::Rejected {
+                code: "too_many_holds",
+                message: "Too many slots are held at once; book or release one first",
+            },
+            SlotError::Database(error) => AppError::Database(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn cursor(key: BookingSortKey, descending: bool) -> BookingCursor {
+        BookingCursor {
+            sort: BookingSort { key, descending },
+            value: NaiveDate::from_ymd_opt(2025, 6, 2)
+                .unwrap()
+                .and_hms_micro_opt(10, 30, 15, 123_456)
+                .unwrap(),
+            id: 42,
+        }
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        for cursor in [
+            cursor(BookingSortKey::StartsAt, false),
+            cursor(BookingSortKey::StartsAt, true),
+            cursor(BookingSortKey::CreatedAt, false),
+            cursor(BookingSortKey::CreatedAt, true),
+        ] {
+            assert_eq!(BookingCursor::decode(&cursor.encode()), Some(cursor));
+        }
+    }
+
+    #[test]
+    fn cursor_before_1970_round_trips() {
+        let cursor = BookingCursor {
+            value: NaiveDate::from_ymd_opt(1969, 12, 31)
+                .unwrap()
+                .and_hms_opt(23, 59, 59)
+                .unwrap(),
+            ..cursor(BookingSortKey::StartsAt, false)
+        };
+
+        assert_eq!(BookingCursor::decode(&cursor.encode()), Some(cursor));
+    }
+
+    #[test]
+    fn malformed_cursors_are_rejected() {
+        let encode = |s: &str| hex::encode(s);
+
+        assert_eq!(BookingCursor::decode("not hex"), None);
+        assert_eq!(BookingCursor::decode(&encode("x+0.1")), None);
+        assert_eq!(BookingCursor::decode(&encode("s*0.1")), None);
+        assert_eq!(BookingCursor::decode(&encode("s+0")), None);
+        assert_eq!(BookingCursor::decode(&encode("s+abc.1")), None);
+        assert_eq!(BookingCursor::decode(&encode("s+0.-1")), None);
+        assert_eq!(BookingCursor::decode(&encode("")), None);
+    }
 }
@@ -1,15 +1,124 @@
-use crate::features::bookings::data_transfer_objects::CreateBookingRequest;
-
-use super::model::BookingRow;
+use super::model::{
+    BookingCursor, BookingFilter, BookingHoldRow, BookingRow, BookingSort, BookingStatus,
+    BusyInterval, NewBooking, SlotError,
+};
+use crate::{
+    features::{
+        booking_limits::{
+            model::IdentityQuota,
+            repository::{check_quota, lock_key},
+        },
+        notifications::repository::enqueue,
+        vouchers::repository::{redeem, reverse_redemptions},
+    },
+    infrastructure::mailer::EmailMessage,
+};
 use async_trait::async_trait;
-use sqlx::{MySql, Pool};
+use chrono::{Duration, NaiveDateTime};
+use sqlx::{MySql, MySqlConnection, Pool, QueryBuilder};
+use std::net::IpAddr;
+
+/// Builds the email queued together with a booking write, from the row as
+/// stored. Writes that take `Option<BookingEmail>` send nothing for `None`.
+pub type BookingEmail<'a> = Box<dyn FnOnce(&BookingRow) -> EmailMessage + Send + 'a>;
 
 #[async_trait]
 pub trait BookingsRepository: Send + Sync {
+    /// Bookings of a calendar that still hold their slot.
     async fn list(&self, calendar_id: u32) -> sqlx::Result<Vec<BookingRow>>;
+    async fn list_by_customer(&self, customer_id: u32) -> sqlx::Result<Vec<BookingRow>>;
+    /// Active bookings and unexpired holds of a calendar overlapping
+    /// `from`..`to`, by start time.
+    async fn busy_intervals(
+        &self,
+        calendar_id: u32,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        now: NaiveDateTime,
+    ) -> sqlx::Result<Vec<BusyInterval>>;
+    /// Up to `limit` bookings matching `filter` in `sort` order, starting
+    /// after `cursor`.
+    async fn search(
+        &self,
+        filter: &BookingFilter,
+        sort: BookingSort,
+        cursor: Option<&BookingCursor>,
+        limit: u32,
+    ) -> sqlx::Result<Vec<BookingRow>>;
+    /// Bookings ending after `since`, cancelled ones included, for one
+    /// calendar or all of them.
+    async fn list_for_feed(
+        &self,
+        calendar_id: Option<u32>,
+        since: NaiveDateTime,
+    ) -> sqlx::Result<Vec<BookingRow>>;
     async fn get(&self, id: u32) -> sqlx::Result<Option<BookingRow>>;
-    async fn insert(&self, data: CreateBookingRequest) -> sqlx::Result<u32>;
-    async fn delete(&self, id: u32) -> sqlx::Result<bool>;
+    /// Inserts the booking and queues its email in one transaction. With a
+    /// hold token the hold must cover the booking and is used up; it is the
+    /// only hold allowed to overlap.
+    async fn insert(
+        &self,
+        data: &NewBooking,
+        hold_token_hash: Option<&str>,
+        now: NaiveDateTime,
+        email: Option<BookingEmail<'_>>,
+    ) -> Result<BookingRow, SlotError>;
+    /// Cancels an active booking, reverses its voucher redemptions and
+    /// queues its email in one transaction. `None` when there is no active
+    /// booking with that id, or it is no longer at `expected_version`.
+    async fn cancel(
+        &self,
+        id: u32,
+        expected_version: Option<u32>,
+        email: Option<BookingEmail<'_>>,
+    ) -> sqlx::Result<Option<BookingRow>>;
+    /// Confirms a booking waiting for payment and queues its email in one
+    /// transaction. `None` when it is no longer waiting.
+    async fn confirm_payment(
+        &self,
+        id: u32,
+        email: BookingEmail<'_>,
+    ) -> sqlx::Result<Option<BookingRow>>;
+    /// Marks a confirmed booking that has started as a no-show. `None` when
+    /// there is no such booking.
+    async fn mark_no_show(&self, id: u32, now: NaiveDateTime) -> sqlx::Result<Option<BookingRow>>;
+    /// Bookings whose payment deadline has passed.
+    async fn list_unpaid_expired(&self, now: NaiveDateTime) -> sqlx::Result<Vec<BookingRow>>;
+    /// Moves an active booking, replacing its price, and queues its email in
+    /// one transaction. The customer's other bookings must leave room for it
+    /// under `quota`.
+    /// `None` when there is no active booking with that id, or it is no
+    /// longer at `expected_version`.
+    async fn reschedule(
+        &self,
+        id: u32,
+        starts_at_utc: NaiveDateTime,
+        ends_at_utc: NaiveDateTime,
+        price_cents: Option<u32>,
+        buffer_minutes: u32,
+        quota: &IdentityQuota,
+        now: NaiveDateTime,
+        expected_version: Option<u32>,
+        email: BookingEmail<'_>,
+    ) -> Result<Option<BookingRow>, SlotError>;
+    /// Refused with `TooManyHolds` when `client_ip` already has
+    /// `max_per_client` unexpired holds; 0 allows any number.
+    async fn insert_hold(
+        &self,
+        calendar_id: u32,
+        starts_at_utc: NaiveDateTime,
+        ends_at_utc: NaiveDateTime,
+        token_hash: &str,
+        buffer_minutes: u32,
+        client_ip: Option<IpAddr>,
+        max_per_client: u32,
+        now: NaiveDateTime,
+        expires_at: NaiveDateTime,
+    ) -> Result<BookingHoldRow, SlotError>;
+    /// Deletes a hold, returning it when it existed.
+    async fn delete_hold(&self, token_hash: &str) -> sqlx::Result<Option<BookingHoldRow>>;
+    /// Deletes holds expired by `now` and returns them.
+    async fn delete_expired_holds(&self, now: NaiveDateTime) -> sqlx::Result<Vec<BookingHoldRow>>;
 }
 
 pub type DynamicBookingsRepository = std::sync::Arc<dyn BookingsRepository>;
@@ -27,92 +136,686 @@ impl MySqlBookingsRepository {
 #[async_trait]
 impl BookingsRepository for MySqlBookingsRepository {
     async fn list(&self, calendar_id: u32) -> sqlx::Result<Vec<BookingRow>> {
-        let rows = sqlx::query!(
+        sqlx::query_as!(
+            BookingRow,
             r#"
             SELECT
-                id, calendar_id, starts_at_utc, ends_at_utc,
-                customer_name, customer_email, customer_phone, customer_notes,
-                created_at, updated_at
+                id, calendar_id, customer_id, starts_at_utc, ends_at_utc,
+                status as `status: BookingStatus`, cancelled_at,
+                customer_name, customer_email, customer_phone, customer_notes, locale, price_cents,
+                discount_cents, created_at, updated_at, version
             FROM bookings
-            WHERE calendar_id = ?
+            WHERE calendar_id = ? AND status <> 'cancelled'
             ORDER BY starts_at_utc
             "#,
             calendar_id
         )
         .fetch_all(&self.pool)
-        .await?;
+        .await
+    }
+
+    async fn busy_intervals(
+        &self,
+        calendar_id: u32,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        now: NaiveDateTime,
+    ) -> sqlx::Result<Vec<BusyInterval>> {
+        sqlx::query_as!(
+            BusyInterval,
+            r#"
+            SELECT starts_at_utc, ends_at_utc
+            FROM bookings
+            WHERE calendar_id = ? AND status <> 'cancelled'
+              AND starts_at_utc < ? AND ends_at_utc > ?
+            UNION ALL
+            SELECT starts_at_utc, ends_at_utc
+            FROM booking_holds
+            WHERE calendar_id = ? AND expires_at > ?
+              AND starts_at_utc < ? AND ends_at_utc > ?
+            ORDER BY starts_at_utc
+            "#,
+            calendar_id,
+            to,
+            from,
+            calendar_id,
+            now,
+            to,
+            from
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
 
-        Ok(rows
-            .into_iter()
-            .map(|row| BookingRow {
-                id: row.id,
-                calendar_id: row.calendar_id,
-                starts_at_utc: row.starts_at_utc.clone(),
-                ends_at_utc: row.ends_at_utc.clone(),
-                customer_name: row.customer_name,
-                customer_email: row.customer_email,
-                customer_phone: row.customer_phone,
-                customer_notes: row.customer_notes,
-                created_at: row.created_at.clone(),
-                updated_at: row.updated_at.clone(),
-            })
-            .collect())
+    async fn list_by_customer(&self, customer_id: u32) -> sqlx::Result<Vec<BookingRow>> {
+        sqlx::query_as!(
+            BookingRow,
+            r#"
+            SELECT
+                id, calendar_id, customer_id, starts_at_utc, ends_at_utc,
+                status as `status: BookingStatus`, cancelled_at,
+                customer_name, customer_email, customer_phone, customer_notes, locale, price_cents,
+                discount_cents, created_at, updated_at, version
+            FROM bookings
+            WHERE customer_id = ?
+            ORDER BY starts_at_utc
+            "#,
+            customer_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn search(
+        &self,
+        filter: &BookingFilter,
+        sort: BookingSort,
+        cursor: Option<&BookingCursor>,
+        limit: u32,
+    ) -> sqlx::Result<Vec<BookingRow>> {
+        // Built at runtime: the calendar and status lists vary in length.
+        let mut query = QueryBuilder::<MySql>::new(
+            r#"
+            SELECT
+                id, calendar_id, customer_id, starts_at_utc, ends_at_utc, status, cancelled_at,
+                customer_name, customer_email, customer_phone, customer_notes, locale, price_cents,
+                discount_cents, created_at, updated_at, version
+            FROM bookings
+            WHERE 1 = 1
+            "#,
+        );
+        if !filter.calendar_ids.is_empty() {
+            query.push(" AND calendar_id IN (");
+            let mut ids = query.separated(", ");
+            for id in &filter.calendar_ids {
+                ids.push_bind(*id);
+            }
+            query.push(")");
+        }
+        if let Some(from) = filter.from {
+            query.push(" AND ends_at_utc > ").push_bind(from);
+        }
+        if let Some(to) = filter.to {
+            query.push(" AND starts_at_utc < ").push_bind(to);
+        }
+        if !filter.statuses.is_empty() {
+            query.push(" AND status IN (");
+            let mut statuses = query.separated(", ");
+            for status in &filter.statuses {
+                statuses.push_bind(*status);
+            }
+            query.push(")");
+        }
+        if let Some(customer) = &filter.customer {
+            let pattern = format!("%{}%", escape_like(customer));
+            query
+                .push(" AND (customer_name LIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR customer_email LIKE ")
+                .push_bind(pattern)
+                .push(")");
+        }
+        if let Some(created_from) = filter.created_from {
+            query.push(" AND created_at >= ").push_bind(created_from);
+        }
+        if let Some(created_to) = filter.created_to {
+            query.push(" AND created_at < ").push_bind(created_to);
+        }
+
+        let column = sort.column();
+        let (after, direction) = if sort.descending {
+            ("<", "DESC")
+        } else {
+            (">", "ASC")
+        };
+        if let Some(cursor) = cursor {
+            query
+                .push(format!(" AND ({column} {after} "))
+                .push_bind(cursor.value)
+                .push(format!(" OR ({column} = "))
+                .push_bind(cursor.value)
+                .push(format!(" AND id {after} "))
+                .push_bind(cursor.id)
+                .push("))");
+        }
+        query
+            .push(format!(
+                " ORDER BY {column} {direction}, id {direction} LIMIT "
+            ))
+            .push_bind(limit);
+
+        query
+            .build_query_as::<BookingRow>()
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn list_for_feed(
+        &self,
+        calendar_id: Option<u32>,
+        since: NaiveDateTime,
+    ) -> sqlx::Result<Vec<BookingRow>> {
+        sqlx::query_as!(
+            BookingRow,
+            r#"
+            SELECT
+                id, calendar_id, customer_id, starts_at_utc, ends_at_utc,
+                status as `status: BookingStatus`, cancelled_at,
+                customer_name, customer_email, customer_phone, customer_notes, locale, price_cents,
+                discount_cents, created_at, updated_at, version
+            FROM bookings
+            WHERE (? IS NULL OR calendar_id = ?) AND ends_at_utc >= ?
+            ORDER BY starts_at_utc
+            "#,
+            calendar_id,
+            calendar_id,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await
     }
 
     async fn get(&self, id: u32) -> sqlx::Result<Option<BookingRow>> {
-        let row = sqlx::query!(
+        sqlx::query_as!(
+            BookingRow,
             r#"
             SELECT
-                id, calendar_id, starts_at_utc, ends_at_utc,
-                customer_name, customer_email, customer_phone, customer_notes,
-                created_at, updated_at
+                id, calendar_id, customer_id, starts_at_utc, ends_at_utc,
+                status as `status: BookingStatus`, cancelled_at,
+                customer_name, customer_email, customer_phone, customer_notes, locale, price_cents,
+                discount_cents, created_at, updated_at, version
             FROM bookings
             WHERE id = ?
             "#,
             id
         )
         .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn insert(
+        &self,
+        data: &NewBooking,
+        hold_token_hash: Option<&str>,
+        now: NaiveDateTime,
+        email: Option<BookingEmail<'_>>,
+    ) -> Result<BookingRow, SlotError> {
+        let mut tx = self.pool.begin().await?;
+
+        lock_calendar(&mut tx, data.calendar_id).await?;
+        if let Some(token_hash) = hold_token_hash {
+            let covered = sqlx::query_scalar!(
+                r#"
+                SELECT COUNT(*) as `count: i64`
+                FROM booking_holds
+                WHERE token_hash = ? AND calendar_id = ? AND expires_at > ?
+                  AND starts_at_utc <= ? AND ends_at_utc >= ?
+                "#,
+                token_hash,
+                data.calendar_id,
+                now,
+                data.starts_at_utc,
+                data.ends_at_utc
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            if covered == 0 {
+                return Err(SlotError::InvalidHold);
+            }
+        }
+        ensure_slot_free(
+            &mut tx,
+            data.calendar_id,
+            data.starts_at_utc,
+            data.ends_at_utc,
+            data.buffer_minutes,
+            now,
+            None,
+            hold_token_hash,
+        )
+        .await?;
+        if let Some(rejection) = check_quota(&mut tx, &data.quota, now, None).await? {
+            return Err(SlotError::Rejected(rejection));
+        }
+
+        let result = sqlx::query!(
+            r#"INSERT INTO bookings (calendar_id, customer_id, starts_at_utc, ends_at_utc, customer_name, customer_email, customer_phone, customer_notes, locale, price_cents, discount_cents, status, payment_expires_at) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?)"#,
+            data.calendar_id,
+            data.customer_id,
+            data.starts_at_utc,
+            data.ends_at_utc,
+            data.customer_name,
+            data.customer_email,
+            data.customer_phone,
+            data.customer_notes,
+            data.locale,
+            data.price_cents,
+            data.voucher.map(|voucher| voucher.discount_cents),
+            data.status,
+            data.payment_expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        let id = result.last_insert_id() as u32;
+
+        if let Some(voucher) = &data.voucher
+            && !redeem(&mut tx, voucher, id).await?
+        {
+            return Err(SlotError::VoucherUnavailable);
+        }
+
+        if let Some(token_hash) = hold_token_hash {
+            sqlx::query!(
+                r#"DELETE FROM booking_holds WHERE token_hash = ?"#,
+                token_hash
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let row = sqlx::query_as!(
+            BookingRow,
+            r#"
+            SELECT
+                id, calendar_id, customer_id, starts_at_utc, ends_at_utc,
+                status as `status: BookingStatus`, cancelled_at,
+                customer_name, customer_email, customer_phone, customer_notes, locale, price_cents,
+                discount_cents, created_at, updated_at, version
+            FROM bookings
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_one(&mut *tx)
         .await?;
 
-        Ok(row.map(|row| BookingRow {
-            id: row.id,
-            calendar_id: row.calendar_id,
-            starts_at_utc: row.starts_at_utc.clone(),
-            ends_at_utc: row.ends_at_utc.clone(),
-            customer_name: row.customer_name,
-            customer_email: row.customer_email,
-            customer_phone: row.customer_phone,
-            customer_notes: row.customer_notes,
-            created_at: row.created_at.clone(),
-            updated_at: row.updated_at.clone(),
-        }))
+        if let Some(email) = email {
+            enqueue(&mut tx, &email(&row)).await?;
+        }
+        tx.commit().await?;
+
+        Ok(row)
     }
 
-    async fn insert(&self, data: CreateBookingRequest) -> sqlx::Result<u32> {
-        let start = data.start.naive_utc();
-        let end = data.end.naive_utc();
+    async fn cancel(
+        &self,
+        id: u32,
+        expected_version: Option<u32>,
+        email: Option<BookingEmail<'_>>,
+    ) -> sqlx::Result<Option<BookingRow>> {
+        let mut tx = self.pool.begin().await?;
 
         let result = sqlx::query!(
-            r#"INSERT INTO bookings (calendar_id, starts_at_utc, ends_at_utc, customer_name, customer_email, customer_phone, customer_notes) VALUES (?,?,?,?,?,?,?)"#,
-            data.calendar_id,
-            start,
-            end,
-            data.name,
-            data.email,
-            data.phone,
-            data.notes
+            r#"UPDATE bookings SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP(6), payment_expires_at = NULL, version = version + 1 WHERE id = ? AND status <> 'cancelled' AND (? IS NULL OR version = ?)"#,
+            id,
+            expected_version,
+            expected_version
+        )
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        reverse_redemptions(&mut tx, id).await?;
+
+        let row = sqlx::query_as!(
+            BookingRow,
+            r#"
+            SELECT
+                id, calendar_id, customer_id, starts_at_utc, ends_at_utc,
+                status as `status: BookingStatus`, cancelled_at,
+                customer_name, customer_email, customer_phone, customer_notes, locale, price_cents,
+                discount_cents, created_at, updated_at, version
+            FROM bookings
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if let Some(email) = email {
+            enqueue(&mut tx, &email(&row)).await?;
+        }
+        tx.commit().await?;
+
+        Ok(Some(row))
+    }
+
+    async fn reschedule(
+        &self,
+        id: u32,
+        starts_at_utc: NaiveDateTime,
+        ends_at_utc: NaiveDateTime,
+        price_cents: Option<u32>,
+        buffer_minutes: u32,
+        quota: &IdentityQuota,
+        now: NaiveDateTime,
+        expected_version: Option<u32>,
+        email: BookingEmail<'_>,
+    ) -> Result<Option<BookingRow>, SlotError> {
+        let mut tx = self.pool.begin().await?;
+
+        let calendar_id = sqlx::query_scalar!(
+            r#"SELECT calendar_id FROM bookings WHERE id = ? AND status <> 'cancelled'"#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(calendar_id) = calendar_id else {
+            return Ok(None);
+        };
+        lock_calendar(&mut tx, calendar_id).await?;
+        ensure_slot_free(
+            &mut tx,
+            calendar_id,
+            starts_at_utc,
+            ends_at_utc,
+            buffer_minutes,
+            now,
+            Some(id),
+            None,
+        )
+        .await?;
+        if let Some(rejection) = check_quota(&mut tx, quota, now, Some(id)).await? {
+            return Err(SlotError::Rejected(rejection));
+        }
+
+        let result = sqlx::query!(
+            r#"UPDATE bookings SET starts_at_utc = ?, ends_at_utc = ?, price_cents = ?, version = version + 1 WHERE id = ? AND status <> 'cancelled' AND (? IS NULL OR version = ?)"#,
+            starts_at_utc,
+            ends_at_utc,
+            price_cents,
+            id,
+            expected_version,
+            expected_version
+        )
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let row = sqlx::query_as!(
+            BookingRow,
+            r#"
+            SELECT
+                id, calendar_id, customer_id, starts_at_utc, ends_at_utc,
+                status as `status: BookingStatus`, cancelled_at,
+                customer_name, customer_email, customer_phone, customer_notes, locale, price_cents,
+                discount_cents, created_at, updated_at, version
+            FROM bookings
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        enqueue(&mut tx, &email(&row)).await?;
+        tx.commit().await?;
+
+        Ok(Some(row))
+    }
+
+    async fn confirm_payment(
+        &self,
+        id: u32,
+        email: BookingEmail<'_>,
+    ) -> sqlx::Result<Option<BookingRow>> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"UPDATE bookings SET status = 'confirmed', payment_expires_at = NULL, version = version + 1 WHERE id = ? AND status = 'pending_payment'"#,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let row = sqlx::query_as!(
+            BookingRow,
+            r#"
+            SELECT
+                id, calendar_id, customer_id, starts_at_utc, ends_at_utc,
+                status as `status: BookingStatus`, cancelled_at,
+                customer_name, customer_email, customer_phone, customer_notes, locale, price_cents,
+                discount_cents, created_at, updated_at, version
+            FROM bookings
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        enqueue(&mut tx, &email(&row)).await?;
+        tx.commit().await?;
+
+        Ok(Some(row))
+    }
+
+    async fn mark_no_show(&self, id: u32, now: NaiveDateTime) -> sqlx::Result<Option<BookingRow>> {
+        let result = sqlx::query!(
+            r#"UPDATE bookings SET status = 'no_show', version = version + 1 WHERE id = ? AND status = 'confirmed' AND starts_at_utc <= ?"#,
+            id,
+            now
         )
         .execute(&self.pool)
         .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get(id).await
+    }
 
-        Ok(result.last_insert_id() as u32)
+    async fn list_unpaid_expired(&self, now: NaiveDateTime) -> sqlx::Result<Vec<BookingRow>> {
+        sqlx::query_as!(
+            BookingRow,
+            r#"
+            SELECT
+                id, calendar_id, customer_id, starts_at_utc, ends_at_utc,
+                status as `status: BookingStatus`, cancelled_at,
+                customer_name, customer_email, customer_phone, customer_notes, locale, price_cents,
+                discount_cents, created_at, updated_at, version
+            FROM bookings
+            WHERE status = 'pending_payment' AND payment_expires_at <= ?
+            ORDER BY payment_expires_at
+            "#,
+            now
+        )
+        .fetch_all(&self.pool)
+        .await
     }
 
-    async fn delete(&self, id: u32) -> sqlx::Result<bool> {
-        let result = sqlx::query!(r#"DELETE FROM bookings WHERE id = ?"#, id)
-            .execute(&self.pool)
+    async fn insert_hold(
+        &self,
+        calendar_id: u32,
+        starts_at_utc: NaiveDateTime,
+        ends_at_utc: NaiveDateTime,
+        token_hash: &str,
+        buffer_minutes: u32,
+        client_ip: Option<IpAddr>,
+        max_per_client: u32,
+        now: NaiveDateTime,
+        expires_at: NaiveDateTime,
+    ) -> Result<BookingHoldRow, SlotError> {
+        let mut tx = self.pool.begin().await?;
+
+        lock_calendar(&mut tx, calendar_id).await?;
+        ensure_slot_free(
+            &mut tx,
+            calendar_id,
+            starts_at_utc,
+            ends_at_utc,
+            buffer_minutes,
+            now,
+            None,
+            None,
+        )
+        .await?;
+
+        let client_ip = client_ip.map(|ip| ip.to_string());
+        if let Some(ip) = &client_ip
+            && max_per_client > 0
+        {
+            lock_key(&mut tx, &format!("ip:{ip}")).await?;
+            let held = sqlx::query_scalar!(
+                r#"SELECT COUNT(*) as `count: i64` FROM booking_holds WHERE client_ip = ? AND expires_at > ?"#,
+                ip,
+                now
+            )
+            .fetch_one(&mut *tx)
             .await?;
+            if held as u32 >= max_per_client {
+                return Err(SlotError::TooManyHolds);
+            }
+        }
+
+        sqlx::query!(
+            r#"INSERT INTO booking_holds (calendar_id, starts_at_utc, ends_at_utc, token_hash, client_ip, expires_at) VALUES (?, ?, ?, ?, ?, ?)"#,
+            calendar_id,
+            starts_at_utc,
+            ends_at_utc,
+            token_hash,
+            client_ip,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
 
-        Ok(result.rows_affected() > 0)
+        Ok(BookingHoldRow {
+            calendar_id,
+            starts_at_utc,
+            ends_at_utc,
+            expires_at,
+        })
     }
+
+    async fn delete_hold(&self, token_hash: &str) -> sqlx::Result<Option<BookingHoldRow>> {
+        let mut tx = self.pool.begin().await?;
+        let hold = sqlx::query_as!(
+            BookingHoldRow,
+            r#"
+            SELECT calendar_id, starts_at_utc, ends_at_utc, expires_at
+            FROM booking_holds
+            WHERE token_hash = ?
+            FOR UPDATE
+            "#,
+            token_hash
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if hold.is_some() {
+            sqlx::query!(
+                r#"DELETE FROM booking_holds WHERE token_hash = ?"#,
+                token_hash
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(hold)
+    }
+
+    async fn delete_expired_holds(&self, now: NaiveDateTime) -> sqlx::Result<Vec<BookingHoldRow>> {
+        let mut tx = self.pool.begin().await?;
+        let holds = sqlx::query_as!(
+            BookingHoldRow,
+            r#"
+            SELECT calendar_id, starts_at_utc, ends_at_utc, expires_at
+            FROM booking_holds
+            WHERE expires_at <= ?
+            FOR UPDATE
+            "#,
+            now
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        sqlx::query!(r#"DELETE FROM booking_holds WHERE expires_at <= ?"#, now)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(holds)
+    }
+}
+
+/// Serializes slot-taking writes per calendar: concurrent transactions queue
+/// on the calendar row until the first one commits.
+async fn lock_calendar(conn: &mut MySqlConnection, calendar_id: u32) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"SELECT id FROM calendars WHERE id = ? FOR UPDATE"#,
+        calendar_id
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(())
+}
+
+/// Fails with `SlotError::Taken` when an active booking or unexpired hold
+/// overlaps the slot widened by `buffer_minutes` on both sides. Touching end
+/// and start times do not overlap.
+async fn ensure_slot_free(
+    conn: &mut MySqlConnection,
+    calendar_id: u32,
+    starts_at_utc: NaiveDateTime,
+    ends_at_utc: NaiveDateTime,
+    buffer_minutes: u32,
+    now: NaiveDateTime,
+    except_booking_id: Option<u32>,
+    except_hold_token_hash: Option<&str>,
+) -> Result<(), SlotError> {
+    let buffer = Duration::minutes(buffer_minutes.into());
+    let starts_at_utc = starts_at_utc - buffer;
+    let ends_at_utc = ends_at_utc + buffer;
+
+    let bookings = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as `count: i64`
+        FROM bookings
+        WHERE calendar_id = ? AND status <> 'cancelled'
+          AND starts_at_utc < ? AND ends_at_utc > ?
+          AND (? IS NULL OR id <> ?)
+        "#,
+        calendar_id,
+        ends_at_utc,
+        starts_at_utc,
+        except_booking_id,
+        except_booking_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let holds = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as `count: i64`
+        FROM booking_holds
+        WHERE calendar_id = ? AND expires_at > ?
+          AND starts_at_utc < ? AND ends_at_utc > ?
+          AND (? IS NULL OR token_hash <> ?)
+        "#,
+        calendar_id,
+        now,
+        ends_at_utc,
+        starts_at_utc,
+        except_hold_token_hash,
+        except_hold_token_hash
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if bookings > 0 || holds > 0 {
+        return Err(SlotError::Taken);
+    }
+    Ok(())
+}
+
+/// `LIKE` treats `%` and `_` as wildcards; searches match them literally.
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
@@ -1,25 +1,81 @@
-use super::data_transfer_objects::BookingResponse;
+use super::data_transfer_objects::{
+    BookingPageResponse, BookingResponse, BookingSearchQuery, BusyIntervalResponse,
+    CreateHoldRequest, FormTokenResponse, HoldResponse, MyBookingsResponse, OccupancyQuery,
+    OccupancyResponse, RescheduleBookingRequest,
+};
 use crate::{
+    auth::{AdminUser, CurrentCustomer, StaffOrCustomer},
     error::AppError,
-    features::bookings::{data_transfer_objects::CreateBookingRequest, model::BookingRow},
+    etag::{IfMatch, WithETag},
+    features::{
+        audit::model::AuditActor,
+        bookings::{
+            bot_protection::FormCheck,
+            data_transfer_objects::CreateBookingRequest,
+            model::{BookingFilter, BookingRow, BookingSort, BookingStatus},
+        },
+        payments::data_transfer_objects::PaymentResponse,
+    },
+    infrastructure::client_ip::ClientIp,
+    openapi::ErrorResponse,
     response::{Created, NoContent},
     state::AppState,
 };
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     routing::{get, post},
 };
 use chrono::{DateTime, Utc};
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list,
+    occupancy,
+    search,
+    create,
+    form_token,
+    create_hold,
+    release_hold,
+    get_by_id,
+    reschedule,
+    delete,
+    list_mine,
+    mark_no_show
+))]
+pub struct ApiDoc;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
-        .route("/api/calendar/{calendar_id}/bookings", get(list))
-        .route("/api/bookings", post(create))
-        .route("/api/bookings/{id}", axum::routing::delete(delete))
+        .route("/api/v1/calendars/{id}/bookings", get(list))
+        .route("/api/v1/calendars/{id}/occupancy", get(occupancy))
+        .route("/api/v1/bookings", get(search).post(create))
+        .route("/api/v1/bookings/form-token", get(form_token))
+        .route("/api/v1/bookings/holds", post(create_hold))
+        .route(
+            "/api/v1/bookings/holds/{token}",
+            axum::routing::delete(release_hold),
+        )
+        .route(
+            "/api/v1/bookings/{id}",
+            get(get_by_id).patch(reschedule).delete(delete),
+        )
+        .route("/api/v1/me/bookings", get(list_mine))
+        .route("/api/v1/admin/bookings/{id}/no-show", post(mark_no_show))
 }
 
+/// With the customers' details; the public only gets `occupancy`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/calendars/{id}/bookings",
+    tag = "bookings",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    responses((status = 200, body = Vec<BookingResponse>), (status = 401, body = ErrorResponse))
+)]
 async fn list(
+    _admin: AdminUser,
     State(state): State<AppState>,
     Path(calendar_id): Path<u32>,
 ) -> Result<Json<Vec<BookingResponse>>, AppError> {
@@ -27,23 +83,387 @@ async fn list(
     Ok(Json(rows.into_iter().map(row_to_response).collect()))
 }
 
+/// When the calendar is taken by bookings or holds, for drawing free slots.
+#[utoipa::path(
+    get,
+    path = "/api/v1/calendars/{id}/occupancy",
+    tag = "bookings",
+    params(("id" = u32, Path), OccupancyQuery),
+    responses(
+        (status = 200, body = OccupancyResponse),
+        (status = 400, body = ErrorResponse),
+        (status = 404, body = ErrorResponse)
+    )
+)]
+async fn occupancy(
+    State(state): State<AppState>,
+    Path(calendar_id): Path<u32>,
+    Query(query): Query<OccupancyQuery>,
+) -> Result<Json<OccupancyResponse>, AppError> {
+    let (from, to, intervals) = state
+        .bookings
+        .occupancy(calendar_id, query.from, query.to)
+        .await?;
+    Ok(Json(OccupancyResponse {
+        calendar_id,
+        from,
+        to,
+        busy: intervals
+            .into_iter()
+            .map(|interval| BusyIntervalResponse {
+                start: interval.starts_at_utc.and_utc(),
+                end: interval.ends_at_utc.and_utc(),
+            })
+            .collect(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/bookings",
+    tag = "bookings",
+    security(("admin" = [])),
+    params(BookingSearchQuery),
+    responses((status = 200, body = BookingPageResponse), (status = 400, body = ErrorResponse))
+)]
+async fn search(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+    Query(query): Query<BookingSearchQuery>,
+) -> Result<Json<BookingPageResponse>, AppError> {
+    let filter = BookingFilter {
+        calendar_ids: parse_list(query.calendar_id.as_deref(), |id| id.parse().ok()).ok_or(
+            AppError::BadRequest("calendar_id must be ids separated by commas"),
+        )?,
+        from: query.from.map(|from| from.naive_utc()),
+        to: query.to.map(|to| to.naive_utc()),
+        statuses: parse_list(query.status.as_deref(), BookingStatus::parse)
+            .ok_or(AppError::BadRequest("Unknown booking status"))?,
+        customer: query
+            .customer
+            .filter(|customer| !customer.trim().is_empty()),
+        created_from: query.created_from.map(|from| from.naive_utc()),
+        created_to: query.created_to.map(|to| to.naive_utc()),
+    };
+    let sort = match query.sort.as_deref() {
+        Some(sort) => BookingSort::parse(sort).ok_or(AppError::BadRequest(
+            "sort must be starts_at or created_at, optionally prefixed with -",
+        ))?,
+        None => BookingSort::default(),
+    };
+    let (rows, next_cursor) = state
+        .bookings
+        .search(filter, sort, query.cursor.as_deref(), query.limit)
+        .await?;
+    Ok(Json(BookingPageResponse {
+        items: rows.into_iter().map(row_to_response).collect(),
+        next_cursor,
+    }))
+}
+
+/// `None` if any comma-separated item doesn't parse.
+fn parse_list<T>(s: Option<&str>, parse: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    s.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(parse)
+        .collect()
+}
+
+/// Without a customer session, `form_token` is required and checked, unless
+/// the booking uses a hold: the hold was checked when it was made, and an
+/// invalid hold token fails the booking.
+#[utoipa::path(
+    post,
+    path = "/api/v1/bookings",
+    tag = "bookings",
+    request_body = CreateBookingRequest,
+    responses(
+        (status = 201, body = BookingResponse),
+        (status = 403, description = "Refused by an anti-abuse rule", body = ErrorResponse),
+        (status = 409, description = "The slot is taken", body = ErrorResponse)
+    )
+)]
 async fn create(
     State(state): State<AppState>,
+    ClientIp(client_ip): ClientIp,
+    customer: Option<CurrentCustomer>,
     Json(body): Json<CreateBookingRequest>,
 ) -> Result<Created<BookingResponse>, AppError> {
-    let row = state.bookings.create(body).await?;
+    let customer = customer.map(|CurrentCustomer(c)| c);
+    if customer.is_none() && body.hold_token.is_none() {
+        let form = FormCheck {
+            honeypot: body.website.as_deref(),
+            form_token: body.form_token.as_deref(),
+            challenge_response: body.challenge_response.as_deref(),
+        };
+        state
+            .bot_protection
+            .check(form, client_ip, Utc::now())
+            .await?;
+    }
+    let actor = AuditActor {
+        name: customer.as_ref().map(|c| c.email.clone()),
+        ip_address: client_ip,
+    };
+    let (row, payment) = state
+        .bookings
+        .create(body, customer.as_ref(), &actor)
+        .await?;
+
+    Ok(Created {
+        location: format!("/api/v1/bookings/{}", row.id),
+        body: BookingResponse {
+            payment: payment.map(|payment| PaymentResponse {
+                amount_cents: payment.amount_cents,
+                currency: payment.currency,
+                status: payment.status,
+                checkout_url: payment.checkout_url,
+                expires_at: payment.expires_at.and_utc(),
+            }),
+            ..row_to_response(row)
+        },
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/bookings/form-token",
+    tag = "bookings",
+    responses((status = 200, body = FormTokenResponse))
+)]
+async fn form_token(
+    State(state): State<AppState>,
+    ClientIp(client_ip): ClientIp,
+) -> Json<FormTokenResponse> {
+    let (token, expires_at) = state.bot_protection.issue_form_token(Utc::now(), client_ip);
+    Json(FormTokenResponse {
+        token,
+        expires_at,
+        min_fill_secs: state.config.bot_protection.min_fill_secs,
+        challenge_required: state.bot_protection.challenge_required(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/bookings",
+    tag = "bookings",
+    security(("customer" = [])),
+    responses((status = 200, body = MyBookingsResponse))
+)]
+async fn list_mine(
+    State(state): State<AppState>,
+    CurrentCustomer(customer): CurrentCustomer,
+) -> Result<Json<MyBookingsResponse>, AppError> {
+    let rows = state.bookings.list_for_customer(customer.id).await?;
+    let now = Utc::now().naive_utc();
+    let (upcoming, mut past): (Vec<_>, Vec<_>) =
+        rows.into_iter().partition(|row| row.ends_at_utc > now);
+    // Most recent first for history.
+    past.reverse();
+
+    Ok(Json(MyBookingsResponse {
+        upcoming: upcoming.into_iter().map(row_to_response).collect(),
+        past: past.into_iter().map(row_to_response).collect(),
+    }))
+}
+
+/// Staff see every booking, customers only their own.
+#[utoipa::path(
+    get,
+    path = "/api/v1/bookings/{id}",
+    tag = "bookings",
+    security(("admin" = []), ("customer" = [])),
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = BookingResponse, headers(("ETag" = String))),
+        (status = 401, body = ErrorResponse),
+        (status = 404, body = ErrorResponse)
+    )
+)]
+async fn get_by_id(
+    caller: StaffOrCustomer,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<WithETag<Json<BookingResponse>>, AppError> {
+    let row = accessible_booking(&state, &caller, id).await?;
+    Ok(WithETag {
+        version: row.version,
+        body: Json(row_to_response(row)),
+    })
+}
+
+/// Staff may cancel every booking, customers only their own.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/bookings/{id}",
+    tag = "bookings",
+    security(("admin" = []), ("customer" = [])),
+    params(
+        ("id" = u32, Path),
+        ("If-Match" = String, Header, description = "ETag from the last read")
+    ),
+    responses(
+        (status = 204),
+        (status = 401, body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+        (status = 412, description = "Changed since it was read", body = ErrorResponse),
+        (status = 428, description = "If-Match missing", body = ErrorResponse)
+    )
+)]
+async fn delete(
+    caller: StaffOrCustomer,
+    State(state): State<AppState>,
+    actor: AuditActor,
+    if_match: IfMatch,
+    Path(id): Path<u32>,
+) -> Result<NoContent, AppError> {
+    accessible_booking(&state, &caller, id).await?;
+    state.bookings.cancel(id, &if_match, &actor).await?;
+    Ok(NoContent)
+}
+
+/// Staff may move every booking, customers only their own. Booking policies
+/// and per-customer limits apply as when booking. Discounted bookings and
+/// those awaiting a deposit can't be moved.
+#[utoipa::path(
+    patch,
+    path = "/api/v1/bookings/{id}",
+    tag = "bookings",
+    security(("admin" = []), ("customer" = [])),
+    params(
+        ("id" = u32, Path),
+        ("If-Match" = String, Header, description = "ETag from the last read")
+    ),
+    request_body = RescheduleBookingRequest,
+    responses(
+        (status = 200, body = BookingResponse, headers(("ETag" = String))),
+        (status = 401, body = ErrorResponse),
+        (status = 403, description = "Refused by an anti-abuse rule", body = ErrorResponse),
+        (status = 404, body = ErrorResponse),
+        (status = 409, description = "The slot is taken, or the booking is discounted or awaiting its deposit", body = ErrorResponse),
+        (status = 412, description = "Changed since it was read", body = ErrorResponse),
+        (status = 428, description = "If-Match missing", body = ErrorResponse)
+    )
+)]
+async fn reschedule(
+    caller: StaffOrCustomer,
+    State(state): State<AppState>,
+    actor: AuditActor,
+    if_match: IfMatch,
+    Path(id): Path<u32>,
+    Json(body): Json<RescheduleBookingRequest>,
+) -> Result<WithETag<Json<BookingResponse>>, AppError> {
+    accessible_booking(&state, &caller, id).await?;
+    let row = state
+        .bookings
+        .reschedule(id, body.start, body.end, &if_match, &actor)
+        .await?;
+    Ok(WithETag {
+        version: row.version,
+        body: Json(row_to_response(row)),
+    })
+}
+
+/// Without a customer session, `form_token` is required and checked.
+#[utoipa::path(
+    post,
+    path = "/api/v1/bookings/holds",
+    tag = "bookings",
+    request_body = CreateHoldRequest,
+    responses(
+        (status = 201, body = HoldResponse),
+        (status = 403, description = "Refused by an anti-abuse rule", body = ErrorResponse),
+        (status = 409, description = "The slot is taken", body = ErrorResponse)
+    )
+)]
+async fn create_hold(
+    State(state): State<AppState>,
+    ClientIp(client_ip): ClientIp,
+    customer: Option<CurrentCustomer>,
+    actor: AuditActor,
+    Json(body): Json<CreateHoldRequest>,
+) -> Result<Created<HoldResponse>, AppError> {
+    if customer.is_none() {
+        let form = FormCheck {
+            honeypot: body.website.as_deref(),
+            form_token: body.form_token.as_deref(),
+            challenge_response: body.challenge_response.as_deref(),
+        };
+        state
+            .bot_protection
+            .check(form, client_ip, Utc::now())
+            .await?;
+    }
+    let (hold, token) = state.bookings.create_hold(body, client_ip, &actor).await?;
 
     Ok(Created {
-        location: format!("/api/bookings/{}", row.id),
-        body: row_to_response(row),
+        location: format!("/api/v1/bookings/holds/{token}"),
+        body: HoldResponse {
+            token,
+            calendar_id: hold.calendar_id,
+            start: DateTime::<Utc>::from_naive_utc_and_offset(hold.starts_at_utc, Utc),
+            end: DateTime::<Utc>::from_naive_utc_and_offset(hold.ends_at_utc, Utc),
+            expires_at: DateTime::<Utc>::from_naive_utc_and_offset(hold.expires_at, Utc),
+        },
     })
 }
 
-async fn delete(State(state): State<AppState>, Path(id): Path<u32>) -> Result<NoContent, AppError> {
-    state.bookings.delete(id).await?;
+#[utoipa::path(
+    delete,
+    path = "/api/v1/bookings/holds/{token}",
+    tag = "bookings",
+    params(("token" = String, Path)),
+    responses((status = 204))
+)]
+async fn release_hold(
+    State(state): State<AppState>,
+    actor: AuditActor,
+    Path(token): Path<String>,
+) -> Result<NoContent, AppError> {
+    state.bookings.release_hold(&token, &actor).await?;
     Ok(NoContent)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/bookings/{id}/no-show",
+    tag = "bookings",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    responses((status = 200, body = BookingResponse))
+)]
+async fn mark_no_show(
+    admin: AdminUser,
+    ClientIp(client_ip): ClientIp,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<Json<BookingResponse>, AppError> {
+    let actor = AuditActor {
+        name: Some(admin.name.clone()),
+        ip_address: client_ip,
+    };
+    let row = state.bookings.mark_no_show(id, &actor).await?;
+    tracing::info!(admin = %admin.name, id, "booking marked as no-show");
+    Ok(Json(row_to_response(row)))
+}
+
+/// The booking if `caller` may access it. Someone else's booking is
+/// reported missing, so ids can't be probed.
+async fn accessible_booking(
+    state: &AppState,
+    caller: &StaffOrCustomer,
+    id: u32,
+) -> Result<BookingRow, AppError> {
+    let row = state.bookings.get(id).await?;
+    if !caller.may_access(row.customer_id) {
+        return Err(AppError::NotFound("Booking not found"));
+    }
+    Ok(row)
+}
+
 fn row_to_response(row: BookingRow) -> BookingResponse {
     BookingResponse {
         id: row.id,
@@ -54,5 +474,9 @@ fn row_to_response(row: BookingRow) -> BookingResponse {
         notes: row.customer_notes,
         start: DateTime::<Utc>::from_naive_utc_and_offset(row.starts_at_utc, Utc),
         end: DateTime::<Utc>::from_naive_utc_and_offset(row.ends_at_utc, Utc),
+        status: row.status,
+        price_cents: row.price_cents,
+        discount_cents: row.discount_cents,
+        payment: None,
     }
 }
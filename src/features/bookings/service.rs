@@ -1,45 +1,671 @@
-use super::{model::BookingRow, repository::DynamicBookingsRepository};
-use crate::{error::AppError, features::bookings::data_transfer_objects::CreateBookingRequest};
+use std::{net::IpAddr, time::Duration};
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use super::{
+    data_transfer_objects::CreateHoldRequest,
+    model::{
+        BookingCursor, BookingFilter, BookingHoldRow, BookingRow, BookingSort, BookingStatus,
+        BusyInterval, NewBooking, SlotError,
+    },
+    repository::{BookingEmail, DynamicBookingsRepository},
+};
+use crate::{
+    error::AppError,
+    etag::IfMatch,
+    features::{
+        audit::{
+            model::AuditActor,
+            service::{AuditService, snapshot},
+        },
+        booking_limits::service::BookingLimitsService,
+        booking_policies::service::BookingPoliciesService,
+        bookings::data_transfer_objects::CreateBookingRequest,
+        calendars::repository::DynamicCalendarsRepository,
+        customers::{model::CustomerRow, repository::DynamicCustomersRepository},
+        events::bus::{AvailabilityEvent, EventBus},
+        notifications::templates::{self, Locale},
+        payments::{model::PaymentRow, service::PaymentsService},
+        pricing::service::PricingService,
+        vouchers::service::VouchersService,
+    },
+    infrastructure::security::{generate_token, hash_token},
+};
+
+/// Largest page of the booking search.
+const MAX_PAGE_SIZE: u32 = 200;
+
+/// Occupancy shown when no `to` is given, and the longest range served.
+const DEFAULT_OCCUPANCY_DAYS: i64 = 7;
+const MAX_OCCUPANCY_DAYS: i64 = 62;
 
 #[derive(Clone)]
 pub struct BookingsService {
     repository: DynamicBookingsRepository,
+    calendars: DynamicCalendarsRepository,
+    customers: DynamicCustomersRepository,
+    pricing: PricingService,
+    policies: BookingPoliciesService,
+    limits: BookingLimitsService,
+    vouchers: VouchersService,
+    /// `None` when online payments are disabled.
+    payments: Option<PaymentsService>,
+    timezone: Tz,
+    events: EventBus,
+    audit: AuditService,
+    hold_ttl: Duration,
+    max_holds_per_ip: u32,
 }
 
 impl BookingsService {
-    pub fn new(repository: DynamicBookingsRepository) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: DynamicBookingsRepository,
+        calendars: DynamicCalendarsRepository,
+        customers: DynamicCustomersRepository,
+        pricing: PricingService,
+        policies: BookingPoliciesService,
+        limits: BookingLimitsService,
+        vouchers: VouchersService,
+        payments: Option<PaymentsService>,
+        timezone: Tz,
+        events: EventBus,
+        audit: AuditService,
+        hold_ttl: Duration,
+        max_holds_per_ip: u32,
+    ) -> Self {
+        Self {
+            repository,
+            calendars,
+            customers,
+            pricing,
+            policies,
+            limits,
+            vouchers,
+            payments,
+            timezone,
+            events,
+            audit,
+            hold_ttl,
+            max_holds_per_ip,
+        }
     }
 
     pub async fn list(&self, calendar_id: u32) -> Result<Vec<BookingRow>, AppError> {
         Ok(self.repository.list(calendar_id).await?)
     }
 
-    pub async fn create(&self, request: CreateBookingRequest) -> Result<BookingRow, AppError> {
-        /* TODO: Check that there is no overlap */
+    /// When the calendar is taken in `from` (default now) .. `to` (default a
+    /// week later), with touching or overlapping bookings and holds merged so
+    /// individual bookings can't be told apart. Returns the range used.
+    pub async fn occupancy(
+        &self,
+        calendar_id: u32,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<(DateTime<Utc>, DateTime<Utc>, Vec<BusyInterval>), AppError> {
+        let now = Utc::now();
+        let from = from.unwrap_or(now);
+        let to = to.unwrap_or(from + chrono::Duration::days(DEFAULT_OCCUPANCY_DAYS));
+        if from >= to {
+            return Err(AppError::BadRequest("from must be before to"));
+        }
+        if to - from > chrono::Duration::days(MAX_OCCUPANCY_DAYS) {
+            return Err(AppError::BadRequest("The range can be at most 62 days"));
+        }
+        self.calendars
+            .get_by_id(calendar_id)
+            .await?
+            .ok_or(AppError::NotFound("Calendar not found"))?;
 
-        let id = self.repository.insert(request).await?;
+        let intervals = self
+            .repository
+            .busy_intervals(
+                calendar_id,
+                from.naive_utc(),
+                to.naive_utc(),
+                now.naive_utc(),
+            )
+            .await?;
+        Ok((from, to, merge_intervals(intervals)))
+    }
 
-        let row = self
+    /// One page of matching bookings, and the cursor of the next page when
+    /// there may be one.
+    pub async fn search(
+        &self,
+        filter: BookingFilter,
+        sort: BookingSort,
+        cursor: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<(Vec<BookingRow>, Option<String>), AppError> {
+        if let (Some(from), Some(to)) = (filter.from, filter.to)
+            && from >= to
+        {
+            return Err(AppError::BadRequest("from must be before to"));
+        }
+        if let (Some(from), Some(to)) = (filter.created_from, filter.created_to)
+            && from >= to
+        {
+            return Err(AppError::BadRequest(
+                "created_from must be before created_to",
+            ));
+        }
+        let cursor = match cursor {
+            Some(cursor) => {
+                let cursor =
+                    BookingCursor::decode(cursor).ok_or(AppError::BadRequest("Invalid cursor"))?;
+                if cursor.sort != sort {
+                    return Err(AppError::BadRequest("The cursor is for another sort order"));
+                }
+                Some(cursor)
+            }
+            None => None,
+        };
+        let limit = limit.unwrap_or(50).clamp(1, MAX_PAGE_SIZE);
+
+        // One extra row tells whether there is a next page.
+        let mut rows = self
             .repository
+            .search(&filter, sort, cursor.as_ref(), limit + 1)
+            .await?;
+        let next_cursor = if rows.len() > limit as usize {
+            rows.truncate(limit as usize);
+            rows.last()
+                .map(|row| BookingCursor::after(sort, row).encode())
+        } else {
+            None
+        };
+        Ok((rows, next_cursor))
+    }
+
+    pub async fn get(&self, id: u32) -> Result<BookingRow, AppError> {
+        self.repository
             .get(id)
             .await?
-            .ok_or(AppError::NotFound("Failed to fetch newly created booking"))?;
+            .ok_or(AppError::NotFound("Booking not found"))
+    }
+
+    pub async fn list_for_customer(&self, customer_id: u32) -> Result<Vec<BookingRow>, AppError> {
+        Ok(self.repository.list_by_customer(customer_id).await?)
+    }
+
+    /// Guests must give their contact details; logged-in customers may leave
+    /// them out to use the ones on their account. The confirmation email is
+    /// queued in the same transaction as the booking. Passing a hold token
+    /// turns that hold into the booking. Booking policies and per-customer
+    /// limits are checked first.
+    ///
+    /// A voucher code takes its discount off the price; the deposit is
+    /// worked out from what is left.
+    ///
+    /// When the slot needs a deposit the booking waits for payment instead,
+    /// and is returned with the checkout to send the customer to. The
+    /// confirmation email then goes out once the payment succeeds.
+    pub async fn create(
+        &self,
+        request: CreateBookingRequest,
+        customer: Option<&CustomerRow>,
+        actor: &AuditActor,
+    ) -> Result<(BookingRow, Option<PaymentRow>), AppError> {
+        if request.end <= request.start {
+            return Err(AppError::BadRequest("end must be after start"));
+        }
+
+        let calendar = self
+            .calendars
+            .get_by_id(request.calendar_id)
+            .await?
+            .ok_or(AppError::NotFound("Calendar not found"))?;
+
+        let pick = |given: Option<String>, account: Option<&String>| {
+            given
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .or_else(|| account.cloned())
+        };
+        let (Some(name), Some(email), Some(phone)) = (
+            pick(request.name, customer.map(|c| &c.name)),
+            pick(request.email, customer.map(|c| &c.email)),
+            pick(request.phone, customer.map(|c| &c.phone)),
+        ) else {
+            return Err(AppError::BadRequest("name, email and phone are required"));
+        };
+
+        let now = Utc::now();
+        let policy = self
+            .policies
+            .enforce(calendar.id, request.start, request.end, now)
+            .await?;
+        let quota = self
+            .limits
+            .check(
+                calendar.id,
+                &email,
+                &phone,
+                request.start,
+                request.end,
+                now,
+                actor.ip_address,
+            )
+            .await?;
+
+        let price = self
+            .pricing
+            .price(
+                &calendar,
+                request.start,
+                request.end,
+                customer.and_then(|c| c.category.as_deref()),
+            )
+            .await?;
+        let price_cents = price.map(|quote| quote.total_cents);
+
+        let voucher = match request.voucher_code.as_deref() {
+            Some(code) => Some(
+                self.vouchers
+                    .apply(code, &calendar, request.start, price_cents, now)
+                    .await?,
+            ),
+            None => None,
+        };
+        let due_cents =
+            price_cents.map(|price| price - voucher.map_or(0, |voucher| voucher.discount_cents));
+        let deposit = self.payments.as_ref().and_then(|payments| {
+            payments
+                .deposit_for(request.start, due_cents)
+                .map(|amount| (payments, amount, payments.payment_deadline(now)))
+        });
+
+        let booking = NewBooking {
+            calendar_id: request.calendar_id,
+            customer_id: customer.map(|c| c.id),
+            starts_at_utc: request.start.naive_utc(),
+            ends_at_utc: request.end.naive_utc(),
+            customer_name: name,
+            customer_email: email,
+            customer_phone: phone,
+            customer_notes: request.notes,
+            locale: Locale::parse(request.locale.as_deref().unwrap_or_default())
+                .as_str()
+                .to_string(),
+            price_cents,
+            voucher,
+            buffer_minutes: policy.buffer_minutes,
+            quota,
+            status: match deposit {
+                Some(_) => BookingStatus::PendingPayment,
+                None => BookingStatus::Confirmed,
+            },
+            payment_expires_at: deposit.map(|(_, _, deadline)| deadline.naive_utc()),
+        };
+
+        let timezone = self.timezone;
+        let calendar_name = calendar.name.clone();
+        let email: Option<BookingEmail> = match deposit {
+            Some(_) => None,
+            None => Some(Box::new(move |row| {
+                templates::booking_confirmation(row, &calendar_name, timezone)
+            })),
+        };
+        let row = match self
+            .repository
+            .insert(
+                &booking,
+                request.hold_token.as_deref().map(hash_token).as_deref(),
+                now.naive_utc(),
+                email,
+            )
+            .await
+        {
+            Err(SlotError::Rejected(rejection)) => {
+                return Err(self
+                    .limits
+                    .refuse(
+                        calendar.id,
+                        &booking.quota.identity,
+                        request.start,
+                        request.end,
+                        rejection,
+                        actor.ip_address,
+                    )
+                    .await);
+            }
+            result => result?,
+        };
+
+        let payment = match deposit {
+            Some((payments, amount, deadline)) => {
+                match payments
+                    .start_checkout(&row, &calendar.name, amount, deadline)
+                    .await
+                {
+                    Ok(payment) => Some(payment),
+                    Err(error) => {
+                        // Nothing can be paid, so don't keep the slot.
+                        self.repository
+                            .cancel(row.id, Some(row.version), None)
+                            .await?;
+                        return Err(error);
+                    }
+                }
+            }
+            None => None,
+        };
+
+        self.events
+            .publish(AvailabilityEvent::booking_created(&row));
+        self.audit
+            .record_change(
+                actor,
+                "booking.created",
+                "booking",
+                Some(row.id.to_string()),
+                None,
+                snapshot(&row),
+            )
+            .await?;
+        Ok((row, payment))
+    }
+
+    /// Cancels the booking and queues the cancellation email. The row is
+    /// kept so the customer's history and calendar feeds still show it.
+    /// Voucher uses and gift card balance are given back, and a paid
+    /// deposit is refunded when the cancellation is early enough.
+    pub async fn cancel(
+        &self,
+        id: u32,
+        if_match: &IfMatch,
+        actor: &AuditActor,
+    ) -> Result<BookingRow, AppError> {
+        let booking = self.get(id).await?;
+        if_match.check(booking.version)?;
+        if booking.status == BookingStatus::Cancelled {
+            return Err(AppError::Conflict("Booking is already cancelled"));
+        }
+        let calendar_name = self.calendar_name(booking.calendar_id).await?;
+
+        let timezone = self.timezone;
+        let row = self
+            .repository
+            .cancel(
+                id,
+                Some(booking.version),
+                Some(Box::new(move |row| {
+                    templates::booking_cancellation(row, &calendar_name, timezone)
+                })),
+            )
+            .await?
+            .ok_or(AppError::PreconditionFailed(
+                "The booking was changed by someone else",
+            ))?;
 
+        self.events
+            .publish(AvailabilityEvent::booking_cancelled(&row));
+        self.audit
+            .record_change(
+                actor,
+                "booking.cancelled",
+                "booking",
+                Some(id.to_string()),
+                snapshot(&booking),
+                snapshot(&row),
+            )
+            .await?;
+        if let Some(payments) = &self.payments {
+            // The booking is cancelled either way; a failed refund is
+            // logged for staff to handle by hand.
+            if let Err(error) = payments.refund_for_cancellation(&row, Utc::now()).await {
+                tracing::error!(%error, booking_id = row.id, "deposit refund failed");
+            }
+        }
+        Ok(row)
+    }
+
+    /// Counts against the customer's no-show limit.
+    pub async fn mark_no_show(&self, id: u32, actor: &AuditActor) -> Result<BookingRow, AppError> {
+        let previous = self
+            .repository
+            .get(id)
+            .await?
+            .ok_or(AppError::NotFound("Booking not found"))?;
+        let row = self
+            .repository
+            .mark_no_show(id, Utc::now().naive_utc())
+            .await?
+            .ok_or(AppError::Conflict(
+                "Only confirmed bookings that have started can be marked as no-show",
+            ))?;
+        self.audit
+            .record_change(
+                actor,
+                "booking.no_show",
+                "booking",
+                Some(id.to_string()),
+                snapshot(&previous),
+                snapshot(&row),
+            )
+            .await?;
         Ok(row)
     }
 
-    pub async fn delete(&self, id: u32) -> Result<(), AppError> {
-        let n = self
+    /// Moves an active booking to a new time and emails the customer the
+    /// updated calendar entry. Bookings with a voucher discount or an open
+    /// deposit payment are priced against their original slot, so they have
+    /// to be cancelled and booked again instead.
+    pub async fn reschedule(
+        &self,
+        id: u32,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        if_match: &IfMatch,
+        actor: &AuditActor,
+    ) -> Result<BookingRow, AppError> {
+        if end <= start {
+            return Err(AppError::BadRequest("end must be after start"));
+        }
+
+        let previous = self.get(id).await?;
+        if_match.check(previous.version)?;
+        if previous.status == BookingStatus::Cancelled {
+            return Err(AppError::Conflict("Booking is cancelled"));
+        }
+        if previous.status == BookingStatus::PendingPayment {
+            return Err(AppError::Conflict("Booking is awaiting its deposit"));
+        }
+        if previous.discount_cents.is_some() {
+            return Err(AppError::Conflict(
+                "Booking was discounted by a voucher and can't be moved",
+            ));
+        }
+        let calendar = self
+            .calendars
+            .get_by_id(previous.calendar_id)
+            .await?
+            .ok_or(AppError::NotFound("Calendar not found"))?;
+        let now = Utc::now();
+        let policy = self.policies.enforce(calendar.id, start, end, now).await?;
+        let quota = self
+            .limits
+            .check(
+                calendar.id,
+                &previous.customer_email,
+                &previous.customer_phone,
+                start,
+                end,
+                now,
+                actor.ip_address,
+            )
+            .await?;
+        let category = match previous.customer_id {
+            Some(customer_id) => self
+                .customers
+                .get_by_id(customer_id)
+                .await?
+                .and_then(|c| c.category),
+            None => None,
+        };
+        let price = self
+            .pricing
+            .price(&calendar, start, end, category.as_deref())
+            .await?;
+
+        let calendar_name = calendar.name;
+        let timezone = self.timezone;
+        let row = match self
             .repository
-            .delete(id)
+            .reschedule(
+                id,
+                start.naive_utc(),
+                end.naive_utc(),
+                price.map(|quote| quote.total_cents),
+                policy.buffer_minutes,
+                &quota,
+                now.naive_utc(),
+                Some(previous.version),
+                Box::new(move |row| templates::booking_rescheduled(row, &calendar_name, timezone)),
+            )
             .await
-            .map_err(AppError::Database)?;
+        {
+            Err(SlotError::Rejected(rejection)) => {
+                return Err(self
+                    .limits
+                    .refuse(
+                        calendar.id,
+                        &quota.identity,
+                        start,
+                        end,
+                        rejection,
+                        actor.ip_address,
+                    )
+                    .await);
+            }
+            result => result?,
+        }
+        .ok_or(AppError::PreconditionFailed(
+            "The booking was changed by someone else",
+        ))?;
 
-        if n == false {
-            Err(AppError::NotFound("Booking not found"))
-        } else {
-            Ok(())
+        self.events
+            .publish(AvailabilityEvent::booking_rescheduled(&previous, &row));
+        self.audit
+            .record_change(
+                actor,
+                "booking.rescheduled",
+                "booking",
+                Some(id.to_string()),
+                snapshot(&previous),
+                snapshot(&row),
+            )
+            .await?;
+        Ok(row)
+    }
+
+    /// Reserves a slot for `hold_ttl`. Returns the hold and its token, which
+    /// is only stored hashed. A client IP may only hold `max_holds_per_ip`
+    /// slots at once.
+    pub async fn create_hold(
+        &self,
+        request: CreateHoldRequest,
+        client_ip: Option<IpAddr>,
+        actor: &AuditActor,
+    ) -> Result<(BookingHoldRow, String), AppError> {
+        if request.end <= request.start {
+            return Err(AppError::BadRequest("end must be after start"));
+        }
+        self.calendars
+            .get_by_id(request.calendar_id)
+            .await?
+            .ok_or(AppError::NotFound("Calendar not found"))?;
+
+        let now = Utc::now();
+        let policy = self
+            .policies
+            .enforce(request.calendar_id, request.start, request.end, now)
+            .await?;
+        let token = generate_token();
+        let hold = self
+            .repository
+            .insert_hold(
+                request.calendar_id,
+                request.start.naive_utc(),
+                request.end.naive_utc(),
+                &hash_token(&token),
+                policy.buffer_minutes,
+                client_ip,
+                self.max_holds_per_ip,
+                now.naive_utc(),
+                (now + self.hold_ttl).naive_utc(),
+            )
+            .await?;
+        self.events.publish(AvailabilityEvent::hold_created(&hold));
+        self.audit
+            .record_change(
+                actor,
+                "booking_hold.created",
+                "booking_hold",
+                None,
+                None,
+                snapshot(&hold),
+            )
+            .await?;
+        Ok((hold, token))
+    }
+
+    pub async fn release_hold(&self, token: &str, actor: &AuditActor) -> Result<(), AppError> {
+        let hold = self
+            .repository
+            .delete_hold(&hash_token(token))
+            .await?
+            .ok_or(AppError::NotFound("Hold not found"))?;
+        self.events.publish(AvailabilityEvent::hold_released(&hold));
+        self.audit
+            .record_change(
+                actor,
+                "booking_hold.released",
+                "booking_hold",
+                None,
+                None,
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes expired holds and announces the freed slots. Expired holds
+    /// already stop blocking slots; this keeps the table small. Returns how
+    /// many were removed.
+    pub async fn expire_holds(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let expired = self
+            .repository
+            .delete_expired_holds(now.naive_utc())
+            .await?;
+        for hold in &expired {
+            self.events.publish(AvailabilityEvent::hold_expired(hold));
+        }
+        Ok(expired.len())
+    }
+
+    async fn calendar_name(&self, calendar_id: u32) -> Result<String, AppError> {
+        Ok(self
+            .calendars
+            .get_by_id(calendar_id)
+            .await?
+            .map(|c| c.name)
+            .unwrap_or_default())
+    }
+}
+
+/// Joins intervals sorted by start that overlap or touch.
+fn merge_intervals(intervals: Vec<BusyInterval>) -> Vec<BusyInterval> {
+    let mut merged: Vec<BusyInterval> = Vec::with_capacity(intervals.len());
+    for interval in intervals {
+        match merged.last_mut() {
+            Some(last) if interval.starts_at_utc <= last.ends_at_utc => {
+                last.ends_at_utc = last.ends_at_utc.max(interval.ends_at_utc);
+            }
+            _ => merged.push(interval),
         }
     }
+    merged
 }
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::model::FeedScope;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateFeedTokenRequest {
+    pub name: String,
+    /// Leave out for the venue-wide feed.
+    pub calendar_id: Option<u32>,
+    pub scope: FeedScope,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeedTokenResponse {
+    pub id: u32,
+    pub name: String,
+    pub calendar_id: Option<u32>,
+    pub scope: FeedScope,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Returned once on creation; only the hash of the token is stored.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedFeedTokenResponse {
+    #[serde(flatten)]
+    pub feed: FeedTokenResponse,
+    pub token: String,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FeedQuery {
+    pub token: Option<String>,
+}
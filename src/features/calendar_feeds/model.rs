@@ -0,0 +1,40 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What a feed token reveals about the people who booked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedScope {
+    /// Customer name, contact details and notes.
+    Full,
+    /// Only that the table is booked.
+    Redacted,
+}
+
+impl FeedScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FeedScope::Full => "full",
+            FeedScope::Redacted => "redacted",
+        }
+    }
+
+    /// Unknown values are treated as redacted.
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "full" => FeedScope::Full,
+            _ => FeedScope::Redacted,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FeedTokenRow {
+    pub id: u32,
+    pub name: String,
+    /// `None` for the venue-wide feed.
+    pub calendar_id: Option<u32>,
+    pub scope: FeedScope,
+    pub created_at: NaiveDateTime,
+}
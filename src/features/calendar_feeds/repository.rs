@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use sqlx::{MySql, Pool};
+
+use super::model::{FeedScope, FeedTokenRow};
+
+#[async_trait]
+pub trait FeedTokensRepository: Send + Sync {
+    async fn list(&self) -> sqlx::Result<Vec<FeedTokenRow>>;
+    async fn get_by_hash(&self, token_hash: &str) -> sqlx::Result<Option<FeedTokenRow>>;
+    async fn insert(
+        &self,
+        name: &str,
+        calendar_id: Option<u32>,
+        scope: FeedScope,
+        token_hash: &str,
+    ) -> sqlx::Result<FeedTokenRow>;
+    async fn delete(&self, id: u32) -> sqlx::Result<bool>;
+}
+
+pub type DynamicFeedTokensRepository = std::sync::Arc<dyn FeedTokensRepository>;
+
+#[derive(Clone)]
+pub struct MySqlFeedTokensRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlFeedTokensRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl FeedTokensRepository for MySqlFeedTokensRepository {
+    async fn list(&self) -> sqlx::Result<Vec<FeedTokenRow>> {
+        let rows = sqlx::query!(
+            r#"SELECT id, name, calendar_id, scope, created_at FROM calendar_feed_tokens ORDER BY id"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| FeedTokenRow {
+                id: row.id,
+                name: row.name,
+                calendar_id: row.calendar_id,
+                scope: FeedScope::parse(&row.scope),
+                created_at: row.created_at,
+            })
+            .collect())
+    }
+
+    async fn get_by_hash(&self, token_hash: &str) -> sqlx::Result<Option<FeedTokenRow>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id, name, calendar_id, scope, created_at
+            FROM calendar_feed_tokens
+            WHERE token_hash = ?
+            "#,
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| FeedTokenRow {
+            id: row.id,
+            name: row.name,
+            calendar_id: row.calendar_id,
+            scope: FeedScope::parse(&row.scope),
+            created_at: row.created_at,
+        }))
+    }
+
+    async fn insert(
+        &self,
+        name: &str,
+        calendar_id: Option<u32>,
+        scope: FeedScope,
+        token_hash: &str,
+    ) -> sqlx::Result<FeedTokenRow> {
+        let result = sqlx::query!(
+            r#"INSERT INTO calendar_feed_tokens (name, calendar_id, scope, token_hash) VALUES (?, ?, ?, ?)"#,
+            name,
+            calendar_id,
+            scope.as_str(),
+            token_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        let id = result.last_insert_id() as u32;
+        let row = sqlx::query!(
+            r#"SELECT created_at FROM calendar_feed_tokens WHERE id = ?"#,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(FeedTokenRow {
+            id,
+            name: name.to_string(),
+            calendar_id,
+            scope,
+            created_at: row.created_at,
+        })
+    }
+
+    async fn delete(&self, id: u32) -> sqlx::Result<bool> {
+        let result = sqlx::query!(r#"DELETE FROM calendar_feed_tokens WHERE id = ?"#, id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,163 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::header,
+    response::IntoResponse,
+    routing::{delete, get},
+};
+use utoipa::OpenApi;
+
+use super::{
+    data_transfer_objects::{
+        CreateFeedTokenRequest, CreatedFeedTokenResponse, FeedQuery, FeedTokenResponse,
+    },
+    model::FeedTokenRow,
+};
+use crate::{
+    auth::AdminUser,
+    error::AppError,
+    response::{Created, NoContent},
+    state::AppState,
+};
+
+#[derive(OpenApi)]
+#[openapi(paths(calendar_feed, venue_feed, list_tokens, create_token, delete_token))]
+pub struct ApiDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/calendars/{id}/bookings.ics", get(calendar_feed))
+        .route("/api/v1/bookings.ics", get(venue_feed))
+        .route(
+            "/api/v1/admin/feed-tokens",
+            get(list_tokens).post(create_token),
+        )
+        .route("/api/v1/admin/feed-tokens/{id}", delete(delete_token))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/calendars/{id}/bookings.ics",
+    tag = "calendar feeds",
+    params(("id" = u32, Path), FeedQuery),
+    responses((
+        status = 200,
+        description = "iCalendar feed",
+        content_type = "text/calendar",
+        body = String
+    ))
+)]
+async fn calendar_feed(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let body = state
+        .calendar_feeds
+        .calendar_feed(id, query.token.as_deref())
+        .await?;
+    Ok(ics_response(body))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/bookings.ics",
+    tag = "calendar feeds",
+    params(FeedQuery),
+    responses((
+        status = 200,
+        description = "iCalendar feed",
+        content_type = "text/calendar",
+        body = String
+    ))
+)]
+async fn venue_feed(
+    State(state): State<AppState>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let body = state
+        .calendar_feeds
+        .venue_feed(query.token.as_deref())
+        .await?;
+    Ok(ics_response(body))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/feed-tokens",
+    tag = "calendar feeds",
+    security(("admin" = [])),
+    responses((status = 200, body = Vec<FeedTokenResponse>))
+)]
+async fn list_tokens(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<FeedTokenResponse>>, AppError> {
+    let rows = state.calendar_feeds.list_tokens().await?;
+    Ok(Json(rows.into_iter().map(row_to_response).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/feed-tokens",
+    tag = "calendar feeds",
+    security(("admin" = [])),
+    request_body = CreateFeedTokenRequest,
+    responses((status = 201, body = CreatedFeedTokenResponse))
+)]
+async fn create_token(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Json(body): Json<CreateFeedTokenRequest>,
+) -> Result<Created<CreatedFeedTokenResponse>, AppError> {
+    let (row, token) = state.calendar_feeds.create_token(body).await?;
+    tracing::info!(admin = %admin.name, id = row.id, "calendar feed token created");
+    let url = match row.calendar_id {
+        Some(calendar_id) => format!("/api/v1/calendars/{calendar_id}/bookings.ics?token={token}"),
+        None => format!("/api/v1/bookings.ics?token={token}"),
+    };
+
+    Ok(Created {
+        location: format!("/api/v1/admin/feed-tokens/{}", row.id),
+        body: CreatedFeedTokenResponse {
+            feed: row_to_response(row),
+            token,
+            url,
+        },
+    })
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/feed-tokens/{id}",
+    tag = "calendar feeds",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    responses((status = 204))
+)]
+async fn delete_token(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<NoContent, AppError> {
+    state.calendar_feeds.delete_token(id).await?;
+    tracing::info!(admin = %admin.name, id, "calendar feed token revoked");
+    Ok(NoContent)
+}
+
+fn ics_response(body: String) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        body,
+    )
+}
+
+fn row_to_response(row: FeedTokenRow) -> FeedTokenResponse {
+    FeedTokenResponse {
+        id: row.id,
+        name: row.name,
+        calendar_id: row.calendar_id,
+        scope: row.scope,
+        created_at: row.created_at.and_utc(),
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use chrono::{Days, Utc};
+
+use super::{
+    data_transfer_objects::CreateFeedTokenRequest,
+    model::{FeedScope, FeedTokenRow},
+    repository::DynamicFeedTokensRepository,
+};
+use crate::{
+    error::AppError,
+    features::{
+        bookings::{
+            ics::{self, EventStatus, IcsEvent},
+            model::{BookingRow, BookingStatus},
+            repository::DynamicBookingsRepository,
+        },
+        calendars::repository::DynamicCalendarsRepository,
+    },
+    infrastructure::security::{generate_token, hash_token},
+};
+
+/// How far back the feeds reach.
+const FEED_HISTORY_DAYS: u64 = 90;
+
+#[derive(Clone)]
+pub struct CalendarFeedsService {
+    repository: DynamicFeedTokensRepository,
+    calendars: DynamicCalendarsRepository,
+    bookings: DynamicBookingsRepository,
+}
+
+impl CalendarFeedsService {
+    pub fn new(
+        repository: DynamicFeedTokensRepository,
+        calendars: DynamicCalendarsRepository,
+        bookings: DynamicBookingsRepository,
+    ) -> Self {
+        Self {
+            repository,
+            calendars,
+            bookings,
+        }
+    }
+
+    pub async fn list_tokens(&self) -> Result<Vec<FeedTokenRow>, AppError> {
+        Ok(self.repository.list().await?)
+    }
+
+    /// Returns the new row and the plain token, which is not stored.
+    pub async fn create_token(
+        &self,
+        request: CreateFeedTokenRequest,
+    ) -> Result<(FeedTokenRow, String), AppError> {
+        let name = request.name.trim();
+        if name.is_empty() {
+            return Err(AppError::BadRequest("name is required"));
+        }
+        if let Some(calendar_id) = request.calendar_id {
+            self.calendars
+                .get_by_id(calendar_id)
+                .await?
+                .ok_or(AppError::NotFound("Calendar not found"))?;
+        }
+
+        let token = generate_token();
+        let row = self
+            .repository
+            .insert(
+                name,
+                request.calendar_id,
+                request.scope,
+                &hash_token(&token),
+            )
+            .await?;
+        Ok((row, token))
+    }
+
+    pub async fn delete_token(&self, id: u32) -> Result<(), AppError> {
+        if !self.repository.delete(id).await? {
+            return Err(AppError::NotFound("Feed token not found"));
+        }
+        Ok(())
+    }
+
+    /// Feed of one table. Venue-wide tokens may read every table's feed.
+    pub async fn calendar_feed(
+        &self,
+        calendar_id: u32,
+        token: Option<&str>,
+    ) -> Result<String, AppError> {
+        let feed = self.authorize(token).await?;
+        if feed.calendar_id.is_some_and(|id| id != calendar_id) {
+            return Err(AppError::Forbidden("Token is not valid for this calendar"));
+        }
+
+        let calendar = self
+            .calendars
+            .get_by_id(calendar_id)
+            .await?
+            .ok_or(AppError::NotFound("Calendar not found"))?;
+        let names = HashMap::from([(calendar.id, calendar.name.clone())]);
+
+        self.render(
+            Some(calendar_id),
+            &format!("Kukkilan Biljardi – {}", calendar.name),
+            &names,
+            feed.scope,
+        )
+        .await
+    }
+
+    pub async fn venue_feed(&self, token: Option<&str>) -> Result<String, AppError> {
+        let feed = self.authorize(token).await?;
+        if feed.calendar_id.is_some() {
+            return Err(AppError::Forbidden("Token is only valid for one calendar"));
+        }
+
+        let names: HashMap<u32, String> = self
+            .calendars
+            .list()
+            .await?
+            .into_iter()
+            .map(|c| (c.id, c.name))
+            .collect();
+
+        self.render(None, "Kukkilan Biljardi", &names, feed.scope)
+            .await
+    }
+
+    async fn authorize(&self, token: Option<&str>) -> Result<FeedTokenRow, AppError> {
+        let token = token
+            .filter(|t| !t.is_empty())
+            .ok_or(AppError::Unauthorized("Missing feed token"))?;
+        self.repository
+            .get_by_hash(&hash_token(token))
+            .await?
+            .ok_or(AppError::Unauthorized("Invalid feed token"))
+    }
+
+    async fn render(
+        &self,
+        calendar_id: Option<u32>,
+        title: &str,
+        calendar_names: &HashMap<u32, String>,
+        scope: FeedScope,
+    ) -> Result<String, AppError> {
+        let since = Utc::now()
+            .naive_utc()
+            .checked_sub_days(Days::new(FEED_HISTORY_DAYS))
+            .ok_or(AppError::Internal("Invalid feed window"))?;
+        let bookings = self.bookings.list_for_feed(calendar_id, since).await?;
+
+        let events: Vec<IcsEvent> = bookings
+            .iter()
+            .map(|booking| {
+                let calendar_name = calendar_names
+                    .get(&booking.calendar_id)
+                    .map(String::as_str)
+                    .unwrap_or_default();
+                feed_event(booking, calendar_name, scope)
+            })
+            .collect();
+
+        Ok(ics::render_calendar(Some(title), &events))
+    }
+}
+
+fn feed_event(booking: &BookingRow, calendar_name: &str, scope: FeedScope) -> IcsEvent {
+    let (summary, description) = match scope {
+        FeedScope::Full => {
+            let mut description = format!(
+                "{}\n{}\n{}",
+                booking.customer_name, booking.customer_phone, booking.customer_email
+            );
+            if let Some(notes) = booking.customer_notes.as_deref().filter(|n| !n.is_empty()) {
+                description.push_str("\n\n");
+                description.push_str(notes);
+            }
+            (
+                format!("{calendar_name}: {}", booking.customer_name),
+                Some(description),
+            )
+        }
+        FeedScope::Redacted => (format!("{calendar_name}: varattu"), None),
+    };
+
+    IcsEvent {
+        uid: ics::booking_uid(booking.id),
+        sequence: ics::booking_sequence(booking.updated_at),
+        stamp: booking.updated_at,
+        start: booking.starts_at_utc,
+        end: booking.ends_at_utc,
+        summary,
+        description,
+        status: match booking.status {
+            BookingStatus::Cancelled => EventStatus::Cancelled,
+            BookingStatus::PendingPayment => EventStatus::Tentative,
+            BookingStatus::Confirmed | BookingStatus::NoShow => EventStatus::Confirmed,
+        },
+    }
+}
@@ -1,20 +1,24 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct CalendarResponse {
     pub id: u32,
     pub name: String,
+    pub table_type: Option<String>,
     pub active: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateCalendarRequest {
     pub name: String,
+    pub table_type: Option<String>,
     pub active: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateCalendarRequest {
     pub name: Option<String>,
+    pub table_type: Option<String>,
     pub active: Option<bool>,
 }
@@ -5,7 +5,11 @@ use serde::{Deserialize, Serialize};
 pub struct CalendarRow {
     pub id: u32,
     pub name: String,
+    /// e.g. `snooker` or `pool`; pricing rules can target a table type.
+    pub table_type: Option<String>,
     pub active: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// Bumped by every update; the calendar's ETag.
+    pub version: u32,
 }
@@ -7,9 +7,17 @@ pub trait CalendarsRepository: Send + Sync {
     async fn list(&self) -> sqlx::Result<Vec<CalendarRow>>;
     async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<CalendarRow>>;
     async fn get_by_name(&self, name: &str) -> sqlx::Result<Option<CalendarRow>>;
-    async fn insert(&self, name: &str, active: bool) -> sqlx::Result<u32>;
-    async fn update(&self, id: u32, name: Option<&str>, active: Option<bool>) -> sqlx::Result<u32>;
-    async fn delete(&self, id: u32) -> sqlx::Result<bool>;
+    async fn insert(&self, name: &str, table_type: Option<&str>, active: bool)
+    -> sqlx::Result<u32>;
+    async fn update(
+        &self,
+        id: u32,
+        name: Option<&str>,
+        table_type: Option<&str>,
+        active: Option<bool>,
+        expected_version: u32,
+    ) -> sqlx::Result<u32>;
+    async fn delete(&self, id: u32, expected_version: u32) -> sqlx::Result<bool>;
 }
 
 pub type DynamicCalendarsRepository = std::sync::Arc<dyn CalendarsRepository>;
@@ -36,9 +44,11 @@ impl CalendarsRepository for MySqlCalendarsRepository {
             .map(|row| CalendarRow {
                 id: row.id,
                 name: row.name,
+                table_type: row.table_type,
                 active: row.active != 0,
                 created_at: row.created_at.clone(),
                 updated_at: row.updated_at.clone(),
+                version: row.version,
             })
             .collect())
     }
@@ -51,9 +61,11 @@ impl CalendarsRepository for MySqlCalendarsRepository {
         Ok(row.map(|row| CalendarRow {
             id: row.id,
             name: row.name,
+            table_type: row.table_type,
             active: row.active != 0,
             created_at: row.created_at.clone(),
             updated_at: row.updated_at.clone(),
+            version: row.version,
         }))
     }
 
@@ -65,16 +77,24 @@ impl CalendarsRepository for MySqlCalendarsRepository {
         Ok(row.map(|row| CalendarRow {
             id: row.id,
             name: row.name,
+            table_type: row.table_type,
             active: row.active != 0,
             created_at: row.created_at.clone(),
             updated_at: row.updated_at.clone(),
+            version: row.version,
         }))
     }
 
-    async fn insert(&self, name: &str, active: bool) -> sqlx::Result<u32> {
+    async fn insert(
+        &self,
+        name: &str,
+        table_type: Option<&str>,
+        active: bool,
+    ) -> sqlx::Result<u32> {
         let response = sqlx::query!(
-            r#"INSERT INTO calendars (name, active) VALUES (?,?)"#,
+            r#"INSERT INTO calendars (name, table_type, active) VALUES (?,?,?)"#,
             name,
+            table_type,
             active
         )
         .execute(&self.pool)
@@ -83,8 +103,15 @@ impl CalendarsRepository for MySqlCalendarsRepository {
         Ok(response.last_insert_id() as u32)
     }
 
-    async fn update(&self, id: u32, name: Option<&str>, active: Option<bool>) -> sqlx::Result<u32> {
-        if name.is_none() && active.is_none() {
+    async fn update(
+        &self,
+        id: u32,
+        name: Option<&str>,
+        table_type: Option<&str>,
+        active: Option<bool>,
+        expected_version: u32,
+    ) -> sqlx::Result<u32> {
+        if name.is_none() && table_type.is_none() && active.is_none() {
             return Ok(0);
         }
 
@@ -92,13 +119,17 @@ impl CalendarsRepository for MySqlCalendarsRepository {
             r#"
             UPDATE calendars
             SET
-                name   = COALESCE(?, name),
-                active = COALESCE(?, active)
-            WHERE id = ?
+                name       = COALESCE(?, name),
+                table_type = COALESCE(?, table_type),
+                active     = COALESCE(?, active),
+                version    = version + 1
+            WHERE id = ? AND version = ?
             "#,
-            name,   // Option<&str> → NULL means "keep existing"
-            active, // Option<bool> → NULL means "keep existing"
-            id
+            name,       // Option<&str> → NULL means "keep existing"
+            table_type, // Option<&str> → NULL means "keep existing"
+            active,     // Option<bool> → NULL means "keep existing"
+            id,
+            expected_version
         )
         .execute(&self.pool)
         .await?;
@@ -106,10 +137,14 @@ impl CalendarsRepository for MySqlCalendarsRepository {
         Ok(res.rows_affected() as u32)
     }
 
-    async fn delete(&self, id: u32) -> sqlx::Result<bool> {
-        let result = sqlx::query!(r#"DELETE FROM calendars WHERE id = ?"#, id)
-            .execute(&self.pool)
-            .await?;
+    async fn delete(&self, id: u32, expected_version: u32) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"DELETE FROM calendars WHERE id = ? AND version = ?"#,
+            id,
+            expected_version
+        )
+        .execute(&self.pool)
+        .await?;
 
         Ok(result.rows_affected() > 0)
     }
@@ -3,62 +3,160 @@ use super::{
     model::CalendarRow,
 };
 use crate::{
+    auth::AdminUser,
     error::AppError,
+    etag::{IfMatch, WithETag},
+    features::audit::model::AuditActor,
+    infrastructure::response_cache::{CachedResource, CachedResponse},
+    openapi::ErrorResponse,
     response::{Created, NoContent},
     state::AppState,
 };
 use axum::{
     Json, Router,
     extract::{Path, State},
-    http::StatusCode,
+    http::HeaderMap,
+    response::Response,
     routing::get,
 };
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(paths(list, create, get_by_id, update, delete))]
+pub struct ApiDoc;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
-        .route("/api/calendars", get(list).post(create))
+        .route("/api/v1/calendars", get(list).post(create))
         .route(
-            "/api/calendars/{id}",
+            "/api/v1/calendars/{id}",
             get(get_by_id).put(update).delete(delete),
         )
 }
 
-async fn list(State(state): State<AppState>) -> Result<Json<Vec<CalendarResponse>>, AppError> {
-    let rows = state.calendars.list().await?;
-    Ok(Json(rows.into_iter().map(row_to_response).collect()))
+/// Cached; answers `If-None-Match` and `If-Modified-Since` with 304.
+#[utoipa::path(
+    get,
+    path = "/api/v1/calendars",
+    tag = "calendars",
+    responses(
+        (
+            status = 200,
+            body = Vec<CalendarResponse>,
+            headers(("ETag" = String), ("Last-Modified" = String))
+        ),
+        (status = 304, description = "Not modified")
+    )
+)]
+async fn list(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, AppError> {
+    let cached = state
+        .response_cache
+        .get_or_load(CachedResource::Calendars, async {
+            let rows = state.calendars.list().await?;
+            let body: Vec<CalendarResponse> = rows.into_iter().map(row_to_response).collect();
+            CachedResponse::json(&body)
+        })
+        .await?;
+    Ok(cached.respond(&headers))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/calendars/{id}",
+    tag = "calendars",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = CalendarResponse, headers(("ETag" = String))),
+        (status = 404, body = ErrorResponse)
+    )
+)]
 async fn get_by_id(
     State(state): State<AppState>,
     Path(id): Path<u32>,
-) -> Result<Json<CalendarResponse>, AppError> {
+) -> Result<WithETag<Json<CalendarResponse>>, AppError> {
     let row = state.calendars.get_by_id(id).await?;
-    Ok(Json(row_to_response(row)))
+    Ok(WithETag {
+        version: row.version,
+        body: Json(row_to_response(row)),
+    })
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/calendars",
+    tag = "calendars",
+    security(("admin" = [])),
+    request_body = CreateCalendarRequest,
+    responses((status = 201, body = CalendarResponse))
+)]
 async fn create(
+    _admin: AdminUser,
     State(state): State<AppState>,
+    actor: AuditActor,
     Json(body): Json<CreateCalendarRequest>,
 ) -> Result<Created<CalendarResponse>, AppError> {
-    let row = state.calendars.create(body).await?;
+    let row = state.calendars.create(body, &actor).await?;
 
     Ok(Created {
-        location: format!("/api/calendars/{}", row.id),
+        location: format!("/api/v1/calendars/{}", row.id),
         body: row_to_response(row),
     })
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/calendars/{id}",
+    tag = "calendars",
+    security(("admin" = [])),
+    params(
+        ("id" = u32, Path),
+        ("If-Match" = String, Header, description = "ETag from the last read")
+    ),
+    request_body = UpdateCalendarRequest,
+    responses(
+        (status = 200, body = CalendarResponse, headers(("ETag" = String))),
+        (status = 412, description = "Changed since it was read", body = ErrorResponse),
+        (status = 428, description = "If-Match missing", body = ErrorResponse)
+    )
+)]
 async fn update(
+    _admin: AdminUser,
     State(state): State<AppState>,
+    actor: AuditActor,
+    if_match: IfMatch,
     Path(id): Path<u32>,
     Json(body): Json<UpdateCalendarRequest>,
-) -> Result<(StatusCode, Json<CalendarRow>), AppError> {
-    let row = state.calendars.update(id, body).await?;
-    Ok((StatusCode::OK, Json(row)))
+) -> Result<WithETag<Json<CalendarResponse>>, AppError> {
+    let row = state.calendars.update(id, body, &if_match, &actor).await?;
+    Ok(WithETag {
+        version: row.version,
+        body: Json(row_to_response(row)),
+    })
 }
 
-async fn delete(State(state): State<AppState>, Path(id): Path<u32>) -> Result<NoContent, AppError> {
-    state.calendars.delete(id).await?;
+#[utoipa::path(
+    delete,
+    path = "/api/v1/calendars/{id}",
+    tag = "calendars",
+    security(("admin" = [])),
+    params(
+        ("id" = u32, Path),
+        ("If-Match" = String, Header, description = "ETag from the last read")
+    ),
+    responses(
+        (status = 204),
+        (status = 412, description = "Changed since it was read", body = ErrorResponse),
+        (status = 428, description = "If-Match missing", body = ErrorResponse)
+    )
+)]
+async fn delete(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+    actor: AuditActor,
+    if_match: IfMatch,
+    Path(id): Path<u32>,
+) -> Result<NoContent, AppError> {
+    state.calendars.delete(id, &if_match, &actor).await?;
     Ok(NoContent)
 }
 
@@ -66,6 +164,7 @@ fn row_to_response(row: CalendarRow) -> CalendarResponse {
     CalendarResponse {
         id: row.id,
         name: row.name,
+        table_type: row.table_type,
         active: row.active,
     }
 }
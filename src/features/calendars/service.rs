@@ -1,9 +1,18 @@
 use crate::{
     error::AppError,
-    features::calendars::{
-        data_transfer_objects::{CreateCalendarRequest, UpdateCalendarRequest},
-        model::CalendarRow,
+    etag::IfMatch,
+    features::{
+        audit::{
+            model::AuditActor,
+            service::{AuditService, snapshot},
+        },
+        calendars::{
+            data_transfer_objects::{CreateCalendarRequest, UpdateCalendarRequest},
+            model::CalendarRow,
+        },
+        events::bus::{AvailabilityEvent, EventBus},
     },
+    infrastructure::response_cache::{CachedResource, ResponseCache},
 };
 
 use super::repository::DynamicCalendarsRepository;
@@ -11,10 +20,23 @@ use super::repository::DynamicCalendarsRepository;
 #[derive(Clone)]
 pub struct CalendarsService {
     repository: DynamicCalendarsRepository,
+    events: EventBus,
+    audit: AuditService,
+    cache: ResponseCache,
 }
 impl CalendarsService {
-    pub fn new(repository: DynamicCalendarsRepository) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: DynamicCalendarsRepository,
+        events: EventBus,
+        audit: AuditService,
+        cache: ResponseCache,
+    ) -> Self {
+        Self {
+            repository,
+            events,
+            audit,
+            cache,
+        }
     }
 
     pub async fn list(&self) -> Result<Vec<CalendarRow>, AppError> {
@@ -31,20 +53,38 @@ impl CalendarsService {
         Ok(row)
     }
 
-    pub async fn create(&self, request: CreateCalendarRequest) -> Result<CalendarRow, AppError> {
+    pub async fn create(
+        &self,
+        request: CreateCalendarRequest,
+        actor: &AuditActor,
+    ) -> Result<CalendarRow, AppError> {
         if self.repository.get_by_name(&request.name).await?.is_some() {
             return Err(AppError::Conflict("Calendar name is already in use"));
         }
 
         let active = request.active.unwrap_or(true);
-        let id = self.repository.insert(&request.name, active).await?;
+        let id = self
+            .repository
+            .insert(&request.name, request.table_type.as_deref(), active)
+            .await?;
 
         let row = self
             .repository
             .get_by_id(id)
             .await?
             .ok_or(AppError::NotFound("Failed to fetch newly created calendar"))?;
+        self.cache.invalidate(CachedResource::Calendars);
 
+        self.audit
+            .record_change(
+                actor,
+                "calendar.created",
+                "calendar",
+                Some(id.to_string()),
+                None,
+                snapshot(&row),
+            )
+            .await?;
         Ok(row)
     }
 
@@ -52,8 +92,10 @@ impl CalendarsService {
         &self,
         id: u32,
         request: UpdateCalendarRequest,
+        if_match: &IfMatch,
+        actor: &AuditActor,
     ) -> Result<CalendarRow, AppError> {
-        if request.name.is_none() && request.active.is_none() {
+        if request.name.is_none() && request.table_type.is_none() && request.active.is_none() {
             return Err(AppError::BadRequest("No fields provided"));
         }
 
@@ -65,15 +107,53 @@ impl CalendarsService {
             }
         }
 
+        let previous = self
+            .repository
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Calendar not found"))?;
+        if_match.check(previous.version)?;
+
         let update_result = self
             .repository
-            .update(id, request.name.as_deref(), request.active)
+            .update(
+                id,
+                request.name.as_deref(),
+                request.table_type.as_deref(),
+                request.active,
+                previous.version,
+            )
             .await;
 
         match update_result {
+            Ok(0) => Err(AppError::PreconditionFailed(
+                "The calendar was changed by someone else",
+            )),
             Ok(_rows_affected) => {
-                let row = self.repository.get_by_id(id).await?;
-                row.ok_or(AppError::NotFound("Calendar not found"))
+                let row = self
+                    .repository
+                    .get_by_id(id)
+                    .await?
+                    .ok_or(AppError::NotFound("Calendar not found"))?;
+                self.cache.invalidate(CachedResource::Calendars);
+                if row.active != previous.active {
+                    self.events.publish(if row.active {
+                        AvailabilityEvent::CalendarUnblocked { calendar_id: id }
+                    } else {
+                        AvailabilityEvent::CalendarBlocked { calendar_id: id }
+                    });
+                }
+                self.audit
+                    .record_change(
+                        actor,
+                        "calendar.updated",
+                        "calendar",
+                        Some(id.to_string()),
+                        snapshot(&previous),
+                        snapshot(&row),
+                    )
+                    .await?;
+                Ok(row)
             }
             Err(sqlx::Error::Database(database_error))
                 if database_error.code().as_deref() == Some("1062") =>
@@ -87,16 +167,40 @@ impl CalendarsService {
         }
     }
 
-    pub async fn delete(&self, id: u32) -> Result<(), AppError> {
+    pub async fn delete(
+        &self,
+        id: u32,
+        if_match: &IfMatch,
+        actor: &AuditActor,
+    ) -> Result<(), AppError> {
+        let previous = self
+            .repository
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Calendar not found"))?;
+        if_match.check(previous.version)?;
         let n = self
             .repository
-            .delete(id)
+            .delete(id, previous.version)
             .await
             .map_err(AppError::Database)?;
 
         if n == false {
-            Err(AppError::NotFound("Calendar not found"))
+            Err(AppError::PreconditionFailed(
+                "The calendar was changed by someone else",
+            ))
         } else {
+            self.cache.invalidate(CachedResource::Calendars);
+            self.audit
+                .record_change(
+                    actor,
+                    "calendar.deleted",
+                    "calendar",
+                    Some(id.to_string()),
+                    snapshot(&previous),
+                    None,
+                )
+                .await?;
             Ok(())
         }
     }
@@ -8,4 +8,6 @@ pub struct ContactInfoRow {
     pub phone: String,
     pub email: String,
     pub updated_at: NaiveDateTime,
+    /// Bumped by every update; the contact info's ETag.
+    pub version: u32,
 }
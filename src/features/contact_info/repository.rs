@@ -6,7 +6,16 @@ use crate::features::contact_info::model::ContactInfoRow;
 #[async_trait]
 pub trait ContactInfoRepository: Send + Sync {
     async fn get(&self) -> sqlx::Result<Option<ContactInfoRow>>;
-    async fn set(&self, address: &str, phone: &str, email: &str) -> sqlx::Result<()>;
+    /// Creates the row; `false` when it already exists.
+    async fn insert(&self, address: &str, phone: &str, email: &str) -> sqlx::Result<bool>;
+    /// `false` when the row is no longer at `expected_version`.
+    async fn update(
+        &self,
+        address: &str,
+        phone: &str,
+        email: &str,
+        expected_version: u32,
+    ) -> sqlx::Result<bool>;
 }
 
 pub type DynamicContactInfoRepository = std::sync::Arc<dyn ContactInfoRepository>;
@@ -26,7 +35,7 @@ impl MySqlContactInfoRepository {
 impl ContactInfoRepository for MySqlContactInfoRepository {
     async fn get(&self) -> sqlx::Result<Option<ContactInfoRow>> {
         let row = sqlx::query!(
-            r#"SELECT id, address, phone, email, updated_at FROM contact_info WHERE id = 1"#
+            r#"SELECT id, address, phone, email, updated_at, version FROM contact_info WHERE id = 1"#
         )
         .fetch_optional(&self.pool)
         .await?;
@@ -37,26 +46,44 @@ impl ContactInfoRepository for MySqlContactInfoRepository {
             phone: row.phone,
             email: row.email,
             updated_at: row.updated_at.naive_utc(),
+            version: row.version,
         }))
     }
 
-    async fn set(&self, address: &str, phone: &str, email: &str) -> sqlx::Result<()> {
-        sqlx::query!(
+    async fn insert(&self, address: &str, phone: &str, email: &str) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"INSERT IGNORE INTO contact_info (id, address, phone, email) VALUES (1, ?, ?, ?)"#,
+            address,
+            phone,
+            email
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn update(
+        &self,
+        address: &str,
+        phone: &str,
+        email: &str,
+        expected_version: u32,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
             r#"
-            INSERT INTO contact_info (id, address, phone, email)
-            VALUES (1, ?, ?, ?)
-            ON DUPLICATE KEY UPDATE
-                address = VALUES(address),
-                phone = VALUES(phone),
-                email = VALUES(email)
+            UPDATE contact_info
+            SET address = ?, phone = ?, email = ?, version = version + 1
+            WHERE id = 1 AND version = ?
             "#,
             address,
             phone,
-            email
+            email,
+            expected_version
         )
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 }
@@ -1,34 +1,94 @@
-use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use axum::{Json, Router, extract::State, http::HeaderMap, response::Response, routing::get};
+use utoipa::OpenApi;
 
 use crate::{
+    auth::AdminUser,
     error::AppError,
-    features::contact_info::{
-        data_transfer_objects::{ContactInfoResponse, UpdateContactInfoRequest},
-        model::ContactInfoRow,
+    etag::{IfMatch, WithETag, etag},
+    features::{
+        audit::model::AuditActor,
+        contact_info::{
+            data_transfer_objects::{ContactInfoResponse, UpdateContactInfoRequest},
+            model::ContactInfoRow,
+        },
     },
+    infrastructure::response_cache::{CachedResource, CachedResponse},
+    openapi::ErrorResponse,
     state::AppState,
 };
 
+#[derive(OpenApi)]
+#[openapi(paths(get_contact_info, update_contact_info))]
+pub struct ApiDoc;
+
 pub fn routes() -> Router<AppState> {
     Router::new().route(
-        "/api/contact-info",
+        "/api/v1/contact-info",
         get(get_contact_info).put(update_contact_info),
     )
 }
 
+/// Keeps the version ETag, so it can be sent back in `If-Match`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/contact-info",
+    tag = "contact info",
+    responses(
+        (
+            status = 200,
+            body = ContactInfoResponse,
+            headers(("ETag" = String), ("Last-Modified" = String))
+        ),
+        (status = 304, description = "Not modified")
+    )
+)]
 async fn get_contact_info(
     State(app_state): State<AppState>,
-) -> Result<Json<ContactInfoResponse>, AppError> {
-    let row = app_state.contact_info.get().await?;
-    Ok(Json(convert_row_to_response(row)))
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let cached = app_state
+        .response_cache
+        .get_or_load(CachedResource::ContactInfo, async {
+            let row = app_state.contact_info.get().await?;
+            let version = row.version;
+            Ok(CachedResponse::json(&convert_row_to_response(row))?.with_etag(etag(version)))
+        })
+        .await?;
+    Ok(cached.respond(&headers))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/contact-info",
+    tag = "contact info",
+    security(("admin" = [])),
+    params((
+        "If-Match" = Option<String>,
+        Header,
+        description = "Required once contact info exists"
+    )),
+    request_body = UpdateContactInfoRequest,
+    responses(
+        (status = 200, body = ContactInfoResponse, headers(("ETag" = String))),
+        (status = 412, description = "Changed since it was read", body = ErrorResponse),
+        (status = 428, description = "If-Match missing", body = ErrorResponse)
+    )
+)]
 async fn update_contact_info(
+    _admin: AdminUser,
     State(app_state): State<AppState>,
+    actor: AuditActor,
+    if_match: Option<IfMatch>,
     Json(request_body): Json<UpdateContactInfoRequest>,
-) -> Result<(StatusCode, Json<ContactInfoRow>), AppError> {
-    let updated = app_state.contact_info.update(request_body).await?;
-    Ok((StatusCode::OK, Json(updated)))
+) -> Result<WithETag<Json<ContactInfoResponse>>, AppError> {
+    let updated = app_state
+        .contact_info
+        .update(request_body, if_match.as_ref(), &actor)
+        .await?;
+    Ok(WithETag {
+        version: updated.version,
+        body: Json(convert_row_to_response(updated)),
+    })
 }
 
 fn convert_row_to_response(row: ContactInfoRow) -> ContactInfoResponse {
@@ -1,8 +1,14 @@
 use crate::{
     error::AppError,
-    features::contact_info::{
-        data_transfer_objects::UpdateContactInfoRequest, model::ContactInfoRow,
+    etag::IfMatch,
+    features::{
+        audit::{
+            model::AuditActor,
+            service::{AuditService, snapshot},
+        },
+        contact_info::{data_transfer_objects::UpdateContactInfoRequest, model::ContactInfoRow},
     },
+    infrastructure::response_cache::{CachedResource, ResponseCache},
 };
 
 use super::repository::DynamicContactInfoRepository;
@@ -10,11 +16,21 @@ use super::repository::DynamicContactInfoRepository;
 #[derive(Clone)]
 pub struct ContactInfoService {
     repository: DynamicContactInfoRepository,
+    audit: AuditService,
+    cache: ResponseCache,
 }
 
 impl ContactInfoService {
-    pub fn new(repository: DynamicContactInfoRepository) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: DynamicContactInfoRepository,
+        audit: AuditService,
+        cache: ResponseCache,
+    ) -> Self {
+        Self {
+            repository,
+            audit,
+            cache,
+        }
     }
 
     pub async fn get(&self) -> Result<ContactInfoRow, AppError> {
@@ -27,9 +43,24 @@ impl ContactInfoService {
     pub async fn update(
         &self,
         request: UpdateContactInfoRequest,
+        if_match: Option<&IfMatch>,
+        actor: &AuditActor,
     ) -> Result<ContactInfoRow, AppError> {
         // Fetch current (if any) to support partial update. If missing, start with blanks.
         let current = self.repository.get().await?;
+        let previous = current.as_ref().and_then(snapshot);
+        // Creating the contact info needs no precondition; changing it does.
+        let expected_version = match &current {
+            Some(row) => {
+                if_match
+                    .ok_or(AppError::PreconditionRequired(
+                        "If-Match header is required",
+                    ))?
+                    .check(row.version)?;
+                Some(row.version)
+            }
+            None => None,
+        };
         let (mut address, mut phone, mut email) = if let Some(row) = current {
             (row.address, row.phone, row.email)
         } else {
@@ -53,8 +84,32 @@ impl ContactInfoService {
             ));
         }
 
-        self.repository.set(&address, &phone, &email).await?;
+        let written = match expected_version {
+            Some(version) => {
+                self.repository
+                    .update(&address, &phone, &email, version)
+                    .await?
+            }
+            None => self.repository.insert(&address, &phone, &email).await?,
+        };
+        if !written {
+            return Err(AppError::PreconditionFailed(
+                "The contact info was changed by someone else",
+            ));
+        }
+        self.cache.invalidate(CachedResource::ContactInfo);
         // Return fresh row (also gives updated_at)
-        self.get().await
+        let row = self.get().await?;
+        self.audit
+            .record_change(
+                actor,
+                "contact_info.updated",
+                "contact_info",
+                None,
+                previous,
+                snapshot(&row),
+            )
+            .await?;
+        Ok(row)
     }
 }
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CustomerResponse {
+    pub id: u32,
+    pub email: String,
+    pub name: String,
+    pub phone: String,
+    pub category: Option<String>,
+    pub email_verified: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetCustomerCategoryRequest {
+    /// `null` clears the category.
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterCustomerRequest {
+    pub email: String,
+    pub name: String,
+    pub phone: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoginResponse {
+    pub token: String, // send as `Authorization: Bearer <token>`
+    pub customer: CustomerResponse,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ResendVerificationRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PasswordResetConfirmRequest {
+    pub token: String,
+    pub password: String,
+}
@@ -0,0 +1,34 @@
+use chrono::NaiveDateTime;
+
+/// Not `Serialize`: the row carries the password hash and must only leave
+/// the service through `CustomerResponse`.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CustomerRow {
+    pub id: u32,
+    pub email: String,
+    pub name: String,
+    pub phone: String,
+    /// Pricing category such as `student` or `member`, set by staff.
+    pub category: Option<String>,
+    pub password_hash: String,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenPurpose {
+    Session,
+    VerifyEmail,
+    PasswordReset,
+}
+
+impl TokenPurpose {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TokenPurpose::Session => "session",
+            TokenPurpose::VerifyEmail => "verify_email",
+            TokenPurpose::PasswordReset => "password_reset",
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::{MySql, Pool};
+
+use super::model::{CustomerRow, TokenPurpose};
+use crate::{features::notifications::repository::enqueue, infrastructure::mailer::EmailMessage};
+
+#[async_trait]
+pub trait CustomersRepository: Send + Sync {
+    async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<CustomerRow>>;
+    async fn get_by_email(&self, email: &str) -> sqlx::Result<Option<CustomerRow>>;
+    async fn insert(
+        &self,
+        email: &str,
+        name: &str,
+        phone: &str,
+        password_hash: &str,
+    ) -> sqlx::Result<u32>;
+    async fn mark_email_verified(&self, id: u32) -> sqlx::Result<()>;
+    async fn set_password_hash(&self, id: u32, password_hash: &str) -> sqlx::Result<()>;
+    async fn set_category(&self, id: u32, category: Option<&str>) -> sqlx::Result<bool>;
+    async fn insert_token(
+        &self,
+        customer_id: u32,
+        purpose: TokenPurpose,
+        token_hash: &str,
+        expires_at: NaiveDateTime,
+    ) -> sqlx::Result<()>;
+    /// Customer id of an unexpired token.
+    async fn find_token(
+        &self,
+        token_hash: &str,
+        purpose: TokenPurpose,
+        now: NaiveDateTime,
+    ) -> sqlx::Result<Option<u32>>;
+    async fn delete_token(&self, token_hash: &str) -> sqlx::Result<bool>;
+    async fn delete_tokens(&self, customer_id: u32, purpose: TokenPurpose) -> sqlx::Result<()>;
+    /// Puts the email in the outbox; the dispatcher sends and retries it.
+    async fn queue_email(&self, email: &EmailMessage) -> sqlx::Result<()>;
+}
+
+pub type DynamicCustomersRepository = std::sync::Arc<dyn CustomersRepository>;
+
+#[derive(Clone)]
+pub struct MySqlCustomersRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlCustomersRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CustomersRepository for MySqlCustomersRepository {
+    async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<CustomerRow>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id, email, name, phone, category, password_hash, email_verified_at, created_at, updated_at
+            FROM customers
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| CustomerRow {
+            id: row.id,
+            email: row.email,
+            name: row.name,
+            phone: row.phone,
+            category: row.category,
+            password_hash: row.password_hash,
+            email_verified_at: row.email_verified_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }))
+    }
+
+    async fn get_by_email(&self, email: &str) -> sqlx::Result<Option<CustomerRow>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id, email, name, phone, category, password_hash, email_verified_at, created_at, updated_at
+            FROM customers
+            WHERE email = ?
+            "#,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| CustomerRow {
+            id: row.id,
+            email: row.email,
+            name: row.name,
+            phone: row.phone,
+            category: row.category,
+            password_hash: row.password_hash,
+            email_verified_at: row.email_verified_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }))
+    }
+
+    async fn insert(
+        &self,
+        email: &str,
+        name: &str,
+        phone: &str,
+        password_hash: &str,
+    ) -> sqlx::Result<u32> {
+        let result = sqlx::query!(
+            r#"INSERT INTO customers (email, name, phone, password_hash) VALUES (?, ?, ?, ?)"#,
+            email,
+            name,
+            phone,
+            password_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_id() as u32)
+    }
+
+    async fn mark_email_verified(&self, id: u32) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"UPDATE customers SET email_verified_at = COALESCE(email_verified_at, CURRENT_TIMESTAMP(6)) WHERE id = ?"#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn set_category(&self, id: u32, category: Option<&str>) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"UPDATE customers SET category = ? WHERE id = ?"#,
+            category,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn set_password_hash(&self, id: u32, password_hash: &str) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"UPDATE customers SET password_hash = ? WHERE id = ?"#,
+            password_hash,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn insert_token(
+        &self,
+        customer_id: u32,
+        purpose: TokenPurpose,
+        token_hash: &str,
+        expires_at: NaiveDateTime,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO customer_tokens (customer_id, purpose, token_hash, expires_at) VALUES (?, ?, ?, ?)"#,
+            customer_id,
+            purpose.as_str(),
+            token_hash,
+            expires_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_token(
+        &self,
+        token_hash: &str,
+        purpose: TokenPurpose,
+        now: NaiveDateTime,
+    ) -> sqlx::Result<Option<u32>> {
+        let row = sqlx::query!(
+            r#"SELECT customer_id FROM customer_tokens WHERE token_hash = ? AND purpose = ? AND expires_at > ?"#,
+            token_hash,
+            purpose.as_str(),
+            now
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| row.customer_id))
+    }
+
+    async fn delete_token(&self, token_hash: &str) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"DELETE FROM customer_tokens WHERE token_hash = ?"#,
+            token_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_tokens(&self, customer_id: u32, purpose: TokenPurpose) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"DELETE FROM customer_tokens WHERE customer_id = ? AND purpose = ?"#,
+            customer_id,
+            purpose.as_str()
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn queue_email(&self, email: &EmailMessage) -> sqlx::Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        enqueue(&mut conn, email).await
+    }
+}
@@ -0,0 +1,226 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing::{get, post, put},
+};
+use utoipa::OpenApi;
+
+use crate::{
+    auth::{AdminUser, CurrentCustomer, SessionToken},
+    error::AppError,
+    features::customers::{
+        data_transfer_objects::{
+            CustomerResponse, LoginRequest, LoginResponse, PasswordResetConfirmRequest,
+            PasswordResetRequest, RegisterCustomerRequest, ResendVerificationRequest,
+            SetCustomerCategoryRequest, VerifyEmailRequest,
+        },
+        model::CustomerRow,
+    },
+    openapi::ErrorResponse,
+    response::NoContent,
+    state::AppState,
+};
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    register,
+    verify_email,
+    resend_verification,
+    login,
+    logout,
+    request_password_reset,
+    confirm_password_reset,
+    me,
+    set_category
+))]
+pub struct ApiDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/customers/register", post(register))
+        .route("/api/v1/customers/verify-email", post(verify_email))
+        .route("/api/v1/customers/verify/resend", post(resend_verification))
+        .route("/api/v1/customers/login", post(login))
+        .route("/api/v1/customers/logout", post(logout))
+        .route(
+            "/api/v1/customers/password-reset",
+            post(request_password_reset),
+        )
+        .route(
+            "/api/v1/customers/password-reset/confirm",
+            post(confirm_password_reset),
+        )
+        .route("/api/v1/me", get(me))
+        .route("/api/v1/admin/customers/{id}/category", put(set_category))
+}
+
+/// Replies the same for new and already registered emails; the email sent
+/// tells the owner which it was.
+#[utoipa::path(
+    post,
+    path = "/api/v1/customers/register",
+    tag = "customers",
+    request_body = RegisterCustomerRequest,
+    responses((status = 204), (status = 400, body = ErrorResponse))
+)]
+async fn register(
+    State(app_state): State<AppState>,
+    Json(request_body): Json<RegisterCustomerRequest>,
+) -> Result<NoContent, AppError> {
+    app_state.customers.register(request_body).await?;
+    Ok(NoContent)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/customers/verify-email",
+    tag = "customers",
+    request_body = VerifyEmailRequest,
+    responses((status = 204))
+)]
+async fn verify_email(
+    State(app_state): State<AppState>,
+    Json(request_body): Json<VerifyEmailRequest>,
+) -> Result<NoContent, AppError> {
+    app_state
+        .customers
+        .verify_email(&request_body.token)
+        .await?;
+    Ok(NoContent)
+}
+
+/// Replies the same whether or not the email has an unverified account.
+#[utoipa::path(
+    post,
+    path = "/api/v1/customers/verify/resend",
+    tag = "customers",
+    request_body = ResendVerificationRequest,
+    responses((status = 204))
+)]
+async fn resend_verification(
+    State(app_state): State<AppState>,
+    Json(request_body): Json<ResendVerificationRequest>,
+) -> Result<NoContent, AppError> {
+    app_state
+        .customers
+        .resend_verification(&request_body.email)
+        .await?;
+    Ok(NoContent)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/customers/login",
+    tag = "customers",
+    request_body = LoginRequest,
+    responses((status = 200, body = LoginResponse), (status = 401, body = ErrorResponse))
+)]
+async fn login(
+    State(app_state): State<AppState>,
+    Json(request_body): Json<LoginRequest>,
+) -> Result<Json<LoginResponse>, AppError> {
+    let (token, customer) = app_state
+        .customers
+        .login(&request_body.email, &request_body.password)
+        .await?;
+    Ok(Json(LoginResponse {
+        token,
+        customer: convert_row_to_response(customer),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/customers/logout",
+    tag = "customers",
+    security(("customer" = [])),
+    responses((status = 204))
+)]
+async fn logout(
+    State(app_state): State<AppState>,
+    SessionToken(token): SessionToken,
+) -> Result<NoContent, AppError> {
+    app_state.customers.logout(&token).await?;
+    Ok(NoContent)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/customers/password-reset",
+    tag = "customers",
+    request_body = PasswordResetRequest,
+    responses((status = 204))
+)]
+async fn request_password_reset(
+    State(app_state): State<AppState>,
+    Json(request_body): Json<PasswordResetRequest>,
+) -> Result<NoContent, AppError> {
+    app_state
+        .customers
+        .request_password_reset(&request_body.email)
+        .await?;
+    Ok(NoContent)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/customers/password-reset/confirm",
+    tag = "customers",
+    request_body = PasswordResetConfirmRequest,
+    responses((status = 204))
+)]
+async fn confirm_password_reset(
+    State(app_state): State<AppState>,
+    Json(request_body): Json<PasswordResetConfirmRequest>,
+) -> Result<NoContent, AppError> {
+    app_state
+        .customers
+        .reset_password(&request_body.token, request_body.password)
+        .await?;
+    Ok(NoContent)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me",
+    tag = "customers",
+    security(("customer" = [])),
+    responses((status = 200, body = CustomerResponse))
+)]
+async fn me(CurrentCustomer(customer): CurrentCustomer) -> Json<CustomerResponse> {
+    Json(convert_row_to_response(customer))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/customers/{id}/category",
+    tag = "customers",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    request_body = SetCustomerCategoryRequest,
+    responses((status = 200, body = CustomerResponse))
+)]
+async fn set_category(
+    admin: AdminUser,
+    State(app_state): State<AppState>,
+    Path(id): Path<u32>,
+    Json(request_body): Json<SetCustomerCategoryRequest>,
+) -> Result<Json<CustomerResponse>, AppError> {
+    let customer = app_state
+        .customers
+        .set_category(id, request_body.category)
+        .await?;
+    tracing::info!(admin = %admin.name, id, category = ?customer.category, "customer category set");
+    Ok(Json(convert_row_to_response(customer)))
+}
+
+fn convert_row_to_response(row: CustomerRow) -> CustomerResponse {
+    CustomerResponse {
+        id: row.id,
+        email: row.email,
+        name: row.name,
+        phone: row.phone,
+        category: row.category,
+        email_verified: row.email_verified_at.is_some(),
+    }
+}
@@ -0,0 +1,340 @@
+use chrono::{Duration, Utc};
+
+use super::{
+    model::{CustomerRow, TokenPurpose},
+    repository::DynamicCustomersRepository,
+};
+use crate::{
+    error::AppError,
+    features::customers::data_transfer_objects::RegisterCustomerRequest,
+    infrastructure::{
+        mailer::EmailMessage,
+        security::{generate_token, hash_password, hash_token, verify_password},
+    },
+};
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+fn session_ttl() -> Duration {
+    Duration::days(30)
+}
+fn verify_email_ttl() -> Duration {
+    Duration::hours(48)
+}
+fn password_reset_ttl() -> Duration {
+    Duration::hours(1)
+}
+
+#[derive(Clone)]
+pub struct CustomersService {
+    repository: DynamicCustomersRepository,
+    frontend_url: String,
+}
+
+impl CustomersService {
+    pub fn new(repository: DynamicCustomersRepository, frontend_url: String) -> Self {
+        Self {
+            repository,
+            frontend_url,
+        }
+    }
+
+    /// Replies the same whether or not the email is registered, so the
+    /// endpoint doesn't reveal which emails exist; the email tells the owner.
+    /// An address that is registered but not verified gets a new
+    /// verification link instead.
+    pub async fn register(&self, request: RegisterCustomerRequest) -> Result<(), AppError> {
+        let email = normalize_email(&request.email)?;
+        let name = request.name.trim();
+        let phone = request.phone.trim();
+        if name.is_empty() || phone.is_empty() {
+            return Err(AppError::BadRequest("name and phone must not be empty"));
+        }
+        check_password(&request.password)?;
+
+        // Hashed either way, so the reply takes as long for a known email.
+        let password_hash = hash_password_blocking(request.password).await?;
+        let id = match self
+            .repository
+            .insert(&email, name, phone, &password_hash)
+            .await
+        {
+            Ok(id) => id,
+            Err(sqlx::Error::Database(database_error))
+                if database_error.code().as_deref() == Some("1062") =>
+            {
+                return self.notify_already_registered(&email).await;
+            }
+            Err(error) => return Err(AppError::Database(error)),
+        };
+
+        let customer = self
+            .repository
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Failed to fetch newly created customer"))?;
+        self.send_verification_email(&customer).await
+    }
+
+    /// Sends a new verification link, replacing earlier ones. Always
+    /// succeeds so the endpoint doesn't reveal which emails exist.
+    pub async fn resend_verification(&self, email: &str) -> Result<(), AppError> {
+        let Ok(email) = normalize_email(email) else {
+            return Ok(());
+        };
+        match self.repository.get_by_email(&email).await? {
+            Some(customer) if customer.email_verified_at.is_none() => {
+                self.send_verification_email(&customer).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn verify_email(&self, token: &str) -> Result<(), AppError> {
+        let customer_id = self
+            .consume_token(token, TokenPurpose::VerifyEmail)
+            .await?
+            .ok_or(AppError::BadRequest(
+                "Verification link is invalid or expired",
+            ))?;
+        Ok(self.repository.mark_email_verified(customer_id).await?)
+    }
+
+    /// Returns a new session token for the customer.
+    pub async fn login(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> Result<(String, CustomerRow), AppError> {
+        let email = normalize_email(email)?;
+        let customer = self.repository.get_by_email(&email).await?;
+
+        let password_ok = match &customer {
+            Some(customer) => {
+                verify_password_blocking(password.to_string(), customer.password_hash.clone())
+                    .await?
+            }
+            None => false,
+        };
+        let customer = match customer {
+            Some(customer) if password_ok => customer,
+            _ => return Err(AppError::Unauthorized("Invalid email or password")),
+        };
+        if customer.email_verified_at.is_none() {
+            return Err(AppError::Forbidden("Email address has not been verified"));
+        }
+
+        let token = self
+            .issue_token(customer.id, TokenPurpose::Session, session_ttl())
+            .await?;
+        Ok((token, customer))
+    }
+
+    pub async fn logout(&self, session_token: &str) -> Result<(), AppError> {
+        self.repository
+            .delete_token(&hash_token(session_token))
+            .await?;
+        Ok(())
+    }
+
+    /// Always succeeds so the endpoint doesn't reveal which emails exist.
+    pub async fn request_password_reset(&self, email: &str) -> Result<(), AppError> {
+        let Ok(email) = normalize_email(email) else {
+            return Ok(());
+        };
+        let Some(customer) = self.repository.get_by_email(&email).await? else {
+            return Ok(());
+        };
+
+        self.repository
+            .delete_tokens(customer.id, TokenPurpose::PasswordReset)
+            .await?;
+        let token = self
+            .issue_token(
+                customer.id,
+                TokenPurpose::PasswordReset,
+                password_reset_ttl(),
+            )
+            .await?;
+
+        self.queue(EmailMessage {
+            to: customer.email,
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Hi {},\n\nSomeone asked to reset the password of your account. If it was you, open the link below within an hour:\n\n{}/reset-password?token={}\n\nIf it wasn't, you can ignore this message.",
+                customer.name, self.frontend_url, token
+            ),
+            attachments: Vec::new(),
+        })
+        .await
+    }
+
+    /// Sets a new password and signs the customer out everywhere.
+    pub async fn reset_password(&self, token: &str, new_password: String) -> Result<(), AppError> {
+        check_password(&new_password)?;
+        let customer_id = self
+            .consume_token(token, TokenPurpose::PasswordReset)
+            .await?
+            .ok_or(AppError::BadRequest("Reset link is invalid or expired"))?;
+
+        let password_hash = hash_password_blocking(new_password).await?;
+        self.repository
+            .set_password_hash(customer_id, &password_hash)
+            .await?;
+        self.repository
+            .delete_tokens(customer_id, TokenPurpose::Session)
+            .await?;
+        // Following the reset link proves the customer owns the address.
+        self.repository.mark_email_verified(customer_id).await?;
+        Ok(())
+    }
+
+    /// Resolves a session token to its customer.
+    pub async fn authenticate(&self, session_token: &str) -> Result<CustomerRow, AppError> {
+        let customer_id = self
+            .repository
+            .find_token(
+                &hash_token(session_token),
+                TokenPurpose::Session,
+                Utc::now().naive_utc(),
+            )
+            .await?
+            .ok_or(AppError::Unauthorized("Session is invalid or expired"))?;
+        self.repository
+            .get_by_id(customer_id)
+            .await?
+            .ok_or(AppError::Unauthorized("Session is invalid or expired"))
+    }
+
+    /// Sets or clears the pricing category. Returns the updated customer.
+    pub async fn set_category(
+        &self,
+        id: u32,
+        category: Option<String>,
+    ) -> Result<CustomerRow, AppError> {
+        let category = category
+            .map(|c| c.trim().to_lowercase())
+            .filter(|c| !c.is_empty());
+        if !self
+            .repository
+            .set_category(id, category.as_deref())
+            .await?
+        {
+            return Err(AppError::NotFound("Customer not found"));
+        }
+        self.repository
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Customer not found"))
+    }
+
+    async fn send_verification_email(&self, customer: &CustomerRow) -> Result<(), AppError> {
+        self.repository
+            .delete_tokens(customer.id, TokenPurpose::VerifyEmail)
+            .await?;
+        let token = self
+            .issue_token(customer.id, TokenPurpose::VerifyEmail, verify_email_ttl())
+            .await?;
+        self.queue(EmailMessage {
+            to: customer.email.clone(),
+            subject: "Confirm your email address".to_string(),
+            body: format!(
+                "Hi {},\n\nPlease confirm your email address by opening the link below:\n\n{}/verify-email?token={}",
+                customer.name, self.frontend_url, token
+            ),
+            attachments: Vec::new(),
+        })
+        .await
+    }
+
+    /// Someone tried to register an address that already has an account.
+    async fn notify_already_registered(&self, email: &str) -> Result<(), AppError> {
+        let Some(customer) = self.repository.get_by_email(email).await? else {
+            return Ok(());
+        };
+        if customer.email_verified_at.is_none() {
+            return self.send_verification_email(&customer).await;
+        }
+        self.queue(EmailMessage {
+            to: customer.email,
+            subject: "You already have an account".to_string(),
+            body: format!(
+                "Hi {},\n\nSomeone tried to create an account with this email address, but you already have one. If it was you, sign in, or reset your password here:\n\n{}/reset-password\n\nIf it wasn't, you can ignore this message.",
+                customer.name, self.frontend_url
+            ),
+            attachments: Vec::new(),
+        })
+        .await
+    }
+
+    async fn issue_token(
+        &self,
+        customer_id: u32,
+        purpose: TokenPurpose,
+        ttl: Duration,
+    ) -> Result<String, AppError> {
+        let token = generate_token();
+        let expires_at = (Utc::now() + ttl).naive_utc();
+        self.repository
+            .insert_token(customer_id, purpose, &hash_token(&token), expires_at)
+            .await?;
+        Ok(token)
+    }
+
+    /// Single-use tokens are deleted as they are redeemed.
+    async fn consume_token(
+        &self,
+        token: &str,
+        purpose: TokenPurpose,
+    ) -> Result<Option<u32>, AppError> {
+        let token_hash = hash_token(token);
+        let customer_id = self
+            .repository
+            .find_token(&token_hash, purpose, Utc::now().naive_utc())
+            .await?;
+        if customer_id.is_some() && !self.repository.delete_token(&token_hash).await? {
+            // Redeemed concurrently by another request.
+            return Ok(None);
+        }
+        Ok(customer_id)
+    }
+
+    async fn queue(&self, message: EmailMessage) -> Result<(), AppError> {
+        Ok(self.repository.queue_email(&message).await?)
+    }
+}
+
+fn normalize_email(email: &str) -> Result<String, AppError> {
+    let email = email.trim().to_lowercase();
+    match email.split_once('@') {
+        Some((local, domain)) if !local.is_empty() && domain.contains('.') => Ok(email),
+        _ => Err(AppError::BadRequest("email is not valid")),
+    }
+}
+
+fn check_password(password: &str) -> Result<(), AppError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(AppError::BadRequest(
+            "password must be at least 8 characters",
+        ));
+    }
+    Ok(())
+}
+
+// Argon2 is deliberately slow; keep it off the async worker threads.
+async fn hash_password_blocking(password: String) -> Result<String, AppError> {
+    tokio::task::spawn_blocking(move || hash_password(&password))
+        .await
+        .ok()
+        .flatten()
+        .ok_or(AppError::Internal("Failed to hash password"))
+}
+
+async fn verify_password_blocking(
+    password: String,
+    password_hash: String,
+) -> Result<bool, AppError> {
+    tokio::task::spawn_blocking(move || verify_password(&password, &password_hash))
+        .await
+        .map_err(|_| AppError::Internal("Failed to verify password"))
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+
+use crate::features::bookings::model::{BookingHoldRow, BookingRow};
+
+/// Events held for slow subscribers before they start missing some.
+const CAPACITY: usize = 256;
+
+/// Change to what can be booked. Sent to the public, so it carries no
+/// customer details.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AvailabilityEvent {
+    BookingCreated {
+        booking_id: u32,
+        calendar_id: u32,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    BookingCancelled {
+        booking_id: u32,
+        calendar_id: u32,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    BookingRescheduled {
+        booking_id: u32,
+        calendar_id: u32,
+        previous_start: DateTime<Utc>,
+        previous_end: DateTime<Utc>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    /// A slot was reserved while a customer fills in the booking form.
+    HoldCreated {
+        calendar_id: u32,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    HoldReleased {
+        calendar_id: u32,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    /// Sent when an expired hold is swept, which may be up to a minute after
+    /// it stopped blocking the slot.
+    HoldExpired {
+        calendar_id: u32,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    /// The table was taken out of use.
+    CalendarBlocked {
+        calendar_id: u32,
+    },
+    CalendarUnblocked {
+        calendar_id: u32,
+    },
+}
+
+impl AvailabilityEvent {
+    pub fn booking_created(row: &BookingRow) -> Self {
+        AvailabilityEvent::BookingCreated {
+            booking_id: row.id,
+            calendar_id: row.calendar_id,
+            start: utc(row.starts_at_utc),
+            end: utc(row.ends_at_utc),
+        }
+    }
+
+    pub fn booking_cancelled(row: &BookingRow) -> Self {
+        AvailabilityEvent::BookingCancelled {
+            booking_id: row.id,
+            calendar_id: row.calendar_id,
+            start: utc(row.starts_at_utc),
+            end: utc(row.ends_at_utc),
+        }
+    }
+
+    pub fn booking_rescheduled(previous: &BookingRow, row: &BookingRow) -> Self {
+        AvailabilityEvent::BookingRescheduled {
+            booking_id: row.id,
+            calendar_id: row.calendar_id,
+            previous_start: utc(previous.starts_at_utc),
+            previous_end: utc(previous.ends_at_utc),
+            start: utc(row.starts_at_utc),
+            end: utc(row.ends_at_utc),
+        }
+    }
+
+    pub fn hold_created(hold: &BookingHoldRow) -> Self {
+        AvailabilityEvent::HoldCreated {
+            calendar_id: hold.calendar_id,
+            start: utc(hold.starts_at_utc),
+            end: utc(hold.ends_at_utc),
+        }
+    }
+
+    pub fn hold_released(hold: &BookingHoldRow) -> Self {
+        AvailabilityEvent::HoldReleased {
+            calendar_id: hold.calendar_id,
+            start: utc(hold.starts_at_utc),
+            end: utc(hold.ends_at_utc),
+        }
+    }
+
+    pub fn hold_expired(hold: &BookingHoldRow) -> Self {
+        AvailabilityEvent::HoldExpired {
+            calendar_id: hold.calendar_id,
+            start: utc(hold.starts_at_utc),
+            end: utc(hold.ends_at_utc),
+        }
+    }
+
+    pub fn calendar_id(&self) -> u32 {
+        match self {
+            AvailabilityEvent::BookingCreated { calendar_id, .. }
+            | AvailabilityEvent::BookingCancelled { calendar_id, .. }
+            | AvailabilityEvent::BookingRescheduled { calendar_id, .. }
+            | AvailabilityEvent::HoldCreated { calendar_id, .. }
+            | AvailabilityEvent::HoldReleased { calendar_id, .. }
+            | AvailabilityEvent::HoldExpired { calendar_id, .. }
+            | AvailabilityEvent::CalendarBlocked { calendar_id }
+            | AvailabilityEvent::CalendarUnblocked { calendar_id } => *calendar_id,
+        }
+    }
+
+    /// SSE event name, same as the `type` field.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AvailabilityEvent::BookingCreated { .. } => "booking_created",
+            AvailabilityEvent::BookingCancelled { .. } => "booking_cancelled",
+            AvailabilityEvent::BookingRescheduled { .. } => "booking_rescheduled",
+            AvailabilityEvent::HoldCreated { .. } => "hold_created",
+            AvailabilityEvent::HoldReleased { .. } => "hold_released",
+            AvailabilityEvent::HoldExpired { .. } => "hold_expired",
+            AvailabilityEvent::CalendarBlocked { .. } => "calendar_blocked",
+            AvailabilityEvent::CalendarUnblocked { .. } => "calendar_unblocked",
+        }
+    }
+}
+
+fn utc(t: NaiveDateTime) -> DateTime<Utc> {
+    DateTime::<Utc>::from_naive_utc_and_offset(t, Utc)
+}
+
+/// In-process fan-out of availability changes. Services publish after their
+/// write has committed; every open event stream holds a subscription.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<AvailabilityEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: AvailabilityEvent) {
+        // An error only means nobody is listening right now.
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AvailabilityEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
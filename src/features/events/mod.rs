@@ -0,0 +1,4 @@
+pub mod bus;
+pub mod routes;
+
+pub use routes::{ApiDoc, routes};
@@ -0,0 +1,87 @@
+use std::convert::Infallible;
+
+use tokio::sync::broadcast;
+
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+};
+use tokio_stream::{
+    Stream, StreamExt,
+    wrappers::{BroadcastStream, errors::BroadcastStreamRecvError},
+};
+use utoipa::OpenApi;
+
+use super::bus::AvailabilityEvent;
+use crate::{error::AppError, state::AppState};
+
+#[derive(OpenApi)]
+#[openapi(paths(venue_events, calendar_events))]
+pub struct ApiDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/events", get(venue_events))
+        .route("/api/v1/calendars/{id}/events", get(calendar_events))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/events",
+    tag = "events",
+    responses((
+        status = 200,
+        description = "Server-sent events; each `data` is one event, named after its `type`",
+        content_type = "text/event-stream",
+        body = AvailabilityEvent
+    ))
+)]
+async fn venue_events(
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    event_stream(state.events.subscribe(), None)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/calendars/{id}/events",
+    tag = "events",
+    params(("id" = u32, Path)),
+    responses((
+        status = 200,
+        description = "Server-sent events; each `data` is one event, named after its `type`",
+        content_type = "text/event-stream",
+        body = AvailabilityEvent
+    ))
+)]
+async fn calendar_events(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    state.calendars.get_by_id(id).await?;
+    Ok(event_stream(state.events.subscribe(), Some(id)))
+}
+
+/// Subscribers that fall behind get a `resync` event and should refetch
+/// availability instead of relying on the events they missed.
+fn event_stream(
+    receiver: broadcast::Receiver<AvailabilityEvent>,
+    calendar_id: Option<u32>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = BroadcastStream::new(receiver).filter_map(move |received| match received {
+        Ok(event) if calendar_id.is_none_or(|id| id == event.calendar_id()) => {
+            Some(Ok(Event::default()
+                .event(event.name())
+                .json_data(&event)
+                .unwrap_or_else(|_| Event::default().event("resync"))))
+        }
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(_)) => {
+            Some(Ok(Event::default().event("resync").data("{}")))
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
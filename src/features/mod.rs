@@ -1,5 +1,15 @@
+pub mod audit;
+pub mod booking_limits;
+pub mod booking_policies;
 pub mod bookings;
+pub mod calendar_feeds;
 pub mod calendars;
 pub mod contact_info;
+pub mod customers;
+pub mod events;
 pub mod notices;
+pub mod notifications;
 pub mod opening_hours;
+pub mod payments;
+pub mod pricing;
+pub mod vouchers;
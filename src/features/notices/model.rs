@@ -8,4 +8,6 @@ pub struct NoticeRow {
     pub content: String,
     pub active: bool,
     pub created_at: NaiveDateTime,
+    /// Bumped by every update; the notice's ETag.
+    pub version: u32,
 }
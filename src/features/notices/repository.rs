@@ -14,8 +14,9 @@ pub trait NoticesRepository: Send + Sync {
         title: Option<&str>,
         content: Option<&str>,
         active: Option<bool>,
+        expected_version: u32,
     ) -> sqlx::Result<u32>;
-    async fn delete(&self, id: u32) -> sqlx::Result<bool>;
+    async fn delete(&self, id: u32, expected_version: u32) -> sqlx::Result<bool>;
 }
 
 pub type DynamicNoticesRepository = std::sync::Arc<dyn NoticesRepository>;
@@ -34,9 +35,10 @@ impl MySqlNoticesRepository {
 #[async_trait]
 impl NoticesRepository for MySqlNoticesRepository {
     async fn list(&self) -> sqlx::Result<Vec<NoticeRow>> {
-        let rows = sqlx::query!(r#"SELECT id, title, content, active, created_at FROM notices"#)
-            .fetch_all(&self.pool)
-            .await?;
+        let rows =
+            sqlx::query!(r#"SELECT id, title, content, active, created_at, version FROM notices"#)
+                .fetch_all(&self.pool)
+                .await?;
 
         Ok(rows
             .into_iter()
@@ -46,13 +48,14 @@ impl NoticesRepository for MySqlNoticesRepository {
                 content: row.content,
                 active: row.active != 0,
                 created_at: row.created_at.clone(),
+                version: row.version,
             })
             .collect())
     }
 
     async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<NoticeRow>> {
         let row = sqlx::query!(
-            r#"SELECT id, title, content, active, created_at FROM notices WHERE id = ?"#,
+            r#"SELECT id, title, content, active, created_at, version FROM notices WHERE id = ?"#,
             id
         )
         .fetch_optional(&self.pool)
@@ -64,6 +67,7 @@ impl NoticesRepository for MySqlNoticesRepository {
             content: row.content,
             active: row.active != 0,
             created_at: row.created_at.clone(),
+            version: row.version,
         }))
     }
 
@@ -86,17 +90,19 @@ impl NoticesRepository for MySqlNoticesRepository {
         title: Option<&str>,
         content: Option<&str>,
         active: Option<bool>,
+        expected_version: u32,
     ) -> sqlx::Result<u32> {
         if title.is_none() && content.is_none() && active.is_none() {
             return Ok(0);
         }
 
         let result = sqlx::query!(
-            r#"UPDATE notices SET title = COALESCE(?, title), content = COALESCE(?, content), active = COALESCE(?, active) WHERE id = ?"#,
+            r#"UPDATE notices SET title = COALESCE(?, title), content = COALESCE(?, content), active = COALESCE(?, active), version = version + 1 WHERE id = ? AND version = ?"#,
             title,
             content,
             active,
-            id
+            id,
+            expected_version
         )
         .execute(&self.pool)
         .await?;
@@ -104,10 +110,14 @@ impl NoticesRepository for MySqlNoticesRepository {
         Ok(result.rows_affected() as u32)
     }
 
-    async fn delete(&self, id: u32) -> sqlx::Result<bool> {
-        let result = sqlx::query!(r#"DELETE FROM notices WHERE id = ?"#, id)
-            .execute(&self.pool)
-            .await?;
+    async fn delete(&self, id: u32, expected_version: u32) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"DELETE FROM notices WHERE id = ? AND version = ?"#,
+            id,
+            expected_version
+        )
+        .execute(&self.pool)
+        .await?;
 
         Ok(result.rows_affected() > 0)
     }
@@ -1,65 +1,176 @@
 use axum::{
     Json, Router,
     extract::{Path, State},
-    http::StatusCode,
-    routing::{get, put},
+    http::HeaderMap,
+    response::Response,
+    routing::get,
 };
+use utoipa::OpenApi;
 
 use crate::{
+    auth::AdminUser,
     error::AppError,
-    features::notices::{
-        data_transfer_objects::{CreateNoticeRequest, NoticeResponse, UpdateNoticeRequest},
-        model::NoticeRow,
+    etag::{IfMatch, WithETag},
+    features::{
+        audit::model::AuditActor,
+        notices::{
+            data_transfer_objects::{CreateNoticeRequest, NoticeResponse, UpdateNoticeRequest},
+            model::NoticeRow,
+        },
     },
+    infrastructure::response_cache::{CachedResource, CachedResponse},
+    openapi::ErrorResponse,
     response::{Created, NoContent},
     state::AppState,
 };
 
+#[derive(OpenApi)]
+#[openapi(paths(list_notices, create_notice, get_notice, update_notice, delete_notice))]
+pub struct ApiDoc;
+
 pub fn routes() -> Router<AppState> {
     Router::new()
-        .route("/api/notices", get(list_notices).post(create_notice))
+        .route("/api/v1/notices", get(list_notices).post(create_notice))
         .route(
-            "/api/notices/{id}",
-            put(update_notice).delete(delete_notice),
+            "/api/v1/notices/{id}",
+            get(get_notice).put(update_notice).delete(delete_notice),
         )
 }
 
+/// Cached; answers `If-None-Match` and `If-Modified-Since` with 304.
+#[utoipa::path(
+    get,
+    path = "/api/v1/notices",
+    tag = "notices",
+    responses(
+        (
+            status = 200,
+            body = Vec<NoticeResponse>,
+            headers(("ETag" = String), ("Last-Modified" = String))
+        ),
+        (status = 304, description = "Not modified")
+    )
+)]
 async fn list_notices(
     State(app_state): State<AppState>,
-) -> Result<Json<Vec<NoticeResponse>>, AppError> {
-    let notice_rows = app_state.notices.list().await?;
-    let responses = notice_rows
-        .into_iter()
-        .map(convert_row_to_response)
-        .collect();
-    Ok(Json(responses))
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let cached = app_state
+        .response_cache
+        .get_or_load(CachedResource::Notices, async {
+            let notice_rows = app_state.notices.list().await?;
+            let responses: Vec<NoticeResponse> = notice_rows
+                .into_iter()
+                .map(convert_row_to_response)
+                .collect();
+            CachedResponse::json(&responses)
+        })
+        .await?;
+    Ok(cached.respond(&headers))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/notices/{id}",
+    tag = "notices",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = NoticeResponse, headers(("ETag" = String))),
+        (status = 404, body = ErrorResponse)
+    )
+)]
+async fn get_notice(
+    State(app_state): State<AppState>,
+    Path(notice_id): Path<u32>,
+) -> Result<WithETag<Json<NoticeResponse>>, AppError> {
+    let notice_row = app_state.notices.get(notice_id).await?;
+    Ok(WithETag {
+        version: notice_row.version,
+        body: Json(convert_row_to_response(notice_row)),
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/notices",
+    tag = "notices",
+    security(("admin" = [])),
+    request_body = CreateNoticeRequest,
+    responses((status = 201, body = NoticeResponse))
+)]
 async fn create_notice(
+    _admin: AdminUser,
     State(app_state): State<AppState>,
+    actor: AuditActor,
     Json(request_body): Json<CreateNoticeRequest>,
 ) -> Result<Created<NoticeResponse>, AppError> {
-    let notice_row = app_state.notices.create(request_body).await?;
+    let notice_row = app_state.notices.create(request_body, &actor).await?;
     Ok(Created {
-        location: format!("/api/notices/{}", notice_row.id),
+        location: format!("/api/v1/notices/{}", notice_row.id),
         body: convert_row_to_response(notice_row),
     })
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/notices/{id}",
+    tag = "notices",
+    security(("admin" = [])),
+    params(
+        ("id" = u32, Path),
+        ("If-Match" = String, Header, description = "ETag from the last read")
+    ),
+    request_body = UpdateNoticeRequest,
+    responses(
+        (status = 200, body = NoticeResponse, headers(("ETag" = String))),
+        (status = 412, description = "Changed since it was read", body = ErrorResponse),
+        (status = 428, description = "If-Match missing", body = ErrorResponse)
+    )
+)]
 async fn update_notice(
+    _admin: AdminUser,
     State(app_state): State<AppState>,
+    actor: AuditActor,
+    if_match: IfMatch,
     Path(notice_id): Path<u32>,
     Json(request_body): Json<UpdateNoticeRequest>,
-) -> Result<(StatusCode, Json<NoticeRow>), AppError> {
-    let updated_row = app_state.notices.update(notice_id, request_body).await?;
-    Ok((StatusCode::OK, Json(updated_row)))
+) -> Result<WithETag<Json<NoticeResponse>>, AppError> {
+    let updated_row = app_state
+        .notices
+        .update(notice_id, request_body, &if_match, &actor)
+        .await?;
+    Ok(WithETag {
+        version: updated_row.version,
+        body: Json(convert_row_to_response(updated_row)),
+    })
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/notices/{id}",
+    tag = "notices",
+    security(("admin" = [])),
+    params(
+        ("id" = u32, Path),
+        ("If-Match" = String, Header, description = "ETag from the last read")
+    ),
+    responses(
+        (status = 204),
+        (status = 412, description = "Changed since it was read", body = ErrorResponse),
+        (status = 428, description = "If-Match missing", body = ErrorResponse)
+    )
+)]
 async fn delete_notice(
+    _admin: AdminUser,
     State(app_state): State<AppState>,
+    actor: AuditActor,
+    if_match: IfMatch,
     Path(notice_id): Path<u32>,
 ) -> Result<NoContent, AppError> {
-    app_state.notices.delete(notice_id).await?;
+    app_state
+        .notices
+        .delete(notice_id, &if_match, &actor)
+        .await?;
     Ok(NoContent)
 }
 
@@ -1,9 +1,17 @@
 use crate::{
     error::AppError,
-    features::notices::{
-        data_transfer_objects::{CreateNoticeRequest, UpdateNoticeRequest},
-        model::NoticeRow,
+    etag::IfMatch,
+    features::{
+        audit::{
+            model::AuditActor,
+            service::{AuditService, snapshot},
+        },
+        notices::{
+            data_transfer_objects::{CreateNoticeRequest, UpdateNoticeRequest},
+            model::NoticeRow,
+        },
     },
+    infrastructure::response_cache::{CachedResource, ResponseCache},
 };
 
 use super::repository::DynamicNoticesRepository;
@@ -11,18 +19,39 @@ use super::repository::DynamicNoticesRepository;
 #[derive(Clone)]
 pub struct NoticesService {
     repository: DynamicNoticesRepository,
+    audit: AuditService,
+    cache: ResponseCache,
 }
 
 impl NoticesService {
-    pub fn new(repository: DynamicNoticesRepository) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: DynamicNoticesRepository,
+        audit: AuditService,
+        cache: ResponseCache,
+    ) -> Self {
+        Self {
+            repository,
+            audit,
+            cache,
+        }
     }
 
     pub async fn list(&self) -> Result<Vec<NoticeRow>, AppError> {
         Ok(self.repository.list().await?)
     }
 
-    pub async fn create(&self, request: CreateNoticeRequest) -> Result<NoticeRow, AppError> {
+    pub async fn get(&self, notice_id: u32) -> Result<NoticeRow, AppError> {
+        self.repository
+            .get_by_id(notice_id)
+            .await?
+            .ok_or(AppError::NotFound("Notice not found"))
+    }
+
+    pub async fn create(
+        &self,
+        request: CreateNoticeRequest,
+        actor: &AuditActor,
+    ) -> Result<NoticeRow, AppError> {
         let is_active = request.active;
         let new_notice_id = self
             .repository
@@ -34,7 +63,18 @@ impl NoticesService {
             .get_by_id(new_notice_id)
             .await?
             .ok_or(AppError::NotFound("Failed to fetch newly created notice"))?;
+        self.cache.invalidate(CachedResource::Notices);
 
+        self.audit
+            .record_change(
+                actor,
+                "notice.created",
+                "notice",
+                Some(new_notice_id.to_string()),
+                None,
+                snapshot(&notice_row),
+            )
+            .await?;
         Ok(notice_row)
     }
 
@@ -42,6 +82,8 @@ impl NoticesService {
         &self,
         notice_id: u32,
         request: UpdateNoticeRequest,
+        if_match: &IfMatch,
+        actor: &AuditActor,
     ) -> Result<NoticeRow, AppError> {
         let no_fields_provided =
             request.title.is_none() && request.content.is_none() && request.active.is_none();
@@ -50,6 +92,8 @@ impl NoticesService {
             return Err(AppError::BadRequest("No fields provided"));
         }
 
+        let previous = self.get(notice_id).await?;
+        if_match.check(previous.version)?;
         let update_result = self
             .repository
             .update(
@@ -57,13 +101,32 @@ impl NoticesService {
                 request.title.as_deref(),
                 request.content.as_deref(),
                 request.active,
+                previous.version,
             )
             .await;
 
         match update_result {
+            Ok(0) => Err(AppError::PreconditionFailed(
+                "The notice was changed by someone else",
+            )),
             Ok(_rows_affected) => {
-                let notice_row = self.repository.get_by_id(notice_id).await?;
-                notice_row.ok_or(AppError::NotFound("Notice not found"))
+                let notice_row = self
+                    .repository
+                    .get_by_id(notice_id)
+                    .await?
+                    .ok_or(AppError::NotFound("Notice not found"))?;
+                self.cache.invalidate(CachedResource::Notices);
+                self.audit
+                    .record_change(
+                        actor,
+                        "notice.updated",
+                        "notice",
+                        Some(notice_id.to_string()),
+                        snapshot(&previous),
+                        snapshot(&notice_row),
+                    )
+                    .await?;
+                Ok(notice_row)
             }
             Err(sqlx::Error::Database(database_error)) => {
                 Err(AppError::Database(sqlx::Error::Database(database_error)))
@@ -72,16 +135,36 @@ impl NoticesService {
         }
     }
 
-    pub async fn delete(&self, notice_id: u32) -> Result<(), AppError> {
+    pub async fn delete(
+        &self,
+        notice_id: u32,
+        if_match: &IfMatch,
+        actor: &AuditActor,
+    ) -> Result<(), AppError> {
+        let previous = self.get(notice_id).await?;
+        if_match.check(previous.version)?;
         let was_deleted = self
             .repository
-            .delete(notice_id)
+            .delete(notice_id, previous.version)
             .await
             .map_err(AppError::Database)?;
 
         if !was_deleted {
-            Err(AppError::NotFound("Notice not found"))
+            Err(AppError::PreconditionFailed(
+                "The notice was changed by someone else",
+            ))
         } else {
+            self.cache.invalidate(CachedResource::Notices);
+            self.audit
+                .record_change(
+                    actor,
+                    "notice.deleted",
+                    "notice",
+                    Some(notice_id.to_string()),
+                    snapshot(&previous),
+                    None,
+                )
+                .await?;
             Ok(())
         }
     }
@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use chrono::Utc;
+
+use super::repository::DynamicOutboxRepository;
+use crate::infrastructure::mailer::DynamicMailer;
+
+/// Attempts before an email is given up on and left as `failed`.
+const MAX_ATTEMPTS: u32 = 8;
+/// How long a claimed email is hidden from other dispatchers.
+const LEASE: Duration = Duration::from_secs(5 * 60);
+const BASE_BACKOFF: Duration = Duration::from_secs(30);
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+/// Polls `email_outbox` and delivers due emails until the process exits.
+pub fn spawn(
+    repository: DynamicOutboxRepository,
+    mailer: DynamicMailer,
+    poll_interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(error) = dispatch_due(&repository, &mailer).await {
+                tracing::error!(%error, "email outbox dispatch failed");
+            }
+        }
+    })
+}
+
+async fn dispatch_due(
+    repository: &DynamicOutboxRepository,
+    mailer: &DynamicMailer,
+) -> sqlx::Result<()> {
+    loop {
+        let now = Utc::now();
+        let Some(email) = repository
+            .claim_next(now.naive_utc(), (now + LEASE).naive_utc())
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let result = match email.to_message() {
+            Ok(message) => mailer.send(&message).await.map_err(|e| e.to_string()),
+            Err(e) => Err(format!("invalid attachments: {e}")),
+        };
+
+        match result {
+            Ok(()) => {
+                repository
+                    .mark_sent(email.id, Utc::now().naive_utc())
+                    .await?
+            }
+            Err(error) if email.attempts >= MAX_ATTEMPTS => {
+                tracing::error!(id = email.id, %error, "giving up on email");
+                repository.mark_failed(email.id, &error).await?;
+            }
+            Err(error) => {
+                let next_attempt_at = Utc::now() + backoff(email.attempts);
+                tracing::warn!(id = email.id, attempt = email.attempts, %error, "email delivery failed, retrying");
+                repository
+                    .mark_retry(email.id, next_attempt_at.naive_utc(), &error)
+                    .await?;
+            }
+        }
+    }
+}
+
+/// 30 s, 1 min, 2 min, … capped at an hour.
+fn backoff(attempts: u32) -> Duration {
+    BASE_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
@@ -0,0 +1,5 @@
+pub mod dispatcher;
+pub mod model;
+pub mod reminders;
+pub mod repository;
+pub mod templates;
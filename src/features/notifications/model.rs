@@ -0,0 +1,42 @@
+use crate::infrastructure::mailer::{EmailAttachment, EmailMessage};
+
+#[derive(Debug, Clone)]
+pub struct OutboxEmailRow {
+    pub id: u32,
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+    /// JSON array of `EmailAttachment`.
+    pub attachments: Option<String>,
+    pub attempts: u32,
+}
+
+impl OutboxEmailRow {
+    pub fn to_message(&self) -> Result<EmailMessage, serde_json::Error> {
+        let attachments: Vec<EmailAttachment> = match &self.attachments {
+            Some(json) => serde_json::from_str(json)?,
+            None => Vec::new(),
+        };
+        Ok(EmailMessage {
+            to: self.recipient.clone(),
+            subject: self.subject.clone(),
+            body: self.body.clone(),
+            attachments,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderChannel {
+    Email,
+    Sms,
+}
+
+impl ReminderChannel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReminderChannel::Email => "email",
+            ReminderChannel::Sms => "sms",
+        }
+    }
+}
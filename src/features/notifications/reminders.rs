@@ -0,0 +1,91 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::Utc;
+use chrono_tz::Tz;
+
+use super::{model::ReminderChannel, repository::DynamicRemindersRepository, templates};
+use crate::{
+    features::{bookings::model::BookingRow, calendars::repository::DynamicCalendarsRepository},
+    infrastructure::sms::DynamicSmsProvider,
+};
+
+const CHANNELS: [ReminderChannel; 2] = [ReminderChannel::Email, ReminderChannel::Sms];
+
+pub struct ReminderScheduler {
+    pub repository: DynamicRemindersRepository,
+    pub calendars: DynamicCalendarsRepository,
+    pub sms: DynamicSmsProvider,
+    pub timezone: Tz,
+    /// Minutes before the start of a booking at which a reminder goes out.
+    pub offsets_minutes: Vec<u32>,
+}
+
+impl ReminderScheduler {
+    /// Checks for due reminders every `poll_interval` until the process
+    /// exits. Each reminder is recorded in `booking_reminders` so it goes
+    /// out once even across restarts.
+    pub fn spawn(self, poll_interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(poll_interval);
+            loop {
+                interval.tick().await;
+                if let Err(error) = self.send_due().await {
+                    tracing::error!(%error, "sending booking reminders failed");
+                }
+            }
+        })
+    }
+
+    async fn send_due(&self) -> sqlx::Result<()> {
+        let now = Utc::now().naive_utc();
+        let calendars: HashMap<u32, String> = self
+            .calendars
+            .list()
+            .await?
+            .into_iter()
+            .map(|c| (c.id, c.name))
+            .collect();
+
+        for &offset in &self.offsets_minutes {
+            for channel in CHANNELS {
+                for booking in self.repository.due(now, offset, channel).await? {
+                    let calendar_name = calendars
+                        .get(&booking.calendar_id)
+                        .map(String::as_str)
+                        .unwrap_or_default();
+                    self.send(&booking, calendar_name, offset, channel).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        booking: &BookingRow,
+        calendar_name: &str,
+        offset: u32,
+        channel: ReminderChannel,
+    ) -> sqlx::Result<()> {
+        match channel {
+            // Delivered and retried by the email outbox.
+            ReminderChannel::Email => {
+                let email = templates::booking_reminder(booking, calendar_name, self.timezone);
+                self.repository
+                    .record_email(booking.id, offset, &email)
+                    .await?;
+            }
+            ReminderChannel::Sms => {
+                if !self.repository.claim(booking.id, offset, channel).await? {
+                    return Ok(());
+                }
+                let sms = templates::booking_reminder_sms(booking, calendar_name, self.timezone);
+                if let Err(error) = self.sms.send(&sms).await {
+                    tracing::warn!(booking = booking.id, %error, "reminder sms failed, retrying later");
+                    self.repository.release(booking.id, offset, channel).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
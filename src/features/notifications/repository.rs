@@ -0,0 +1,296 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::{MySql, MySqlConnection, Pool};
+
+use super::model::{OutboxEmailRow, ReminderChannel};
+use crate::{
+    features::bookings::model::{BookingRow, BookingStatus},
+    infrastructure::mailer::EmailMessage,
+};
+
+/// Queues an email on the caller's connection, so it commits or rolls back
+/// together with the write that caused it.
+pub async fn enqueue(conn: &mut MySqlConnection, email: &EmailMessage) -> sqlx::Result<()> {
+    let attachments = if email.attachments.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&email.attachments).map_err(|e| sqlx::Error::Encode(e.into()))?)
+    };
+
+    sqlx::query!(
+        r#"INSERT INTO email_outbox (recipient, subject, body, attachments) VALUES (?, ?, ?, ?)"#,
+        email.to,
+        email.subject,
+        email.body,
+        attachments
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    /// Takes the oldest due email and pushes its `next_attempt_at` to
+    /// `lease_until`, so other dispatchers skip it while it is being sent.
+    async fn claim_next(
+        &self,
+        now: NaiveDateTime,
+        lease_until: NaiveDateTime,
+    ) -> sqlx::Result<Option<OutboxEmailRow>>;
+    async fn mark_sent(&self, id: u32, sent_at: NaiveDateTime) -> sqlx::Result<()>;
+    async fn mark_retry(
+        &self,
+        id: u32,
+        next_attempt_at: NaiveDateTime,
+        error: &str,
+    ) -> sqlx::Result<()>;
+    async fn mark_failed(&self, id: u32, error: &str) -> sqlx::Result<()>;
+}
+
+pub type DynamicOutboxRepository = std::sync::Arc<dyn OutboxRepository>;
+
+#[derive(Clone)]
+pub struct MySqlOutboxRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlOutboxRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for MySqlOutboxRepository {
+    async fn claim_next(
+        &self,
+        now: NaiveDateTime,
+        lease_until: NaiveDateTime,
+    ) -> sqlx::Result<Option<OutboxEmailRow>> {
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query!(
+            r#"
+            SELECT id, recipient, subject, body, attachments, attempts
+            FROM email_outbox
+            WHERE status = 'pending' AND next_attempt_at <= ?
+            ORDER BY next_attempt_at, id
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+            "#,
+            now
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(row) = row else {
+            tx.commit().await?;
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            r#"UPDATE email_outbox SET attempts = attempts + 1, next_attempt_at = ? WHERE id = ?"#,
+            lease_until,
+            row.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(Some(OutboxEmailRow {
+            id: row.id,
+            recipient: row.recipient,
+            subject: row.subject,
+            body: row.body,
+            attachments: row.attachments,
+            attempts: row.attempts + 1,
+        }))
+    }
+
+    async fn mark_sent(&self, id: u32, sent_at: NaiveDateTime) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"UPDATE email_outbox SET status = 'sent', sent_at = ?, last_error = NULL WHERE id = ?"#,
+            sent_at,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_retry(
+        &self,
+        id: u32,
+        next_attempt_at: NaiveDateTime,
+        error: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"UPDATE email_outbox SET next_attempt_at = ?, last_error = ? WHERE id = ?"#,
+            next_attempt_at,
+            error,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: u32, error: &str) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"UPDATE email_outbox SET status = 'failed', last_error = ? WHERE id = ?"#,
+            error,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+pub trait RemindersRepository: Send + Sync {
+    /// Confirmed bookings starting within `offset_minutes` of `now` that
+    /// have not had this reminder yet. Bookings made after the reminder
+    /// time are left out.
+    async fn due(
+        &self,
+        now: NaiveDateTime,
+        offset_minutes: u32,
+        channel: ReminderChannel,
+    ) -> sqlx::Result<Vec<BookingRow>>;
+    /// Records the email reminder and queues it in one transaction. `false`
+    /// when it was already recorded.
+    async fn record_email(
+        &self,
+        booking_id: u32,
+        offset_minutes: u32,
+        email: &EmailMessage,
+    ) -> sqlx::Result<bool>;
+    /// Records a reminder before it is sent. `false` when it was already
+    /// recorded.
+    async fn claim(
+        &self,
+        booking_id: u32,
+        offset_minutes: u32,
+        channel: ReminderChannel,
+    ) -> sqlx::Result<bool>;
+    /// Drops a claim whose delivery failed, so the next run retries it.
+    async fn release(
+        &self,
+        booking_id: u32,
+        offset_minutes: u32,
+        channel: ReminderChannel,
+    ) -> sqlx::Result<()>;
+}
+
+pub type DynamicRemindersRepository = std::sync::Arc<dyn RemindersRepository>;
+
+#[derive(Clone)]
+pub struct MySqlRemindersRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlRemindersRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+async fn insert_reminder(
+    conn: &mut MySqlConnection,
+    booking_id: u32,
+    offset_minutes: u32,
+    channel: ReminderChannel,
+) -> sqlx::Result<bool> {
+    let result = sqlx::query!(
+        r#"INSERT IGNORE INTO booking_reminders (booking_id, offset_minutes, channel) VALUES (?, ?, ?)"#,
+        booking_id,
+        offset_minutes,
+        channel.as_str()
+    )
+    .execute(conn)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+#[async_trait]
+impl RemindersRepository for MySqlRemindersRepository {
+    async fn due(
+        &self,
+        now: NaiveDateTime,
+        offset_minutes: u32,
+        channel: ReminderChannel,
+    ) -> sqlx::Result<Vec<BookingRow>> {
+        sqlx::query_as!(
+            BookingRow,
+            r#"
+            SELECT
+                b.id, b.calendar_id, b.customer_id, b.starts_at_utc, b.ends_at_utc,
+                b.status as `status: BookingStatus`, b.cancelled_at,
+                b.customer_name, b.customer_email, b.customer_phone, b.customer_notes, b.locale, b.price_cents,
+                b.discount_cents, b.created_at, b.updated_at, b.version
+            FROM bookings b
+            WHERE b.status = 'confirmed'
+              AND b.starts_at_utc > ?
+              AND b.starts_at_utc <= DATE_ADD(?, INTERVAL ? MINUTE)
+              AND b.created_at < DATE_SUB(b.starts_at_utc, INTERVAL ? MINUTE)
+              AND NOT EXISTS (
+                  SELECT 1 FROM booking_reminders r
+                  WHERE r.booking_id = b.id AND r.offset_minutes = ? AND r.channel = ?
+              )
+            ORDER BY b.starts_at_utc
+            "#,
+            now,
+            now,
+            offset_minutes,
+            offset_minutes,
+            offset_minutes,
+            channel.as_str()
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn record_email(
+        &self,
+        booking_id: u32,
+        offset_minutes: u32,
+        email: &EmailMessage,
+    ) -> sqlx::Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        if !insert_reminder(&mut tx, booking_id, offset_minutes, ReminderChannel::Email).await? {
+            return Ok(false);
+        }
+        enqueue(&mut tx, email).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn claim(
+        &self,
+        booking_id: u32,
+        offset_minutes: u32,
+        channel: ReminderChannel,
+    ) -> sqlx::Result<bool> {
+        let mut conn = self.pool.acquire().await?;
+        insert_reminder(&mut conn, booking_id, offset_minutes, channel).await
+    }
+
+    async fn release(
+        &self,
+        booking_id: u32,
+        offset_minutes: u32,
+        channel: ReminderChannel,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"DELETE FROM booking_reminders WHERE booking_id = ? AND offset_minutes = ? AND channel = ?"#,
+            booking_id,
+            offset_minutes,
+            channel.as_str()
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
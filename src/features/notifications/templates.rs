@@ -0,0 +1,318 @@
+use chrono::{DateTime, Datelike, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    features::bookings::{
+        ics::{self, EventStatus, IcsEvent},
+        model::BookingRow,
+    },
+    infrastructure::{
+        mailer::{EmailAttachment, EmailMessage},
+        sms::SmsMessage,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    Fi,
+    En,
+}
+
+impl Locale {
+    /// Unknown or missing locales fall back to Finnish.
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "en" => Locale::En,
+            _ => Locale::Fi,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Locale::Fi => "fi",
+            Locale::En => "en",
+        }
+    }
+}
+
+struct Template {
+    subject: &'static str,
+    body: &'static str,
+}
+
+const CONFIRMATION_FI: Template = Template {
+    subject: "Varausvahvistus: {calendar} {date}",
+    body: "Hei {name},
+
+kiitos varauksestasi! Varauksesi on vahvistettu.
+
+Pöytä: {calendar}
+Aika: {date} klo {start}–{end}
+Varausnumero: {id}
+
+Liitteenä on kalenterimerkintä. Jos haluat perua varauksen, ota meihin yhteyttä.
+
+Tervetuloa pelaamaan!
+Kukkilan Biljardi",
+};
+
+const CONFIRMATION_EN: Template = Template {
+    subject: "Booking confirmed: {calendar} {date}",
+    body: "Hi {name},
+
+thank you for your booking! It has been confirmed.
+
+Table: {calendar}
+Time: {date} {start}–{end}
+Booking number: {id}
+
+A calendar entry is attached. If you need to cancel, please contact us.
+
+See you at the table!
+Kukkilan Biljardi",
+};
+
+const CANCELLATION_FI: Template = Template {
+    subject: "Varaus peruttu: {calendar} {date}",
+    body: "Hei {name},
+
+varauksesi on peruttu.
+
+Pöytä: {calendar}
+Aika: {date} klo {start}–{end}
+Varausnumero: {id}
+
+Terveisin
+Kukkilan Biljardi",
+};
+
+const CANCELLATION_EN: Template = Template {
+    subject: "Booking cancelled: {calendar} {date}",
+    body: "Hi {name},
+
+your booking has been cancelled.
+
+Table: {calendar}
+Time: {date} {start}–{end}
+Booking number: {id}
+
+Best regards,
+Kukkilan Biljardi",
+};
+
+const RESCHEDULE_FI: Template = Template {
+    subject: "Varauksen aika muuttunut: {calendar} {date}",
+    body: "Hei {name},
+
+varauksesi aikaa on muutettu. Uusi aika:
+
+Pöytä: {calendar}
+Aika: {date} klo {start}–{end}
+Varausnumero: {id}
+
+Liitteenä on päivitetty kalenterimerkintä.
+
+Tervetuloa pelaamaan!
+Kukkilan Biljardi",
+};
+
+const RESCHEDULE_EN: Template = Template {
+    subject: "Booking time changed: {calendar} {date}",
+    body: "Hi {name},
+
+the time of your booking has changed. The new time is:
+
+Table: {calendar}
+Time: {date} {start}–{end}
+Booking number: {id}
+
+An updated calendar entry is attached.
+
+See you at the table!
+Kukkilan Biljardi",
+};
+
+const REMINDER_FI: Template = Template {
+    subject: "Muistutus varauksesta: {calendar} {date}",
+    body: "Hei {name},
+
+muistutamme tulevasta varauksestasi.
+
+Pöytä: {calendar}
+Aika: {date} klo {start}–{end}
+Varausnumero: {id}
+
+Jos et pääsekään paikalle, ilmoitathan meille, jotta pöytä vapautuu muille.
+
+Tervetuloa pelaamaan!
+Kukkilan Biljardi",
+};
+
+const REMINDER_EN: Template = Template {
+    subject: "Booking reminder: {calendar} {date}",
+    body: "Hi {name},
+
+this is a reminder of your upcoming booking.
+
+Table: {calendar}
+Time: {date} {start}–{end}
+Booking number: {id}
+
+If you can't make it, please let us know so the table can go to someone else.
+
+See you at the table!
+Kukkilan Biljardi",
+};
+
+const REMINDER_SMS_FI: &str = "Muistutus: varauksesi Kukkilan Biljardissa {date} klo {start}–{end}, {calendar}. Varausnumero {id}.";
+const REMINDER_SMS_EN: &str = "Reminder: your booking at Kukkilan Biljardi {date} {start}–{end}, {calendar}. Booking number {id}.";
+
+pub fn booking_confirmation(booking: &BookingRow, calendar_name: &str, tz: Tz) -> EmailMessage {
+    let template = match Locale::parse(&booking.locale) {
+        Locale::Fi => &CONFIRMATION_FI,
+        Locale::En => &CONFIRMATION_EN,
+    };
+    booking_email(template, booking, calendar_name, tz, false)
+}
+
+pub fn booking_cancellation(booking: &BookingRow, calendar_name: &str, tz: Tz) -> EmailMessage {
+    let template = match Locale::parse(&booking.locale) {
+        Locale::Fi => &CANCELLATION_FI,
+        Locale::En => &CANCELLATION_EN,
+    };
+    booking_email(template, booking, calendar_name, tz, true)
+}
+
+pub fn booking_rescheduled(booking: &BookingRow, calendar_name: &str, tz: Tz) -> EmailMessage {
+    let template = match Locale::parse(&booking.locale) {
+        Locale::Fi => &RESCHEDULE_FI,
+        Locale::En => &RESCHEDULE_EN,
+    };
+    booking_email(template, booking, calendar_name, tz, false)
+}
+
+pub fn booking_reminder(booking: &BookingRow, calendar_name: &str, tz: Tz) -> EmailMessage {
+    let template = match Locale::parse(&booking.locale) {
+        Locale::Fi => &REMINDER_FI,
+        Locale::En => &REMINDER_EN,
+    };
+    let vars = booking_vars(booking, calendar_name, tz);
+    EmailMessage {
+        to: booking.customer_email.clone(),
+        subject: render(template.subject, &vars),
+        body: render(template.body, &vars),
+        attachments: Vec::new(),
+    }
+}
+
+pub fn booking_reminder_sms(booking: &BookingRow, calendar_name: &str, tz: Tz) -> SmsMessage {
+    let template = match Locale::parse(&booking.locale) {
+        Locale::Fi => REMINDER_SMS_FI,
+        Locale::En => REMINDER_SMS_EN,
+    };
+    SmsMessage {
+        to: booking.customer_phone.clone(),
+        body: render(template, &booking_vars(booking, calendar_name, tz)),
+    }
+}
+
+fn booking_email(
+    template: &Template,
+    booking: &BookingRow,
+    calendar_name: &str,
+    tz: Tz,
+    cancelled: bool,
+) -> EmailMessage {
+    let vars = booking_vars(booking, calendar_name, tz);
+
+    let event = IcsEvent {
+        uid: ics::booking_uid(booking.id),
+        sequence: ics::booking_sequence(booking.updated_at),
+        stamp: Utc::now().naive_utc(),
+        start: booking.starts_at_utc,
+        end: booking.ends_at_utc,
+        summary: format!("Kukkilan Biljardi – {calendar_name}"),
+        description: None,
+        status: if cancelled {
+            EventStatus::Cancelled
+        } else {
+            EventStatus::Confirmed
+        },
+    };
+
+    EmailMessage {
+        to: booking.customer_email.clone(),
+        subject: render(template.subject, &vars),
+        body: render(template.body, &vars),
+        attachments: vec![EmailAttachment {
+            filename: "booking.ics".to_string(),
+            content_type: "text/calendar; charset=utf-8".to_string(),
+            content: ics::render_calendar(None, &[event]),
+        }],
+    }
+}
+
+/// Placeholder values for a booking, formatted for its locale.
+fn booking_vars(booking: &BookingRow, calendar_name: &str, tz: Tz) -> Vec<(&'static str, String)> {
+    let locale = Locale::parse(&booking.locale);
+    let start =
+        DateTime::<Utc>::from_naive_utc_and_offset(booking.starts_at_utc, Utc).with_timezone(&tz);
+    let end =
+        DateTime::<Utc>::from_naive_utc_and_offset(booking.ends_at_utc, Utc).with_timezone(&tz);
+    let (date, start_s, end_s) = match locale {
+        Locale::Fi => (
+            format!(
+                "{} {}",
+                FI_WEEKDAYS[start.weekday().num_days_from_monday() as usize],
+                start.format("%-d.%-m.%Y")
+            ),
+            start.format("%H.%M").to_string(),
+            end.format("%H.%M").to_string(),
+        ),
+        Locale::En => (
+            start.format("%a %-d %b %Y").to_string(),
+            start.format("%H:%M").to_string(),
+            end.format("%H:%M").to_string(),
+        ),
+    };
+
+    vec![
+        ("name", booking.customer_name.clone()),
+        ("calendar", calendar_name.to_string()),
+        ("date", date),
+        ("start", start_s),
+        ("end", end_s),
+        ("id", booking.id.to_string()),
+    ]
+}
+
+const FI_WEEKDAYS: [&str; 7] = ["ma", "ti", "ke", "to", "pe", "la", "su"];
+
+/// Replaces `{key}` placeholders in one pass, so values that contain braces
+/// are never expanded themselves. Unknown placeholders are kept as they are.
+fn render(template: &str, vars: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let value = after.find('}').and_then(|close| {
+            let key = &after[..close];
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| (value, close))
+        });
+        match value {
+            Some((value, close)) => {
+                out.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
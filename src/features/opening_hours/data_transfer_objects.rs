@@ -1,37 +1,146 @@
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::resolver::DaySource;
 
 // ===== Opening Hours =====
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct OpeningHourResponse {
     pub weekday: u8,       // 1=Mon … 7=Sun
     pub opens_at: String,  // "HH:MM:SS"
     pub closes_at: String, // "HH:MM:SS"
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpsertOpeningHourRequest {
     pub opens_at: String,  // required
     pub closes_at: String, // required
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct WeekdayHoursRequest {
+    pub weekday: u8,
+    pub opens_at: String,
+    pub closes_at: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReplaceWeekRequest {
+    pub days: Vec<WeekdayHoursRequest>, // weekdays left out are closed
+}
+
 // ===== Opening Exceptions =====
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct OpeningExceptionResponse {
-    pub date: String, // "YYYY-MM-DD"
+    pub date: String,     // "YYYY-MM-DD", first day of the exception
+    pub end_date: String, // "YYYY-MM-DD", last day (inclusive)
     pub is_closed: bool,
     pub opens_at: Option<String>,
     pub closes_at: Option<String>,
+    pub note: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpsertOpeningExceptionRequest {
     pub is_closed: bool,
     pub opens_at: Option<String>,
     pub closes_at: Option<String>,
+    pub note: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateOpeningExceptionRangeRequest {
+    pub from: String, // YYYY-MM-DD
+    pub to: String,   // YYYY-MM-DD, inclusive
+    pub is_closed: bool,
+    pub opens_at: Option<String>,
+    pub closes_at: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ExceptionsQuery {
     pub from: Option<String>, // YYYY-MM-DD
     pub to: Option<String>,   // YYYY-MM-DD
 }
+
+// ===== Recurring Holidays =====
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OpeningHolidayResponse {
+    pub id: u32,
+    pub name: String,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+    pub movable: Option<String>, // e.g. "good_friday", "midsummer_eve"
+    pub is_closed: bool,
+    pub opens_at: Option<String>,
+    pub closes_at: Option<String>,
+    pub date: Option<String>, // occurrence in the requested year
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpsertOpeningHolidayRequest {
+    pub name: String,
+    pub month: Option<u8>,       // fixed rules: month + day
+    pub day: Option<u8>,         //
+    pub movable: Option<String>, // movable rules: holiday key
+    pub is_closed: bool,
+    pub opens_at: Option<String>,
+    pub closes_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HolidaysQuery {
+    pub year: Option<i32>,
+}
+
+// ===== Seasonal Schedules =====
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OpeningScheduleResponse {
+    pub id: u32,
+    pub name: String,
+    pub valid_from: String, // "YYYY-MM-DD"
+    pub valid_to: String,   // "YYYY-MM-DD", inclusive
+    pub hours: Vec<OpeningHourResponse>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpsertOpeningScheduleRequest {
+    pub name: String,
+    pub valid_from: String,
+    pub valid_to: String,
+    pub hours: Vec<WeekdayHoursRequest>, // weekdays left out are closed
+}
+
+// ===== Effective Hours =====
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EffectiveDayResponse {
+    pub date: String, // "YYYY-MM-DD"
+    pub weekday: u8,  // 1=Mon … 7=Sun
+    pub source: DaySource,
+    pub is_closed: bool,
+    pub opens_at: Option<String>,
+    pub closes_at: Option<String>,
+    pub note: Option<String>,
+    pub schedule: Option<String>, // season name when a seasonal schedule applied
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EffectiveQuery {
+    pub from: Option<String>, // YYYY-MM-DD, defaults to today
+    pub to: Option<String>,   // YYYY-MM-DD, defaults to from + 6 days
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OpeningStatusResponse {
+    pub now: DateTime<FixedOffset>,
+    pub is_open: bool,
+    pub next_open: Option<DateTime<FixedOffset>>,
+    pub next_close: Option<DateTime<FixedOffset>>,
+    pub today: EffectiveDayResponse,
+    pub notice: Option<String>,
+}
@@ -0,0 +1,204 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+use super::model::OpeningHolidayRow;
+
+/// Finnish public holidays whose date changes from year to year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovableHoliday {
+    GoodFriday,
+    EasterSunday,
+    EasterMonday,
+    AscensionDay,
+    Whitsunday,
+    MidsummerEve,
+    MidsummerDay,
+    AllSaintsDay,
+}
+
+impl MovableHoliday {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MovableHoliday::GoodFriday => "good_friday",
+            MovableHoliday::EasterSunday => "easter_sunday",
+            MovableHoliday::EasterMonday => "easter_monday",
+            MovableHoliday::AscensionDay => "ascension_day",
+            MovableHoliday::Whitsunday => "whitsunday",
+            MovableHoliday::MidsummerEve => "midsummer_eve",
+            MovableHoliday::MidsummerDay => "midsummer_day",
+            MovableHoliday::AllSaintsDay => "all_saints_day",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "good_friday" => Some(MovableHoliday::GoodFriday),
+            "easter_sunday" => Some(MovableHoliday::EasterSunday),
+            "easter_monday" => Some(MovableHoliday::EasterMonday),
+            "ascension_day" => Some(MovableHoliday::AscensionDay),
+            "whitsunday" => Some(MovableHoliday::Whitsunday),
+            "midsummer_eve" => Some(MovableHoliday::MidsummerEve),
+            "midsummer_day" => Some(MovableHoliday::MidsummerDay),
+            "all_saints_day" => Some(MovableHoliday::AllSaintsDay),
+            _ => None,
+        }
+    }
+
+    pub fn date_in(self, year: i32) -> Option<NaiveDate> {
+        match self {
+            MovableHoliday::GoodFriday => easter_sunday(year)?.checked_sub_days(Days::new(2)),
+            MovableHoliday::EasterSunday => easter_sunday(year),
+            MovableHoliday::EasterMonday => easter_sunday(year)?.checked_add_days(Days::new(1)),
+            MovableHoliday::AscensionDay => easter_sunday(year)?.checked_add_days(Days::new(39)),
+            MovableHoliday::Whitsunday => easter_sunday(year)?.checked_add_days(Days::new(49)),
+            // Friday between 19 and 25 June
+            MovableHoliday::MidsummerEve => first_weekday_from(year, 6, 19, Weekday::Fri),
+            // Saturday between 20 and 26 June
+            MovableHoliday::MidsummerDay => first_weekday_from(year, 6, 20, Weekday::Sat),
+            // Saturday between 31 October and 6 November
+            MovableHoliday::AllSaintsDay => first_weekday_from(year, 10, 31, Weekday::Sat),
+        }
+    }
+}
+
+/// Date of a holiday rule in the given year. Fixed rules on 29 February
+/// have no occurrence in non-leap years.
+pub fn occurrence(rule: &OpeningHolidayRow, year: i32) -> Option<NaiveDate> {
+    match rule.movable.as_deref() {
+        Some(key) => MovableHoliday::parse(key)?.date_in(year),
+        None => NaiveDate::from_ymd_opt(year, rule.month?.into(), rule.day?.into()),
+    }
+}
+
+/// Gregorian Easter Sunday (anonymous Gregorian algorithm).
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+fn first_weekday_from(year: i32, month: u32, day: u32, weekday: Weekday) -> Option<NaiveDate> {
+    let start = NaiveDate::from_ymd_opt(year, month, day)?;
+    let offset = (7 + weekday.num_days_from_monday() - start.weekday().num_days_from_monday()) % 7;
+    start.checked_add_days(Days::new(offset.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn rule(month: Option<u8>, day: Option<u8>, movable: Option<&str>) -> OpeningHolidayRow {
+        OpeningHolidayRow {
+            id: 1,
+            name: "Holiday".to_string(),
+            month,
+            day,
+            movable: movable.map(str::to_string),
+            is_closed: true,
+            opens_at: None,
+            closes_at: None,
+        }
+    }
+
+    #[test]
+    fn easter_sunday_matches_known_dates() {
+        assert_eq!(easter_sunday(2000), Some(date(2000, 4, 23)));
+        assert_eq!(easter_sunday(2008), Some(date(2008, 3, 23)));
+        assert_eq!(easter_sunday(2024), Some(date(2024, 3, 31)));
+        assert_eq!(easter_sunday(2025), Some(date(2025, 4, 20)));
+        assert_eq!(easter_sunday(2038), Some(date(2038, 4, 25)));
+    }
+
+    #[test]
+    fn holidays_relative_to_easter() {
+        let date_in = |holiday: MovableHoliday| holiday.date_in(2025);
+
+        assert_eq!(date_in(MovableHoliday::GoodFriday), Some(date(2025, 4, 18)));
+        assert_eq!(
+            date_in(MovableHoliday::EasterMonday),
+            Some(date(2025, 4, 21))
+        );
+        assert_eq!(
+            date_in(MovableHoliday::AscensionDay),
+            Some(date(2025, 5, 29))
+        );
+        assert_eq!(date_in(MovableHoliday::Whitsunday), Some(date(2025, 6, 8)));
+    }
+
+    #[test]
+    fn nth_weekday_holidays() {
+        assert_eq!(
+            MovableHoliday::MidsummerEve.date_in(2025),
+            Some(date(2025, 6, 20))
+        );
+        assert_eq!(
+            MovableHoliday::MidsummerDay.date_in(2025),
+            Some(date(2025, 6, 21))
+        );
+        assert_eq!(
+            MovableHoliday::AllSaintsDay.date_in(2025),
+            Some(date(2025, 11, 1))
+        );
+        // The first day of the window is itself the weekday.
+        assert_eq!(
+            MovableHoliday::MidsummerEve.date_in(2026),
+            Some(date(2026, 6, 19))
+        );
+        assert_eq!(
+            MovableHoliday::AllSaintsDay.date_in(2026),
+            Some(date(2026, 10, 31))
+        );
+        // The last day of the window.
+        assert_eq!(
+            MovableHoliday::MidsummerEve.date_in(2027),
+            Some(date(2027, 6, 25))
+        );
+    }
+
+    #[test]
+    fn movable_holiday_keys_round_trip() {
+        for holiday in [
+            MovableHoliday::GoodFriday,
+            MovableHoliday::EasterSunday,
+            MovableHoliday::EasterMonday,
+            MovableHoliday::AscensionDay,
+            MovableHoliday::Whitsunday,
+            MovableHoliday::MidsummerEve,
+            MovableHoliday::MidsummerDay,
+            MovableHoliday::AllSaintsDay,
+        ] {
+            assert_eq!(MovableHoliday::parse(holiday.as_str()), Some(holiday));
+        }
+        assert_eq!(MovableHoliday::parse("christmas"), None);
+    }
+
+    #[test]
+    fn occurrence_of_fixed_and_movable_rules() {
+        assert_eq!(
+            occurrence(&rule(Some(12), Some(6), None), 2025),
+            Some(date(2025, 12, 6))
+        );
+        assert_eq!(
+            occurrence(&rule(None, None, Some("easter_monday")), 2024),
+            Some(date(2024, 4, 1))
+        );
+        let leap_day = rule(Some(2), Some(29), None);
+        assert_eq!(occurrence(&leap_day, 2024), Some(date(2024, 2, 29)));
+        assert_eq!(occurrence(&leap_day, 2025), None);
+    }
+}
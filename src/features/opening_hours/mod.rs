@@ -1,7 +1,9 @@
 pub mod data_transfer_objects;
+pub mod holidays;
 pub mod model;
 pub mod repository;
+pub mod resolver;
 pub mod routes;
 pub mod service;
 
-pub use routes::routes;
+pub use routes::{ApiDoc, routes};
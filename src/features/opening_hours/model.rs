@@ -12,8 +12,46 @@ pub struct OpeningHourRow {
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct OpeningExceptionRow {
     pub id: u32,
-    pub date: NaiveDate,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
     pub is_closed: bool,
     pub opens_at: Option<NaiveTime>,
     pub closes_at: Option<NaiveTime>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OpeningHolidayRow {
+    pub id: u32,
+    pub name: String,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+    pub movable: Option<String>,
+    pub is_closed: bool,
+    pub opens_at: Option<NaiveTime>,
+    pub closes_at: Option<NaiveTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OpeningScheduleRow {
+    pub id: u32,
+    pub name: String,
+    pub valid_from: NaiveDate,
+    pub valid_to: NaiveDate,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OpeningScheduleHourRow {
+    pub id: u32,
+    pub schedule_id: u32,
+    pub weekday: u8,
+    pub opens_at: NaiveTime,
+    pub closes_at: NaiveTime,
+}
+
+/// A season together with its own weekday rows.
+#[derive(Debug, Serialize)]
+pub struct OpeningSchedule {
+    pub schedule: OpeningScheduleRow,
+    pub hours: Vec<OpeningScheduleHourRow>,
 }
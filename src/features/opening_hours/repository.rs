@@ -2,7 +2,10 @@ use async_trait::async_trait;
 use chrono::{NaiveDate, NaiveTime};
 use sqlx::{MySql, Pool};
 
-use super::model::{OpeningExceptionRow, OpeningHourRow};
+use super::model::{
+    OpeningExceptionRow, OpeningHolidayRow, OpeningHourRow, OpeningScheduleHourRow,
+    OpeningScheduleRow,
+};
 
 // ---------- Traits ----------
 #[async_trait]
@@ -15,6 +18,9 @@ pub trait OpeningHoursRepository: Send + Sync {
         closes_at: NaiveTime,
     ) -> sqlx::Result<()>;
     async fn delete_weekday(&self, weekday: u8) -> sqlx::Result<u64>;
+    /// Replaces the whole week in one transaction; weekdays not in `days`
+    /// end up without a row, i.e. closed.
+    async fn replace_week(&self, days: &[(u8, NaiveTime, NaiveTime)]) -> sqlx::Result<()>;
 }
 
 #[async_trait]
@@ -24,18 +30,66 @@ pub trait OpeningExceptionsRepository: Send + Sync {
         from: Option<NaiveDate>,
         to: Option<NaiveDate>,
     ) -> sqlx::Result<Vec<OpeningExceptionRow>>;
+    /// Writes the exception keyed on `start_date`. `false`, writing nothing,
+    /// when another exception overlaps `start_date..=end_date`.
     async fn upsert(
         &self,
-        date: NaiveDate,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
         is_closed: bool,
         opens_at: Option<NaiveTime>,
         closes_at: Option<NaiveTime>,
-    ) -> sqlx::Result<()>;
+        note: Option<&str>,
+    ) -> sqlx::Result<bool>;
+    /// Deletes the exception covering `date`.
     async fn delete(&self, date: NaiveDate) -> sqlx::Result<u64>;
 }
 
+#[async_trait]
+pub trait OpeningHolidaysRepository: Send + Sync {
+    async fn list(&self) -> sqlx::Result<Vec<OpeningHolidayRow>>;
+    async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<OpeningHolidayRow>>;
+    async fn insert(&self, holiday: &NewOpeningHoliday) -> sqlx::Result<u32>;
+    async fn update(&self, id: u32, holiday: &NewOpeningHoliday) -> sqlx::Result<u64>;
+    async fn delete(&self, id: u32) -> sqlx::Result<u64>;
+}
+
+/// Validated holiday rule, ready to be written.
+pub struct NewOpeningHoliday {
+    pub name: String,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+    pub movable: Option<&'static str>,
+    pub is_closed: bool,
+    pub opens_at: Option<NaiveTime>,
+    pub closes_at: Option<NaiveTime>,
+}
+
+#[async_trait]
+pub trait OpeningSchedulesRepository: Send + Sync {
+    async fn list(&self) -> sqlx::Result<Vec<OpeningScheduleRow>>;
+    async fn list_hours(&self) -> sqlx::Result<Vec<OpeningScheduleHourRow>>;
+    async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<OpeningScheduleRow>>;
+    async fn get_by_name(&self, name: &str) -> sqlx::Result<Option<OpeningScheduleRow>>;
+    /// Inserts the schedule and its weekday rows in one transaction.
+    async fn insert(&self, schedule: &NewOpeningSchedule) -> sqlx::Result<u32>;
+    /// Replaces the schedule and all of its weekday rows in one transaction.
+    async fn update(&self, id: u32, schedule: &NewOpeningSchedule) -> sqlx::Result<()>;
+    async fn delete(&self, id: u32) -> sqlx::Result<u64>;
+}
+
+/// Validated season, ready to be written.
+pub struct NewOpeningSchedule {
+    pub name: String,
+    pub valid_from: NaiveDate,
+    pub valid_to: NaiveDate,
+    pub hours: Vec<(u8, NaiveTime, NaiveTime)>,
+}
+
 pub type DynOpeningHoursRepo = std::sync::Arc<dyn OpeningHoursRepository>;
 pub type DynOpeningExceptionsRepo = std::sync::Arc<dyn OpeningExceptionsRepository>;
+pub type DynOpeningHolidaysRepo = std::sync::Arc<dyn OpeningHolidaysRepository>;
+pub type DynOpeningSchedulesRepo = std::sync::Arc<dyn OpeningSchedulesRepository>;
 
 // ---------- MySQL impls ----------
 #[derive(Clone)]
@@ -89,6 +143,27 @@ impl OpeningHoursRepository for MySqlOpeningHoursRepository {
             .await?;
         Ok(res.rows_affected())
     }
+
+    async fn replace_week(&self, days: &[(u8, NaiveTime, NaiveTime)]) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("DELETE FROM opening_hours")
+            .execute(&mut *tx)
+            .await?;
+
+        for (weekday, opens_at, closes_at) in days {
+            sqlx::query!(
+                r#"INSERT INTO opening_hours (weekday, opens_at, closes_at) VALUES (?, ?, ?)"#,
+                weekday,
+                opens_at,
+                closes_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
 }
 
 #[derive(Clone)]
@@ -104,6 +179,8 @@ impl MySqlOpeningExceptionsRepository {
 
 #[async_trait]
 impl OpeningExceptionsRepository for MySqlOpeningExceptionsRepository {
+    /// Exceptions overlapping the `from..=to` window, so a range that
+    /// started before `from` is still returned.
     async fn list(
         &self,
         from: Option<NaiveDate>,
@@ -112,27 +189,27 @@ impl OpeningExceptionsRepository for MySqlOpeningExceptionsRepository {
         match (from, to) {
             (None, None) => sqlx::query_as!(
                 OpeningExceptionRow,
-                r#"SELECT id as `id: u32`, date, is_closed as `is_closed: bool`, opens_at, closes_at FROM opening_exceptions ORDER BY date"#
+                r#"SELECT id as `id: u32`, start_date, end_date, is_closed as `is_closed: bool`, opens_at, closes_at, note FROM opening_exceptions ORDER BY start_date"#
             )
             .fetch_all(&self.pool)
             .await,
             (Some(f), None) => sqlx::query_as!(
                 OpeningExceptionRow,
-                r#"SELECT id as `id: u32`, date, is_closed as `is_closed: bool`, opens_at, closes_at FROM opening_exceptions WHERE date >= ? ORDER BY date"#,
+                r#"SELECT id as `id: u32`, start_date, end_date, is_closed as `is_closed: bool`, opens_at, closes_at, note FROM opening_exceptions WHERE end_date >= ? ORDER BY start_date"#,
                 f
             )
             .fetch_all(&self.pool)
             .await,
             (None, Some(t)) => sqlx::query_as!(
                 OpeningExceptionRow,
-                r#"SELECT id as `id: u32`, date, is_closed as `is_closed: bool`, opens_at, closes_at FROM opening_exceptions WHERE date <= ? ORDER BY date"#,
+                r#"SELECT id as `id: u32`, start_date, end_date, is_closed as `is_closed: bool`, opens_at, closes_at, note FROM opening_exceptions WHERE start_date <= ? ORDER BY start_date"#,
                 t
             )
             .fetch_all(&self.pool)
             .await,
             (Some(f), Some(t)) => sqlx::query_as!(
                 OpeningExceptionRow,
-                r#"SELECT id as `id: u32`, date, is_closed as `is_closed: bool`, opens_at, closes_at FROM opening_exceptions WHERE date BETWEEN ? AND ? ORDER BY date"#,
+                r#"SELECT id as `id: u32`, start_date, end_date, is_closed as `is_closed: bool`, opens_at, closes_at, note FROM opening_exceptions WHERE end_date >= ? AND start_date <= ? ORDER BY start_date"#,
                 f,
                 t
             )
@@ -143,32 +220,262 @@ impl OpeningExceptionsRepository for MySqlOpeningExceptionsRepository {
 
     async fn upsert(
         &self,
-        date: NaiveDate,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
         is_closed: bool,
         opens_at: Option<NaiveTime>,
         closes_at: Option<NaiveTime>,
-    ) -> sqlx::Result<()> {
+        note: Option<&str>,
+    ) -> sqlx::Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        // Exceptions are keyed on their first day; anything else in the window
+        // would make the resolved hours depend on which row wins. Locking the
+        // window keeps a concurrent write from slipping in before the insert.
+        let overlapping = sqlx::query_scalar!(
+            r#"SELECT start_date FROM opening_exceptions WHERE end_date >= ? AND start_date <= ? FOR UPDATE"#,
+            start_date,
+            end_date
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        if overlapping.iter().any(|date| *date != start_date) {
+            return Ok(false);
+        }
+
         sqlx::query!(
             r#"
-                INSERT INTO opening_exceptions (date, is_closed, opens_at, closes_at)
-                VALUES (?, ?, ?, ?)
+                INSERT INTO opening_exceptions (start_date, end_date, is_closed, opens_at, closes_at, note)
+                VALUES (?, ?, ?, ?, ?, ?)
                 ON DUPLICATE KEY UPDATE
+                    end_date = VALUES(end_date),
                     is_closed = VALUES(is_closed),
                     opens_at = VALUES(opens_at),
-                    closes_at = VALUES(closes_at)
+                    closes_at = VALUES(closes_at),
+                    note = VALUES(note)
             "#,
-            date,
+            start_date,
+            end_date,
             is_closed,
             opens_at,
-            closes_at
+            closes_at,
+            note
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
-        Ok(())
+        tx.commit().await?;
+        Ok(true)
     }
 
     async fn delete(&self, date: NaiveDate) -> sqlx::Result<u64> {
-        let res = sqlx::query!("DELETE FROM opening_exceptions WHERE date = ?", date)
+        let res = sqlx::query!(
+            "DELETE FROM opening_exceptions WHERE start_date <= ? AND end_date >= ?",
+            date,
+            date
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+}
+
+#[derive(Clone)]
+pub struct MySqlOpeningHolidaysRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlOpeningHolidaysRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OpeningHolidaysRepository for MySqlOpeningHolidaysRepository {
+    async fn list(&self) -> sqlx::Result<Vec<OpeningHolidayRow>> {
+        sqlx::query_as!(
+            OpeningHolidayRow,
+            r#"SELECT id as `id: u32`, name, month as `month?: u8`, day as `day?: u8`, movable, is_closed as `is_closed: bool`, opens_at, closes_at FROM opening_holidays ORDER BY month, day, id"#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<OpeningHolidayRow>> {
+        sqlx::query_as!(
+            OpeningHolidayRow,
+            r#"SELECT id as `id: u32`, name, month as `month?: u8`, day as `day?: u8`, movable, is_closed as `is_closed: bool`, opens_at, closes_at FROM opening_holidays WHERE id = ?"#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn insert(&self, holiday: &NewOpeningHoliday) -> sqlx::Result<u32> {
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO opening_holidays (name, month, day, movable, is_closed, opens_at, closes_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            holiday.name,
+            holiday.month,
+            holiday.day,
+            holiday.movable,
+            holiday.is_closed,
+            holiday.opens_at,
+            holiday.closes_at
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(res.last_insert_id() as u32)
+    }
+
+    async fn update(&self, id: u32, holiday: &NewOpeningHoliday) -> sqlx::Result<u64> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE opening_holidays
+                SET name = ?, month = ?, day = ?, movable = ?, is_closed = ?, opens_at = ?, closes_at = ?
+                WHERE id = ?
+            "#,
+            holiday.name,
+            holiday.month,
+            holiday.day,
+            holiday.movable,
+            holiday.is_closed,
+            holiday.opens_at,
+            holiday.closes_at,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+
+    async fn delete(&self, id: u32) -> sqlx::Result<u64> {
+        let res = sqlx::query!("DELETE FROM opening_holidays WHERE id = ?", id)
+            .execute(&self.pool)
+            .await?;
+        Ok(res.rows_affected())
+    }
+}
+
+#[derive(Clone)]
+pub struct MySqlOpeningSchedulesRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlOpeningSchedulesRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OpeningSchedulesRepository for MySqlOpeningSchedulesRepository {
+    async fn list(&self) -> sqlx::Result<Vec<OpeningScheduleRow>> {
+        sqlx::query_as!(
+            OpeningScheduleRow,
+            r#"SELECT id as `id: u32`, name, valid_from, valid_to FROM opening_schedules ORDER BY valid_from"#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn list_hours(&self) -> sqlx::Result<Vec<OpeningScheduleHourRow>> {
+        sqlx::query_as!(
+            OpeningScheduleHourRow,
+            r#"SELECT id as `id: u32`, schedule_id as `schedule_id: u32`, weekday as `weekday: u8`, opens_at, closes_at FROM opening_schedule_hours ORDER BY schedule_id, weekday"#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<OpeningScheduleRow>> {
+        sqlx::query_as!(
+            OpeningScheduleRow,
+            r#"SELECT id as `id: u32`, name, valid_from, valid_to FROM opening_schedules WHERE id = ?"#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn get_by_name(&self, name: &str) -> sqlx::Result<Option<OpeningScheduleRow>> {
+        sqlx::query_as!(
+            OpeningScheduleRow,
+            r#"SELECT id as `id: u32`, name, valid_from, valid_to FROM opening_schedules WHERE name = ?"#,
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn insert(&self, schedule: &NewOpeningSchedule) -> sqlx::Result<u32> {
+        let mut tx = self.pool.begin().await?;
+
+        let res = sqlx::query!(
+            r#"INSERT INTO opening_schedules (name, valid_from, valid_to) VALUES (?, ?, ?)"#,
+            schedule.name,
+            schedule.valid_from,
+            schedule.valid_to
+        )
+        .execute(&mut *tx)
+        .await?;
+        let id = res.last_insert_id() as u32;
+
+        for (weekday, opens_at, closes_at) in &schedule.hours {
+            sqlx::query!(
+                r#"INSERT INTO opening_schedule_hours (schedule_id, weekday, opens_at, closes_at) VALUES (?, ?, ?, ?)"#,
+                id,
+                weekday,
+                opens_at,
+                closes_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    async fn update(&self, id: u32, schedule: &NewOpeningSchedule) -> sqlx::Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"UPDATE opening_schedules SET name = ?, valid_from = ?, valid_to = ? WHERE id = ?"#,
+            schedule.name,
+            schedule.valid_from,
+            schedule.valid_to,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM opening_schedule_hours WHERE schedule_id = ?",
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for (weekday, opens_at, closes_at) in &schedule.hours {
+            sqlx::query!(
+                r#"INSERT INTO opening_schedule_hours (schedule_id, weekday, opens_at, closes_at) VALUES (?, ?, ?, ?)"#,
+                id,
+                weekday,
+                opens_at,
+                closes_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    async fn delete(&self, id: u32) -> sqlx::Result<u64> {
+        let res = sqlx::query!("DELETE FROM opening_schedules WHERE id = ?", id)
             .execute(&self.pool)
             .await?;
         Ok(res.rows_affected())
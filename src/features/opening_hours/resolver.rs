@@ -0,0 +1,113 @@
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::{
+    holidays,
+    model::{OpeningExceptionRow, OpeningHolidayRow, OpeningHourRow, OpeningSchedule},
+};
+
+/// Where the hours of a resolved day came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DaySource {
+    Weekly,
+    Exception,
+    Holiday,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedDay {
+    pub date: NaiveDate,
+    pub source: DaySource,
+    /// `None` when the venue is closed for the whole day.
+    pub hours: Option<(NaiveTime, NaiveTime)>,
+    pub note: Option<String>,
+    /// Season whose weekday rows applied, if any.
+    pub schedule: Option<String>,
+}
+
+/// Rows the resolver needs; load them once and resolve any number of days.
+pub struct OpeningRules {
+    pub weekly: Vec<OpeningHourRow>,
+    pub exceptions: Vec<OpeningExceptionRow>,
+    pub holidays: Vec<OpeningHolidayRow>,
+    pub schedules: Vec<OpeningSchedule>,
+}
+
+impl OpeningRules {
+    /// Exceptions win over recurring holidays, which win over the weekly
+    /// hours. The weekly hours come from the season covering the date, or
+    /// from the default week when no season does.
+    pub fn resolve(&self, date: NaiveDate) -> ResolvedDay {
+        if let Some(e) = self
+            .exceptions
+            .iter()
+            .find(|e| e.start_date <= date && date <= e.end_date)
+        {
+            return ResolvedDay {
+                date,
+                source: DaySource::Exception,
+                hours: hours_of(e.is_closed, e.opens_at, e.closes_at),
+                note: e.note.clone(),
+                schedule: None,
+            };
+        }
+
+        if let Some(h) = self
+            .holidays
+            .iter()
+            .find(|h| holidays::occurrence(h, date.year()) == Some(date))
+        {
+            return ResolvedDay {
+                date,
+                source: DaySource::Holiday,
+                hours: hours_of(h.is_closed, h.opens_at, h.closes_at),
+                note: Some(h.name.clone()),
+                schedule: None,
+            };
+        }
+
+        let weekday = date.weekday().number_from_monday() as u8;
+        if let Some(season) = self
+            .schedules
+            .iter()
+            .find(|s| s.schedule.valid_from <= date && date <= s.schedule.valid_to)
+        {
+            return ResolvedDay {
+                date,
+                source: DaySource::Weekly,
+                hours: season
+                    .hours
+                    .iter()
+                    .find(|h| h.weekday == weekday)
+                    .map(|h| (h.opens_at, h.closes_at)),
+                note: None,
+                schedule: Some(season.schedule.name.clone()),
+            };
+        }
+
+        ResolvedDay {
+            date,
+            source: DaySource::Weekly,
+            hours: self
+                .weekly
+                .iter()
+                .find(|w| w.weekday == weekday)
+                .map(|w| (w.opens_at, w.closes_at)),
+            note: None,
+            schedule: None,
+        }
+    }
+}
+
+fn hours_of(
+    is_closed: bool,
+    opens_at: Option<NaiveTime>,
+    closes_at: Option<NaiveTime>,
+) -> Option<(NaiveTime, NaiveTime)> {
+    if is_closed {
+        return None;
+    }
+    opens_at.zip(closes_at)
+}
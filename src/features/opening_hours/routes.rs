@@ -1,56 +1,171 @@
 use axum::{
     Json, Router,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::HeaderMap,
+    response::Response,
     routing::{get, put},
 };
+use chrono::{Datelike, Utc};
+use utoipa::OpenApi;
 
 use super::{
-    data_transfer_objects as dto,
-    model::{OpeningExceptionRow, OpeningHourRow},
+    data_transfer_objects as dto, holidays,
+    model::{OpeningExceptionRow, OpeningHolidayRow, OpeningHourRow, OpeningSchedule},
+    resolver::ResolvedDay,
 };
 
-use crate::{error::AppError, state::AppState};
+use crate::{
+    auth::AdminUser,
+    error::AppError,
+    features::audit::model::AuditActor,
+    infrastructure::response_cache::{CachedResource, CachedResponse},
+    openapi::ErrorResponse,
+    response::{Created, NoContent},
+    state::AppState,
+};
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_hours,
+    replace_week,
+    upsert_hour,
+    delete_hour,
+    list_exceptions,
+    create_exception_range,
+    upsert_exception,
+    delete_exception,
+    list_holidays,
+    create_holiday,
+    update_holiday,
+    delete_holiday,
+    list_schedules,
+    create_schedule,
+    update_schedule,
+    delete_schedule,
+    effective_hours,
+    opening_status
+))]
+pub struct ApiDoc;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
-        .route("/api/opening-hours", get(list_hours))
+        .route("/api/v1/opening-hours", get(list_hours).put(replace_week))
+        .route("/api/v1/opening-hours/effective", get(effective_hours))
+        .route("/api/v1/opening-hours/status", get(opening_status))
         .route(
-            "/api/opening-hours/{weekday}",
+            "/api/v1/opening-hours/{weekday}",
             put(upsert_hour).delete(delete_hour),
         )
-        .route("/api/opening-hours/exceptions", get(list_exceptions))
         .route(
-            "/api/opening-hours/exceptions/{date}",
+            "/api/v1/opening-hours/exceptions",
+            get(list_exceptions).post(create_exception_range),
+        )
+        .route(
+            "/api/v1/opening-hours/exceptions/{date}",
             put(upsert_exception).delete(delete_exception),
         )
+        .route(
+            "/api/v1/opening-hours/schedules",
+            get(list_schedules).post(create_schedule),
+        )
+        .route(
+            "/api/v1/opening-hours/schedules/{id}",
+            put(update_schedule).delete(delete_schedule),
+        )
+        .route(
+            "/api/v1/opening-hours/holidays",
+            get(list_holidays).post(create_holiday),
+        )
+        .route(
+            "/api/v1/opening-hours/holidays/{id}",
+            put(update_holiday).delete(delete_holiday),
+        )
 }
 
 // ----- Hours -----
-async fn list_hours(
-    State(app): State<AppState>,
-) -> Result<Json<Vec<dto::OpeningHourResponse>>, AppError> {
-    let rows: Vec<OpeningHourRow> = app.opening_hours.list().await?;
-    Ok(Json(rows.into_iter().map(hour_row_to_resp).collect()))
+/// Cached; answers `If-None-Match` and `If-Modified-Since` with 304.
+#[utoipa::path(
+    get,
+    path = "/api/v1/opening-hours",
+    tag = "opening hours",
+    responses(
+        (
+            status = 200,
+            body = Vec<dto::OpeningHourResponse>,
+            headers(("ETag" = String), ("Last-Modified" = String))
+        ),
+        (status = 304, description = "Not modified")
+    )
+)]
+async fn list_hours(State(app): State<AppState>, headers: HeaderMap) -> Result<Response, AppError> {
+    let cached = app
+        .response_cache
+        .get_or_load(CachedResource::OpeningHours, async {
+            let rows: Vec<OpeningHourRow> = app.opening_hours.list().await?;
+            let body: Vec<dto::OpeningHourResponse> =
+                rows.into_iter().map(hour_row_to_resp).collect();
+            CachedResponse::json(&body)
+        })
+        .await?;
+    Ok(cached.respond(&headers))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/opening-hours/{weekday}",
+    tag = "opening hours",
+    security(("admin" = [])),
+    params(("weekday" = u8, Path, description = "1 = Monday … 7 = Sunday")),
+    request_body = dto::UpsertOpeningHourRequest,
+    responses((status = 204))
+)]
 async fn upsert_hour(
+    _admin: AdminUser,
     State(app): State<AppState>,
+    actor: AuditActor,
     Path(weekday): Path<u8>,
     Json(body): Json<dto::UpsertOpeningHourRequest>,
-) -> Result<StatusCode, AppError> {
+) -> Result<NoContent, AppError> {
     app.opening_hours
-        .upsert(weekday, &body.opens_at, &body.closes_at)
+        .upsert(weekday, &body.opens_at, &body.closes_at, &actor)
         .await?;
-    Ok(StatusCode::NO_CONTENT)
+    Ok(NoContent)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/opening-hours",
+    tag = "opening hours",
+    security(("admin" = [])),
+    request_body = dto::ReplaceWeekRequest,
+    responses((status = 204))
+)]
+async fn replace_week(
+    _admin: AdminUser,
+    State(app): State<AppState>,
+    actor: AuditActor,
+    Json(body): Json<dto::ReplaceWeekRequest>,
+) -> Result<NoContent, AppError> {
+    app.opening_hours.replace_week(&body.days, &actor).await?;
+    Ok(NoContent)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/opening-hours/{weekday}",
+    tag = "opening hours",
+    security(("admin" = [])),
+    params(("weekday" = u8, Path, description = "1 = Monday … 7 = Sunday")),
+    responses((status = 204), (status = 404, body = ErrorResponse))
+)]
 async fn delete_hour(
+    _admin: AdminUser,
     State(app): State<AppState>,
+    actor: AuditActor,
     Path(weekday): Path<u8>,
-) -> Result<(StatusCode, Json<u64>), AppError> {
-    let affected = app.opening_hours.delete_weekday(weekday).await?;
-    Ok((StatusCode::OK, Json(affected)))
+) -> Result<NoContent, AppError> {
+    app.opening_hours.delete_weekday(weekday, &actor).await?;
+    Ok(NoContent)
 }
 
 fn hour_row_to_resp(r: OpeningHourRow) -> dto::OpeningHourResponse {
@@ -62,6 +177,13 @@ fn hour_row_to_resp(r: OpeningHourRow) -> dto::OpeningHourResponse {
 }
 
 // ----- Exceptions -----
+#[utoipa::path(
+    get,
+    path = "/api/v1/opening-hours/exceptions",
+    tag = "opening hours",
+    params(dto::ExceptionsQuery),
+    responses((status = 200, body = Vec<dto::OpeningExceptionResponse>))
+)]
 async fn list_exceptions(
     State(app): State<AppState>,
     Query(q): Query<dto::ExceptionsQuery>,
@@ -73,30 +195,312 @@ async fn list_exceptions(
     Ok(Json(rows.into_iter().map(exception_row_to_resp).collect()))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/opening-hours/exceptions/{date}",
+    tag = "opening hours",
+    security(("admin" = [])),
+    params(("date" = String, Path, description = "YYYY-MM-DD")),
+    request_body = dto::UpsertOpeningExceptionRequest,
+    responses((status = 204))
+)]
 async fn upsert_exception(
+    _admin: AdminUser,
     State(app): State<AppState>,
+    actor: AuditActor,
     Path(date): Path<String>,
     Json(body): Json<dto::UpsertOpeningExceptionRequest>,
-) -> Result<StatusCode, AppError> {
-    app.opening_exceptions
-        .upsert(&date, body.is_closed, &body.opens_at, &body.closes_at)
-        .await?;
-    Ok(StatusCode::NO_CONTENT)
+) -> Result<NoContent, AppError> {
+    app.opening_exceptions.upsert(&date, &body, &actor).await?;
+    Ok(NoContent)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/opening-hours/exceptions",
+    tag = "opening hours",
+    security(("admin" = [])),
+    request_body = dto::CreateOpeningExceptionRangeRequest,
+    responses((status = 204))
+)]
+async fn create_exception_range(
+    _admin: AdminUser,
+    State(app): State<AppState>,
+    actor: AuditActor,
+    Json(body): Json<dto::CreateOpeningExceptionRangeRequest>,
+) -> Result<NoContent, AppError> {
+    app.opening_exceptions.create_range(&body, &actor).await?;
+    Ok(NoContent)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/opening-hours/exceptions/{date}",
+    tag = "opening hours",
+    security(("admin" = [])),
+    params(("date" = String, Path, description = "YYYY-MM-DD, any day the exception covers")),
+    responses((status = 204), (status = 404, body = ErrorResponse))
+)]
 async fn delete_exception(
+    _admin: AdminUser,
     State(app): State<AppState>,
+    actor: AuditActor,
     Path(date): Path<String>,
-) -> Result<(StatusCode, Json<u64>), AppError> {
-    let affected = app.opening_exceptions.delete(&date).await?;
-    Ok((StatusCode::OK, Json(affected)))
+) -> Result<NoContent, AppError> {
+    app.opening_exceptions.delete(&date, &actor).await?;
+    Ok(NoContent)
 }
 
 fn exception_row_to_resp(r: OpeningExceptionRow) -> dto::OpeningExceptionResponse {
     dto::OpeningExceptionResponse {
-        date: r.date.format("%Y-%m-%d").to_string(),
+        date: r.start_date.format("%Y-%m-%d").to_string(),
+        end_date: r.end_date.format("%Y-%m-%d").to_string(),
+        is_closed: r.is_closed,
+        opens_at: r.opens_at.map(|t| t.format("%H:%M:%S").to_string()),
+        closes_at: r.closes_at.map(|t| t.format("%H:%M:%S").to_string()),
+        note: r.note,
+    }
+}
+
+// ----- Recurring Holidays -----
+#[utoipa::path(
+    get,
+    path = "/api/v1/opening-hours/holidays",
+    tag = "opening hours",
+    params(dto::HolidaysQuery),
+    responses((status = 200, body = Vec<dto::OpeningHolidayResponse>))
+)]
+async fn list_holidays(
+    State(app): State<AppState>,
+    Query(q): Query<dto::HolidaysQuery>,
+) -> Result<Json<Vec<dto::OpeningHolidayResponse>>, AppError> {
+    let year = q.year.unwrap_or_else(|| Utc::now().year());
+    let rows: Vec<OpeningHolidayRow> = app.opening_holidays.list().await?;
+    Ok(Json(
+        rows.into_iter()
+            .map(|r| holiday_row_to_resp(r, year))
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/opening-hours/holidays",
+    tag = "opening hours",
+    security(("admin" = [])),
+    request_body = dto::UpsertOpeningHolidayRequest,
+    responses((status = 201, body = dto::OpeningHolidayResponse))
+)]
+async fn create_holiday(
+    _admin: AdminUser,
+    State(app): State<AppState>,
+    actor: AuditActor,
+    Json(body): Json<dto::UpsertOpeningHolidayRequest>,
+) -> Result<Created<dto::OpeningHolidayResponse>, AppError> {
+    let row = app.opening_holidays.create(&body, &actor).await?;
+    Ok(Created {
+        location: format!("/api/v1/opening-hours/holidays/{}", row.id),
+        body: holiday_row_to_resp(row, Utc::now().year()),
+    })
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/opening-hours/holidays/{id}",
+    tag = "opening hours",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    request_body = dto::UpsertOpeningHolidayRequest,
+    responses((status = 200, body = dto::OpeningHolidayResponse))
+)]
+async fn update_holiday(
+    _admin: AdminUser,
+    State(app): State<AppState>,
+    actor: AuditActor,
+    Path(id): Path<u32>,
+    Json(body): Json<dto::UpsertOpeningHolidayRequest>,
+) -> Result<Json<dto::OpeningHolidayResponse>, AppError> {
+    let row = app.opening_holidays.update(id, &body, &actor).await?;
+    Ok(Json(holiday_row_to_resp(row, Utc::now().year())))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/opening-hours/holidays/{id}",
+    tag = "opening hours",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    responses((status = 204))
+)]
+async fn delete_holiday(
+    _admin: AdminUser,
+    State(app): State<AppState>,
+    actor: AuditActor,
+    Path(id): Path<u32>,
+) -> Result<NoContent, AppError> {
+    app.opening_holidays.delete(id, &actor).await?;
+    Ok(NoContent)
+}
+
+fn holiday_row_to_resp(r: OpeningHolidayRow, year: i32) -> dto::OpeningHolidayResponse {
+    let date = holidays::occurrence(&r, year).map(|d| d.format("%Y-%m-%d").to_string());
+    dto::OpeningHolidayResponse {
+        id: r.id,
+        name: r.name,
+        month: r.month,
+        day: r.day,
+        movable: r.movable,
         is_closed: r.is_closed,
         opens_at: r.opens_at.map(|t| t.format("%H:%M:%S").to_string()),
         closes_at: r.closes_at.map(|t| t.format("%H:%M:%S").to_string()),
+        date,
+    }
+}
+
+// ----- Seasonal Schedules -----
+#[utoipa::path(
+    get,
+    path = "/api/v1/opening-hours/schedules",
+    tag = "opening hours",
+    responses((status = 200, body = Vec<dto::OpeningScheduleResponse>))
+)]
+async fn list_schedules(
+    State(app): State<AppState>,
+) -> Result<Json<Vec<dto::OpeningScheduleResponse>>, AppError> {
+    let schedules = app.opening_schedules.list().await?;
+    Ok(Json(schedules.into_iter().map(schedule_to_resp).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/opening-hours/schedules",
+    tag = "opening hours",
+    security(("admin" = [])),
+    request_body = dto::UpsertOpeningScheduleRequest,
+    responses((status = 201, body = dto::OpeningScheduleResponse))
+)]
+async fn create_schedule(
+    _admin: AdminUser,
+    State(app): State<AppState>,
+    actor: AuditActor,
+    Json(body): Json<dto::UpsertOpeningScheduleRequest>,
+) -> Result<Created<dto::OpeningScheduleResponse>, AppError> {
+    let schedule = app.opening_schedules.create(&body, &actor).await?;
+    Ok(Created {
+        location: format!("/api/v1/opening-hours/schedules/{}", schedule.schedule.id),
+        body: schedule_to_resp(schedule),
+    })
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/opening-hours/schedules/{id}",
+    tag = "opening hours",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    request_body = dto::UpsertOpeningScheduleRequest,
+    responses((status = 200, body = dto::OpeningScheduleResponse))
+)]
+async fn update_schedule(
+    _admin: AdminUser,
+    State(app): State<AppState>,
+    actor: AuditActor,
+    Path(id): Path<u32>,
+    Json(body): Json<dto::UpsertOpeningScheduleRequest>,
+) -> Result<Json<dto::OpeningScheduleResponse>, AppError> {
+    let schedule = app.opening_schedules.update(id, &body, &actor).await?;
+    Ok(Json(schedule_to_resp(schedule)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/opening-hours/schedules/{id}",
+    tag = "opening hours",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    responses((status = 204))
+)]
+async fn delete_schedule(
+    _admin: AdminUser,
+    State(app): State<AppState>,
+    actor: AuditActor,
+    Path(id): Path<u32>,
+) -> Result<NoContent, AppError> {
+    app.opening_schedules.delete(id, &actor).await?;
+    Ok(NoContent)
+}
+
+fn schedule_to_resp(s: OpeningSchedule) -> dto::OpeningScheduleResponse {
+    dto::OpeningScheduleResponse {
+        id: s.schedule.id,
+        name: s.schedule.name,
+        valid_from: s.schedule.valid_from.format("%Y-%m-%d").to_string(),
+        valid_to: s.schedule.valid_to.format("%Y-%m-%d").to_string(),
+        hours: s
+            .hours
+            .into_iter()
+            .map(|h| dto::OpeningHourResponse {
+                weekday: h.weekday,
+                opens_at: h.opens_at.format("%H:%M:%S").to_string(),
+                closes_at: h.closes_at.format("%H:%M:%S").to_string(),
+            })
+            .collect(),
+    }
+}
+
+// ----- Effective Hours -----
+#[utoipa::path(
+    get,
+    path = "/api/v1/opening-hours/effective",
+    tag = "opening hours",
+    params(dto::EffectiveQuery),
+    responses((status = 200, body = Vec<dto::EffectiveDayResponse>))
+)]
+async fn effective_hours(
+    State(app): State<AppState>,
+    Query(q): Query<dto::EffectiveQuery>,
+) -> Result<Json<Vec<dto::EffectiveDayResponse>>, AppError> {
+    let today = Utc::now()
+        .with_timezone(&app.config.venue_timezone)
+        .date_naive();
+    let days = app
+        .effective_hours
+        .effective(q.from.as_deref(), q.to.as_deref(), today)
+        .await?;
+    Ok(Json(days.into_iter().map(resolved_day_to_resp).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/opening-hours/status",
+    tag = "opening hours",
+    responses((status = 200, body = dto::OpeningStatusResponse))
+)]
+async fn opening_status(
+    State(app): State<AppState>,
+) -> Result<Json<dto::OpeningStatusResponse>, AppError> {
+    let now = Utc::now().with_timezone(&app.config.venue_timezone);
+    let status = app.effective_hours.status(now).await?;
+    let notice = status.today.note.clone();
+    Ok(Json(dto::OpeningStatusResponse {
+        now: status.now.fixed_offset(),
+        is_open: status.is_open,
+        next_open: status.next_open.map(|t| t.fixed_offset()),
+        next_close: status.next_close.map(|t| t.fixed_offset()),
+        today: resolved_day_to_resp(status.today),
+        notice,
+    }))
+}
+
+fn resolved_day_to_resp(d: ResolvedDay) -> dto::EffectiveDayResponse {
+    dto::EffectiveDayResponse {
+        date: d.date.format("%Y-%m-%d").to_string(),
+        weekday: d.date.weekday().number_from_monday() as u8,
+        source: d.source,
+        is_closed: d.hours.is_none(),
+        opens_at: d.hours.map(|(o, _)| o.format("%H:%M:%S").to_string()),
+        closes_at: d.hours.map(|(_, c)| c.format("%H:%M:%S").to_string()),
+        note: d.note,
+        schedule: d.schedule,
     }
 }
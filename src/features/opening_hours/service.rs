@@ -1,16 +1,35 @@
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
 
-use super::repository::{DynOpeningExceptionsRepo, DynOpeningHoursRepo};
+use super::holidays::MovableHoliday;
+use super::repository::{
+    DynOpeningExceptionsRepo, DynOpeningHolidaysRepo, DynOpeningHoursRepo, DynOpeningSchedulesRepo,
+    NewOpeningHoliday, NewOpeningSchedule,
+};
+use super::resolver::{OpeningRules, ResolvedDay};
 use crate::error::AppError;
-use crate::features::opening_hours::model::{OpeningExceptionRow, OpeningHourRow};
+use crate::features::audit::{
+    model::AuditActor,
+    service::{AuditService, snapshot},
+};
+use crate::features::opening_hours::data_transfer_objects::{
+    CreateOpeningExceptionRangeRequest, UpsertOpeningExceptionRequest, UpsertOpeningHolidayRequest,
+    UpsertOpeningScheduleRequest, WeekdayHoursRequest,
+};
+use crate::features::opening_hours::model::{
+    OpeningExceptionRow, OpeningHolidayRow, OpeningHourRow, OpeningSchedule,
+};
+use crate::infrastructure::response_cache::{CachedResource, ResponseCache};
 
 #[derive(Clone)]
 pub struct OpeningHoursService {
     repo: DynOpeningHoursRepo,
+    audit: AuditService,
+    cache: ResponseCache,
 }
 impl OpeningHoursService {
-    pub fn new(repo: DynOpeningHoursRepo) -> Self {
-        Self { repo }
+    pub fn new(repo: DynOpeningHoursRepo, audit: AuditService, cache: ResponseCache) -> Self {
+        Self { repo, audit, cache }
     }
 
     pub async fn list(&self) -> Result<Vec<OpeningHourRow>, AppError> {
@@ -22,6 +41,7 @@ impl OpeningHoursService {
         weekday: u8,
         opens_at_s: &str,
         closes_at_s: &str,
+        actor: &AuditActor,
     ) -> Result<(), AppError> {
         if !(1..=7).contains(&weekday) {
             return Err(AppError::BadRequest("weekday must be 1..=7"));
@@ -31,24 +51,87 @@ impl OpeningHoursService {
         if opens >= closes {
             return Err(AppError::BadRequest("opens_at must be before closes_at"));
         }
-        Ok(self.repo.upsert(weekday, opens, closes).await?)
+        let previous = self.weekday_snapshot(weekday).await?;
+        self.repo.upsert(weekday, opens, closes).await?;
+        self.cache.invalidate(CachedResource::OpeningHours);
+        let current = self.weekday_snapshot(weekday).await?;
+        self.audit
+            .record_change(
+                actor,
+                "opening_hours.updated",
+                "opening_hours",
+                Some(weekday.to_string()),
+                previous,
+                current,
+            )
+            .await?;
+        Ok(())
     }
 
-    pub async fn delete_weekday(&self, weekday: u8) -> Result<u64, AppError> {
+    /// Validates every day before writing anything, then swaps the whole
+    /// week in one transaction.
+    pub async fn replace_week(
+        &self,
+        days: &[WeekdayHoursRequest],
+        actor: &AuditActor,
+    ) -> Result<(), AppError> {
+        let week = parse_week(days)?;
+        let previous = self.repo.list().await?;
+        self.repo.replace_week(&week).await?;
+        self.cache.invalidate(CachedResource::OpeningHours);
+        let current = self.repo.list().await?;
+        self.audit
+            .record_change(
+                actor,
+                "opening_hours.replaced",
+                "opening_hours",
+                None,
+                snapshot(&previous),
+                snapshot(&current),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_weekday(&self, weekday: u8, actor: &AuditActor) -> Result<(), AppError> {
         if !(1..=7).contains(&weekday) {
             return Err(AppError::BadRequest("weekday must be 1..=7"));
         }
-        Ok(self.repo.delete_weekday(weekday).await?)
+        let previous = self.weekday_snapshot(weekday).await?;
+        if self.repo.delete_weekday(weekday).await? == 0 {
+            return Err(AppError::NotFound("Opening hours not found"));
+        }
+        self.cache.invalidate(CachedResource::OpeningHours);
+        self.audit
+            .record_change(
+                actor,
+                "opening_hours.deleted",
+                "opening_hours",
+                Some(weekday.to_string()),
+                previous,
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn weekday_snapshot(&self, weekday: u8) -> Result<Option<serde_json::Value>, AppError> {
+        let rows = self.repo.list().await?;
+        Ok(rows
+            .iter()
+            .find(|row| row.weekday == weekday)
+            .and_then(snapshot))
     }
 }
 
 #[derive(Clone)]
 pub struct OpeningExceptionsService {
     repo: DynOpeningExceptionsRepo,
+    audit: AuditService,
 }
 impl OpeningExceptionsService {
-    pub fn new(repo: DynOpeningExceptionsRepo) -> Self {
-        Self { repo }
+    pub fn new(repo: DynOpeningExceptionsRepo, audit: AuditService) -> Self {
+        Self { repo, audit }
     }
 
     pub async fn list(
@@ -67,44 +150,588 @@ impl OpeningExceptionsService {
         Ok(self.repo.list(from_d, to_d).await?)
     }
 
+    /// Single-day exception, replacing any exception that starts on `date_s`.
     pub async fn upsert(
         &self,
         date_s: &str,
+        request: &UpsertOpeningExceptionRequest,
+        actor: &AuditActor,
+    ) -> Result<(), AppError> {
+        let date = parse_date(date_s)?;
+        let previous = self.starting_on(date).await?;
+        self.upsert_range(
+            date,
+            date,
+            request.is_closed,
+            &request.opens_at,
+            &request.closes_at,
+            &request.note,
+        )
+        .await?;
+        self.record(actor, "opening_exception.updated", date, previous)
+            .await
+    }
+
+    /// Exception covering every day from `from` to `to` inclusive.
+    pub async fn create_range(
+        &self,
+        request: &CreateOpeningExceptionRangeRequest,
+        actor: &AuditActor,
+    ) -> Result<(), AppError> {
+        let from = parse_date(&request.from)?;
+        let to = parse_date(&request.to)?;
+        if from > to {
+            return Err(AppError::BadRequest("from must not be after to"));
+        }
+        let previous = self.starting_on(from).await?;
+        self.upsert_range(
+            from,
+            to,
+            request.is_closed,
+            &request.opens_at,
+            &request.closes_at,
+            &request.note,
+        )
+        .await?;
+        self.record(actor, "opening_exception.created", from, previous)
+            .await
+    }
+
+    /// Deletes the exception covering `date_s`, which for a range need not
+    /// be its first day.
+    pub async fn delete(&self, date_s: &str, actor: &AuditActor) -> Result<(), AppError> {
+        let date = parse_date(date_s)?;
+        // Exceptions don't overlap, so at most one covers the date.
+        let previous = self.repo.list(Some(date), Some(date)).await?.pop();
+        if self.repo.delete(date).await? == 0 {
+            return Err(AppError::NotFound("Opening exception not found"));
+        }
+        let start_date = previous.as_ref().map_or(date, |row| row.start_date);
+        self.record(actor, "opening_exception.deleted", start_date, previous)
+            .await
+    }
+
+    async fn starting_on(&self, date: NaiveDate) -> Result<Option<OpeningExceptionRow>, AppError> {
+        let rows = self.repo.list(Some(date), Some(date)).await?;
+        Ok(rows.into_iter().find(|row| row.start_date == date))
+    }
+
+    /// Audits the exception starting on `date` against its state before
+    /// the change.
+    async fn record(
+        &self,
+        actor: &AuditActor,
+        action: &'static str,
+        date: NaiveDate,
+        previous: Option<OpeningExceptionRow>,
+    ) -> Result<(), AppError> {
+        let current = self.starting_on(date).await?;
+        self.audit
+            .record_change(
+                actor,
+                action,
+                "opening_exception",
+                Some(date.format("%Y-%m-%d").to_string()),
+                previous.as_ref().and_then(snapshot),
+                current.as_ref().and_then(snapshot),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn upsert_range(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
         is_closed: bool,
         opens_at: &Option<String>,
         closes_at: &Option<String>,
+        note: &Option<String>,
     ) -> Result<(), AppError> {
-        let date = parse_date(date_s)?;
-        let (o, c) = if is_closed {
-            (None, None)
-        } else {
-            let o = opens_at.as_deref().ok_or(AppError::BadRequest(
-                "opens_at required when is_closed=false",
-            ))?;
-            let c = closes_at.as_deref().ok_or(AppError::BadRequest(
-                "closes_at required when is_closed=false",
-            ))?;
-            let o_t = parse_time(o)?;
-            let c_t = parse_time(c)?;
-            if o_t >= c_t {
-                return Err(AppError::BadRequest("opens_at must be before closes_at"));
+        let (o, c) = parse_hours(is_closed, opens_at, closes_at)?;
+        if !self
+            .repo
+            .upsert(from, to, is_closed, o, c, note.as_deref())
+            .await?
+        {
+            return Err(AppError::Conflict(
+                "Exception overlaps an existing exception",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct OpeningHolidaysService {
+    repo: DynOpeningHolidaysRepo,
+    audit: AuditService,
+}
+impl OpeningHolidaysService {
+    pub fn new(repo: DynOpeningHolidaysRepo, audit: AuditService) -> Self {
+        Self { repo, audit }
+    }
+
+    pub async fn list(&self) -> Result<Vec<OpeningHolidayRow>, AppError> {
+        Ok(self.repo.list().await?)
+    }
+
+    pub async fn create(
+        &self,
+        request: &UpsertOpeningHolidayRequest,
+        actor: &AuditActor,
+    ) -> Result<OpeningHolidayRow, AppError> {
+        let holiday = validate_holiday(request)?;
+        let id = self.repo.insert(&holiday).await?;
+        let row = self
+            .repo
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Failed to fetch newly created holiday"))?;
+        self.audit
+            .record_change(
+                actor,
+                "opening_holiday.created",
+                "opening_holiday",
+                Some(id.to_string()),
+                None,
+                snapshot(&row),
+            )
+            .await?;
+        Ok(row)
+    }
+
+    pub async fn update(
+        &self,
+        id: u32,
+        request: &UpsertOpeningHolidayRequest,
+        actor: &AuditActor,
+    ) -> Result<OpeningHolidayRow, AppError> {
+        let holiday = validate_holiday(request)?;
+        let previous = self
+            .repo
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Holiday not found"))?;
+        self.repo.update(id, &holiday).await?;
+        let row = self
+            .repo
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Holiday not found"))?;
+        self.audit
+            .record_change(
+                actor,
+                "opening_holiday.updated",
+                "opening_holiday",
+                Some(id.to_string()),
+                snapshot(&previous),
+                snapshot(&row),
+            )
+            .await?;
+        Ok(row)
+    }
+
+    pub async fn delete(&self, id: u32, actor: &AuditActor) -> Result<(), AppError> {
+        let previous = self.repo.get_by_id(id).await?;
+        if self.repo.delete(id).await? == 0 {
+            return Err(AppError::NotFound("Holiday not found"));
+        }
+        self.audit
+            .record_change(
+                actor,
+                "opening_holiday.deleted",
+                "opening_holiday",
+                Some(id.to_string()),
+                previous.as_ref().and_then(snapshot),
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct OpeningSchedulesService {
+    repo: DynOpeningSchedulesRepo,
+    audit: AuditService,
+}
+impl OpeningSchedulesService {
+    pub fn new(repo: DynOpeningSchedulesRepo, audit: AuditService) -> Self {
+        Self { repo, audit }
+    }
+
+    pub async fn list(&self) -> Result<Vec<OpeningSchedule>, AppError> {
+        load_schedules(&self.repo).await
+    }
+
+    pub async fn create(
+        &self,
+        request: &UpsertOpeningScheduleRequest,
+        actor: &AuditActor,
+    ) -> Result<OpeningSchedule, AppError> {
+        let schedule = self.validate_schedule(None, request).await?;
+        let id = self.repo.insert(&schedule).await?;
+        let created = self
+            .get(id)
+            .await?
+            .ok_or(AppError::NotFound("Failed to fetch newly created schedule"))?;
+        self.audit
+            .record_change(
+                actor,
+                "opening_schedule.created",
+                "opening_schedule",
+                Some(id.to_string()),
+                None,
+                snapshot(&created),
+            )
+            .await?;
+        Ok(created)
+    }
+
+    pub async fn update(
+        &self,
+        id: u32,
+        request: &UpsertOpeningScheduleRequest,
+        actor: &AuditActor,
+    ) -> Result<OpeningSchedule, AppError> {
+        let previous = self
+            .get(id)
+            .await?
+            .ok_or(AppError::NotFound("Schedule not found"))?;
+        let schedule = self.validate_schedule(Some(id), request).await?;
+        self.repo.update(id, &schedule).await?;
+        let updated = self
+            .get(id)
+            .await?
+            .ok_or(AppError::NotFound("Schedule not found"))?;
+        self.audit
+            .record_change(
+                actor,
+                "opening_schedule.updated",
+                "opening_schedule",
+                Some(id.to_string()),
+                snapshot(&previous),
+                snapshot(&updated),
+            )
+            .await?;
+        Ok(updated)
+    }
+
+    pub async fn delete(&self, id: u32, actor: &AuditActor) -> Result<(), AppError> {
+        let previous = self.get(id).await?;
+        if self.repo.delete(id).await? == 0 {
+            return Err(AppError::NotFound("Schedule not found"));
+        }
+        self.audit
+            .record_change(
+                actor,
+                "opening_schedule.deleted",
+                "opening_schedule",
+                Some(id.to_string()),
+                previous.as_ref().and_then(snapshot),
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get(&self, id: u32) -> Result<Option<OpeningSchedule>, AppError> {
+        Ok(load_schedules(&self.repo)
+            .await?
+            .into_iter()
+            .find(|s| s.schedule.id == id))
+    }
+
+    /// `id` is the schedule being updated, which may overlap itself.
+    async fn validate_schedule(
+        &self,
+        id: Option<u32>,
+        request: &UpsertOpeningScheduleRequest,
+    ) -> Result<NewOpeningSchedule, AppError> {
+        let name = request.name.trim();
+        if name.is_empty() {
+            return Err(AppError::BadRequest("name must not be empty"));
+        }
+        let valid_from = parse_date(&request.valid_from)?;
+        let valid_to = parse_date(&request.valid_to)?;
+        if valid_from > valid_to {
+            return Err(AppError::BadRequest(
+                "valid_from must not be after valid_to",
+            ));
+        }
+
+        let hours = parse_week(&request.hours)?;
+
+        if let Some(existing) = self.repo.get_by_name(name).await? {
+            if Some(existing.id) != id {
+                return Err(AppError::Conflict("Schedule name is already in use"));
+            }
+        }
+        let overlaps = self.repo.list().await?.into_iter().any(|other| {
+            Some(other.id) != id && other.valid_from <= valid_to && valid_from <= other.valid_to
+        });
+        if overlaps {
+            return Err(AppError::Conflict("Schedule overlaps another season"));
+        }
+
+        Ok(NewOpeningSchedule {
+            name: name.to_string(),
+            valid_from,
+            valid_to,
+            hours,
+        })
+    }
+}
+
+async fn load_schedules(repo: &DynOpeningSchedulesRepo) -> Result<Vec<OpeningSchedule>, AppError> {
+    let mut hours = repo.list_hours().await?;
+    Ok(repo
+        .list()
+        .await?
+        .into_iter()
+        .map(|schedule| {
+            let (own, rest) = hours.drain(..).partition(|h| h.schedule_id == schedule.id);
+            hours = rest;
+            OpeningSchedule {
+                schedule,
+                hours: own,
             }
-            (Some(o_t), Some(c_t))
+        })
+        .collect())
+}
+
+/// Longest window `effective` will resolve in one request.
+const MAX_EFFECTIVE_DAYS: u64 = 366;
+/// How far ahead `status` looks for the next opening.
+const STATUS_LOOKAHEAD_DAYS: u64 = 31;
+
+pub struct OpeningStatus {
+    pub now: DateTime<Tz>,
+    pub is_open: bool,
+    pub today: ResolvedDay,
+    pub next_open: Option<DateTime<Tz>>,
+    pub next_close: Option<DateTime<Tz>>,
+}
+
+/// Merges weekly hours, exceptions and holidays into the hours that
+/// actually apply; every endpoint that needs "the hours on day X" goes
+/// through here.
+#[derive(Clone)]
+pub struct EffectiveHoursService {
+    hours: DynOpeningHoursRepo,
+    exceptions: DynOpeningExceptionsRepo,
+    holidays: DynOpeningHolidaysRepo,
+    schedules: DynOpeningSchedulesRepo,
+}
+impl EffectiveHoursService {
+    pub fn new(
+        hours: DynOpeningHoursRepo,
+        exceptions: DynOpeningExceptionsRepo,
+        holidays: DynOpeningHolidaysRepo,
+        schedules: DynOpeningSchedulesRepo,
+    ) -> Self {
+        Self {
+            hours,
+            exceptions,
+            holidays,
+            schedules,
+        }
+    }
+
+    /// Resolved hours for `from..=to`, defaulting to the week starting `today`.
+    pub async fn effective(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+        today: NaiveDate,
+    ) -> Result<Vec<ResolvedDay>, AppError> {
+        let from = match from {
+            Some(s) => parse_date(s)?,
+            None => today,
+        };
+        let to = match to {
+            Some(s) => parse_date(s)?,
+            None => from + Days::new(6),
+        };
+        if from > to {
+            return Err(AppError::BadRequest("from must not be after to"));
+        }
+        if (to - from).num_days() as u64 >= MAX_EFFECTIVE_DAYS {
+            return Err(AppError::BadRequest("date range is limited to 366 days"));
+        }
+        self.resolve_range(from, to).await
+    }
+
+    pub async fn status(&self, now: DateTime<Tz>) -> Result<OpeningStatus, AppError> {
+        let tz = now.timezone();
+        let today = now.date_naive();
+        let until = today + Days::new(STATUS_LOOKAHEAD_DAYS);
+        let days = self.resolve_range(today, until).await?;
+
+        let at = |date: NaiveDate, time: NaiveTime| {
+            tz.from_local_datetime(&date.and_time(time)).earliest()
+        };
+        let intervals: Vec<(DateTime<Tz>, DateTime<Tz>)> = days
+            .iter()
+            .filter_map(|d| {
+                let (opens, closes) = d.hours?;
+                Some((at(d.date, opens)?, at(d.date, closes)?))
+            })
+            .collect();
+
+        let is_open = intervals
+            .iter()
+            .any(|(opens, closes)| *opens <= now && now < *closes);
+        let next_open = intervals
+            .iter()
+            .map(|(opens, _)| *opens)
+            .find(|opens| *opens > now);
+        let next_close = intervals
+            .iter()
+            .map(|(_, closes)| *closes)
+            .find(|closes| *closes > now);
+
+        Ok(OpeningStatus {
+            now,
+            is_open,
+            today: days[0].clone(),
+            next_open,
+            next_close,
+        })
+    }
+
+    async fn resolve_range(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ResolvedDay>, AppError> {
+        let rules = OpeningRules {
+            weekly: self.hours.list().await?,
+            exceptions: self.exceptions.list(Some(from), Some(to)).await?,
+            holidays: self.holidays.list().await?,
+            schedules: load_schedules(&self.schedules).await?,
         };
-        Ok(self.repo.upsert(date, is_closed, o, c).await?)
+        Ok(from
+            .iter_days()
+            .take_while(|d| *d <= to)
+            .map(|d| rules.resolve(d))
+            .collect())
     }
+}
 
-    pub async fn delete(&self, date_s: &str) -> Result<u64, AppError> {
-        Ok(self.repo.delete(parse_date(date_s)?).await?)
+fn validate_holiday(request: &UpsertOpeningHolidayRequest) -> Result<NewOpeningHoliday, AppError> {
+    if request.name.trim().is_empty() {
+        return Err(AppError::BadRequest("name must not be empty"));
     }
+
+    let (month, day, movable) = match (&request.movable, request.month, request.day) {
+        (Some(key), None, None) => {
+            let movable = MovableHoliday::parse(key)
+                .ok_or(AppError::BadRequest("unknown movable holiday"))?;
+            (None, None, Some(movable.as_str()))
+        }
+        (None, Some(month), Some(day)) => {
+            // 2024 is a leap year, so 29 February is accepted.
+            if NaiveDate::from_ymd_opt(2024, month.into(), day.into()).is_none() {
+                return Err(AppError::BadRequest("month and day must form a valid date"));
+            }
+            (Some(month), Some(day), None)
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "provide either movable or both month and day",
+            ));
+        }
+    };
+
+    let (opens_at, closes_at) =
+        parse_hours(request.is_closed, &request.opens_at, &request.closes_at)?;
+
+    Ok(NewOpeningHoliday {
+        name: request.name.trim().to_string(),
+        month,
+        day,
+        movable,
+        is_closed: request.is_closed,
+        opens_at,
+        closes_at,
+    })
 }
 
 // ---- helpers ----
+/// Parses a list of weekday rows, collecting every problem instead of
+/// stopping at the first one.
+fn parse_week(days: &[WeekdayHoursRequest]) -> Result<Vec<(u8, NaiveTime, NaiveTime)>, AppError> {
+    let mut week: Vec<(u8, NaiveTime, NaiveTime)> = Vec::with_capacity(days.len());
+    let mut seen: Vec<u8> = Vec::with_capacity(days.len());
+    let mut errors = Vec::new();
+
+    for day in days {
+        let mut fail = |msg: &str| errors.push(format!("weekday {}: {}", day.weekday, msg));
+
+        if !(1..=7).contains(&day.weekday) {
+            fail("weekday must be 1..=7");
+            continue;
+        }
+        // Checked before the times so a repeat is reported even when its
+        // hours don't parse.
+        if seen.contains(&day.weekday) {
+            fail("weekday listed more than once");
+            continue;
+        }
+        seen.push(day.weekday);
+        let (opens, closes) = match (parse_time(&day.opens_at), parse_time(&day.closes_at)) {
+            (Ok(o), Ok(c)) => (o, c),
+            (o, c) => {
+                if let Err(e) = o {
+                    fail(&format!("opens_at: {e}"));
+                }
+                if let Err(e) = c {
+                    fail(&format!("closes_at: {e}"));
+                }
+                continue;
+            }
+        };
+        if opens >= closes {
+            fail("opens_at must be before closes_at");
+            continue;
+        }
+        week.push((day.weekday, opens, closes));
+    }
+
+    if !errors.is_empty() {
+        return Err(AppError::Validation(errors));
+    }
+    Ok(week)
+}
+
 fn parse_time(s: &str) -> Result<NaiveTime, AppError> {
     NaiveTime::parse_from_str(s, "%H:%M:%S")
         .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
         .map_err(|_| AppError::BadRequest("time must be HH:MM[:SS]"))
 }
+fn parse_hours(
+    is_closed: bool,
+    opens_at: &Option<String>,
+    closes_at: &Option<String>,
+) -> Result<(Option<NaiveTime>, Option<NaiveTime>), AppError> {
+    if is_closed {
+        return Ok((None, None));
+    }
+    let o = opens_at.as_deref().ok_or(AppError::BadRequest(
+        "opens_at required when is_closed=false",
+    ))?;
+    let c = closes_at.as_deref().ok_or(AppError::BadRequest(
+        "closes_at required when is_closed=false",
+    ))?;
+    let o_t = parse_time(o)?;
+    let c_t = parse_time(c)?;
+    if o_t >= c_t {
+        return Err(AppError::BadRequest("opens_at must be before closes_at"));
+    }
+    Ok((Some(o_t), Some(c_t)))
+}
 fn parse_date(s: &str) -> Result<NaiveDate, AppError> {
     NaiveDate::parse_from_str(s, "%Y-%m-%d")
         .map_err(|_| AppError::BadRequest("date must be YYYY-MM-DD"))
@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::model::PaymentStatus;
+
+/// Deposit the customer still has to pay, returned with a new booking.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PaymentResponse {
+    pub amount_cents: u32,
+    pub currency: String,
+    pub status: PaymentStatus,
+    pub checkout_url: String,
+    /// The booking is released if the payment has not gone through by then.
+    pub expires_at: DateTime<Utc>,
+}
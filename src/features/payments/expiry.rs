@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use chrono::Utc;
+
+use super::service::PaymentsService;
+
+/// Releases bookings whose deposit was not paid in time, every `interval`.
+pub fn spawn_sweeper(service: PaymentsService, interval: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            match service.release_unpaid(Utc::now()).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!(count, "unpaid bookings released"),
+                Err(error) => tracing::error!(%error, "releasing unpaid bookings failed"),
+            }
+        }
+    })
+}
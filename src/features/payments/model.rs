@@ -0,0 +1,90 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    MySql,
+    encode::IsNull,
+    error::BoxDynError,
+    mysql::{MySqlTypeInfo, MySqlValueRef},
+};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PaymentRow {
+    pub id: u32,
+    pub booking_id: u32,
+    pub provider_payment_id: String,
+    /// Provider's id for the captured money, used for refunds.
+    pub charge_reference: Option<String>,
+    pub amount_cents: u32,
+    pub currency: String,
+    pub status: PaymentStatus,
+    pub checkout_url: String,
+    /// Same as the booking's payment deadline.
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentStatus {
+    Pending,
+    Succeeded,
+    /// Declined, abandoned or timed out.
+    Failed,
+    Refunded,
+}
+
+impl PaymentStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PaymentStatus::Pending => "pending",
+            PaymentStatus::Succeeded => "succeeded",
+            PaymentStatus::Failed => "failed",
+            PaymentStatus::Refunded => "refunded",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(PaymentStatus::Pending),
+            "succeeded" => Some(PaymentStatus::Succeeded),
+            "failed" => Some(PaymentStatus::Failed),
+            "refunded" => Some(PaymentStatus::Refunded),
+            _ => None,
+        }
+    }
+}
+
+// Stored as VARCHAR, like `BookingStatus`.
+impl sqlx::Type<MySql> for PaymentStatus {
+    fn type_info() -> MySqlTypeInfo {
+        <str as sqlx::Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        <str as sqlx::Type<MySql>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, MySql> for PaymentStatus {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let s = <&str as sqlx::Decode<MySql>>::decode(value)?;
+        PaymentStatus::parse(s).ok_or_else(|| format!("unknown payment status {s:?}").into())
+    }
+}
+
+impl sqlx::Encode<'_, MySql> for PaymentStatus {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, BoxDynError> {
+        <&str as sqlx::Encode<MySql>>::encode(self.as_str(), buf)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewPayment {
+    pub booking_id: u32,
+    pub provider: &'static str,
+    pub provider_payment_id: String,
+    pub amount_cents: u32,
+    pub currency: &'static str,
+    pub checkout_url: String,
+    pub expires_at: NaiveDateTime,
+}
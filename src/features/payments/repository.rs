@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use sqlx::{MySql, Pool};
+
+use super::model::{NewPayment, PaymentRow, PaymentStatus};
+
+#[async_trait]
+pub trait PaymentsRepository: Send + Sync {
+    async fn insert(&self, payment: &NewPayment) -> sqlx::Result<PaymentRow>;
+    async fn get_by_provider_id(
+        &self,
+        provider_payment_id: &str,
+    ) -> sqlx::Result<Option<PaymentRow>>;
+    /// Most recent payment made for the booking.
+    async fn get_for_booking(&self, booking_id: u32) -> sqlx::Result<Option<PaymentRow>>;
+    /// Records the provider's final word on a pending payment. A success
+    /// also overrides a failure from the payment timing out, as the money
+    /// was taken anyway. `false` when nothing changed, e.g. a webhook
+    /// delivered twice.
+    async fn settle(
+        &self,
+        id: u32,
+        status: PaymentStatus,
+        charge_reference: Option<&str>,
+    ) -> sqlx::Result<bool>;
+    /// Fails every pending payment of the booking.
+    async fn fail_pending_for_booking(&self, booking_id: u32) -> sqlx::Result<()>;
+    async fn mark_refunded(&self, id: u32, refund_reference: &str) -> sqlx::Result<()>;
+}
+
+pub type DynamicPaymentsRepository = std::sync::Arc<dyn PaymentsRepository>;
+
+#[derive(Clone)]
+pub struct MySqlPaymentsRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlPaymentsRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PaymentsRepository for MySqlPaymentsRepository {
+    async fn insert(&self, payment: &NewPayment) -> sqlx::Result<PaymentRow> {
+        let result = sqlx::query!(
+            r#"INSERT INTO payments (booking_id, provider, provider_payment_id, amount_cents, currency, checkout_url, expires_at) VALUES (?,?,?,?,?,?,?)"#,
+            payment.booking_id,
+            payment.provider,
+            payment.provider_payment_id,
+            payment.amount_cents,
+            payment.currency,
+            payment.checkout_url,
+            payment.expires_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query_as!(
+            PaymentRow,
+            r#"
+            SELECT
+                id, booking_id, provider_payment_id, charge_reference, amount_cents,
+                currency, status as `status: PaymentStatus`, checkout_url, expires_at
+            FROM payments
+            WHERE id = ?
+            "#,
+            result.last_insert_id() as u32
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn get_by_provider_id(
+        &self,
+        provider_payment_id: &str,
+    ) -> sqlx::Result<Option<PaymentRow>> {
+        sqlx::query_as!(
+            PaymentRow,
+            r#"
+            SELECT
+                id, booking_id, provider_payment_id, charge_reference, amount_cents,
+                currency, status as `status: PaymentStatus`, checkout_url, expires_at
+            FROM payments
+            WHERE provider_payment_id = ?
+            "#,
+            provider_payment_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn get_for_booking(&self, booking_id: u32) -> sqlx::Result<Option<PaymentRow>> {
+        sqlx::query_as!(
+            PaymentRow,
+            r#"
+            SELECT
+                id, booking_id, provider_payment_id, charge_reference, amount_cents,
+                currency, status as `status: PaymentStatus`, checkout_url, expires_at
+            FROM payments
+            WHERE booking_id = ?
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+            booking_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn settle(
+        &self,
+        id: u32,
+        status: PaymentStatus,
+        charge_reference: Option<&str>,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"UPDATE payments SET status = ?, charge_reference = COALESCE(?, charge_reference) WHERE id = ? AND (status = 'pending' OR (status = 'failed' AND ? = 'succeeded'))"#,
+            status,
+            charge_reference,
+            id,
+            status
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn fail_pending_for_booking(&self, booking_id: u32) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"UPDATE payments SET status = 'failed' WHERE booking_id = ? AND status = 'pending'"#,
+            booking_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_refunded(&self, id: u32, refund_reference: &str) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"UPDATE payments SET status = 'refunded', refund_reference = ?, refunded_at = CURRENT_TIMESTAMP(6) WHERE id = ?"#,
+            refund_reference,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use axum::{Router, body::Bytes, extract::State, http::HeaderMap, routing::post};
+
+use utoipa::OpenApi;
+
+use crate::{error::AppError, response::NoContent, state::AppState};
+
+#[derive(OpenApi)]
+#[openapi(paths(webhook))]
+pub struct ApiDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/v1/payments/webhook", post(webhook))
+}
+
+/// Called by the payment provider. The raw body is needed to check the
+/// signature.
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments/webhook",
+    tag = "payments",
+    request_body(content = String, description = "Provider event, signed"),
+    responses((status = 204), (status = 404, description = "Payments are not enabled"))
+)]
+async fn webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<NoContent, AppError> {
+    let payments = state
+        .payments
+        .as_ref()
+        .ok_or(AppError::NotFound("Payments are not enabled"))?;
+    payments.handle_webhook(&headers, &body).await?;
+    Ok(NoContent)
+}
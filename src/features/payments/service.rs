@@ -0,0 +1,311 @@
+use axum::http::HeaderMap;
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc};
+use chrono_tz::Tz;
+
+use super::{
+    model::{NewPayment, PaymentRow, PaymentStatus},
+    repository::DynamicPaymentsRepository,
+};
+use crate::{
+    config::PaymentsConfig,
+    error::AppError,
+    features::{
+        bookings::{
+            model::{BookingRow, BookingStatus},
+            repository::DynamicBookingsRepository,
+        },
+        calendars::repository::DynamicCalendarsRepository,
+        events::bus::{AvailabilityEvent, EventBus},
+        notifications::templates,
+    },
+    infrastructure::payments::{
+        CheckoutRequest, DynamicPaymentProvider, PaymentError, PaymentOutcome,
+    },
+};
+
+const CURRENCY: &str = "EUR";
+
+/// When a deposit is taken and what happens to it afterwards.
+#[derive(Debug, Clone)]
+pub struct DepositPolicy {
+    weekdays: Vec<u8>,
+    from: NaiveTime,
+    percent: u32,
+    payment_timeout: Duration,
+    refund_cutoff: Duration,
+}
+
+impl DepositPolicy {
+    pub fn from_config(config: &PaymentsConfig) -> Self {
+        Self {
+            weekdays: config.deposit_weekdays.clone(),
+            from: config.deposit_from,
+            percent: config.deposit_percent,
+            payment_timeout: Duration::minutes(config.payment_timeout_minutes.into()),
+            refund_cutoff: Duration::hours(config.refund_cutoff_hours.into()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PaymentsService {
+    provider: DynamicPaymentProvider,
+    policy: DepositPolicy,
+    repository: DynamicPaymentsRepository,
+    bookings: DynamicBookingsRepository,
+    calendars: DynamicCalendarsRepository,
+    timezone: Tz,
+    events: EventBus,
+}
+
+impl PaymentsService {
+    pub fn new(
+        provider: DynamicPaymentProvider,
+        policy: DepositPolicy,
+        repository: DynamicPaymentsRepository,
+        bookings: DynamicBookingsRepository,
+        calendars: DynamicCalendarsRepository,
+        timezone: Tz,
+        events: EventBus,
+    ) -> Self {
+        Self {
+            provider,
+            policy,
+            repository,
+            bookings,
+            calendars,
+            timezone,
+            events,
+        }
+    }
+
+    /// Deposit for a booking starting at `start`, or `None` when none is
+    /// needed. Only bookings with a price can require one.
+    pub fn deposit_for(&self, start: DateTime<Utc>, price_cents: Option<u32>) -> Option<u32> {
+        let local = start.with_timezone(&self.timezone);
+        let weekday = local.weekday().number_from_monday() as u8;
+        if !self.policy.weekdays.contains(&weekday) || local.time() < self.policy.from {
+            return None;
+        }
+        let amount = (u64::from(price_cents?) * u64::from(self.policy.percent)).div_ceil(100);
+        u32::try_from(amount).ok().filter(|&amount| amount > 0)
+    }
+
+    /// Deadline for paying a booking made at `now`.
+    pub fn payment_deadline(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now + self.policy.payment_timeout
+    }
+
+    /// Opens a checkout with the provider for a booking waiting for payment.
+    pub async fn start_checkout(
+        &self,
+        booking: &BookingRow,
+        calendar_name: &str,
+        amount_cents: u32,
+        expires_at: DateTime<Utc>,
+    ) -> Result<PaymentRow, AppError> {
+        let request = CheckoutRequest {
+            booking_id: booking.id,
+            amount_cents,
+            currency: CURRENCY,
+            description: format!("Deposit for booking #{} ({calendar_name})", booking.id),
+            customer_email: booking.customer_email.clone(),
+            expires_at,
+        };
+        let checkout = self
+            .provider
+            .create_checkout(&request)
+            .await
+            .map_err(|error| {
+                tracing::error!(%error, booking_id = booking.id, "creating checkout failed");
+                AppError::Internal("Payment provider is unavailable")
+            })?;
+
+        Ok(self
+            .repository
+            .insert(&NewPayment {
+                booking_id: booking.id,
+                provider: self.provider.name(),
+                provider_payment_id: checkout.provider_payment_id,
+                amount_cents,
+                currency: CURRENCY,
+                checkout_url: checkout.checkout_url,
+                expires_at: expires_at.naive_utc(),
+            })
+            .await?)
+    }
+
+    /// Applies a payment notification. A successful payment confirms the
+    /// booking, or is refunded when the booking was already released; a
+    /// failed one releases the booking.
+    pub async fn handle_webhook(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), AppError> {
+        let event = match self.provider.parse_webhook(headers, body) {
+            Ok(Some(event)) => event,
+            Ok(None) => return Ok(()),
+            Err(PaymentError::InvalidSignature) => {
+                return Err(AppError::Unauthorized("Invalid webhook signature"));
+            }
+            Err(error) => {
+                tracing::warn!(%error, "rejected payment webhook");
+                return Err(AppError::BadRequest("Invalid webhook payload"));
+            }
+        };
+
+        let payment = self
+            .repository
+            .get_by_provider_id(&event.provider_payment_id)
+            .await?
+            .ok_or(AppError::NotFound("Payment not found"))?;
+
+        match event.outcome {
+            PaymentOutcome::Succeeded => {
+                if !self
+                    .repository
+                    .settle(
+                        payment.id,
+                        PaymentStatus::Succeeded,
+                        event.charge_reference.as_deref(),
+                    )
+                    .await?
+                {
+                    return Ok(());
+                }
+
+                let calendar_name = self.calendar_name(payment.booking_id).await?;
+                let timezone = self.timezone;
+                let confirmed = self
+                    .bookings
+                    .confirm_payment(
+                        payment.booking_id,
+                        Box::new(move |row| {
+                            templates::booking_confirmation(row, &calendar_name, timezone)
+                        }),
+                    )
+                    .await?;
+                if confirmed.is_none() {
+                    tracing::warn!(
+                        booking_id = payment.booking_id,
+                        "payment arrived after the booking was released, refunding"
+                    );
+                    let payment = PaymentRow {
+                        charge_reference: event.charge_reference,
+                        ..payment
+                    };
+                    self.refund(&payment).await?;
+                }
+            }
+            PaymentOutcome::Failed => {
+                if !self
+                    .repository
+                    .settle(payment.id, PaymentStatus::Failed, None)
+                    .await?
+                {
+                    return Ok(());
+                }
+                self.release(payment.booking_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Cancels every booking whose payment deadline has passed. Returns how
+    /// many were released.
+    pub async fn release_unpaid(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let expired = self.bookings.list_unpaid_expired(now.naive_utc()).await?;
+        let mut released = 0;
+        for booking in expired {
+            self.repository.fail_pending_for_booking(booking.id).await?;
+            if self.release(booking.id).await? {
+                released += 1;
+            }
+        }
+        Ok(released)
+    }
+
+    /// Refunds the deposit of a cancelled booking when it was cancelled
+    /// early enough.
+    pub async fn refund_for_cancellation(
+        &self,
+        booking: &BookingRow,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let Some(payment) = self.repository.get_for_booking(booking.id).await? else {
+            return Ok(());
+        };
+        if payment.status != PaymentStatus::Succeeded {
+            return Ok(());
+        }
+        if booking.starts_at_utc.and_utc() - now < self.policy.refund_cutoff {
+            tracing::info!(
+                booking_id = booking.id,
+                "cancelled too late for a deposit refund"
+            );
+            return Ok(());
+        }
+        self.refund(&payment).await
+    }
+
+    async fn refund(&self, payment: &PaymentRow) -> Result<(), AppError> {
+        let refund_reference = self
+            .provider
+            .refund(
+                &payment.provider_payment_id,
+                payment.charge_reference.as_deref(),
+                payment.amount_cents,
+            )
+            .await
+            .map_err(|error| {
+                tracing::error!(%error, payment_id = payment.id, "refund failed");
+                AppError::Internal("Refunding the deposit failed")
+            })?;
+        self.repository
+            .mark_refunded(payment.id, &refund_reference)
+            .await?;
+        Ok(())
+    }
+
+    /// Cancels a booking still waiting for payment and emails the customer.
+    /// `false` when it was confirmed or cancelled in the meantime; confirming
+    /// bumps the version, so a payment arriving after the read wins.
+    async fn release(&self, booking_id: u32) -> Result<bool, AppError> {
+        let Some(pending) = self
+            .bookings
+            .get(booking_id)
+            .await?
+            .filter(|booking| booking.status == BookingStatus::PendingPayment)
+        else {
+            return Ok(false);
+        };
+
+        let calendar_name = self.calendar_name(booking_id).await?;
+        let timezone = self.timezone;
+        let Some(row) = self
+            .bookings
+            .cancel(
+                booking_id,
+                Some(pending.version),
+                Some(Box::new(move |row| {
+                    templates::booking_cancellation(row, &calendar_name, timezone)
+                })),
+            )
+            .await?
+        else {
+            return Ok(false);
+        };
+        self.events
+            .publish(AvailabilityEvent::booking_cancelled(&row));
+        Ok(true)
+    }
+
+    async fn calendar_name(&self, booking_id: u32) -> Result<String, AppError> {
+        let Some(booking) = self.bookings.get(booking_id).await? else {
+            return Ok(String::new());
+        };
+        Ok(self
+            .calendars
+            .get_by_id(booking.calendar_id)
+            .await?
+            .map(|c| c.name)
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpsertPricingRuleRequest {
+    pub name: String,
+    pub calendar_id: Option<u32>,
+    pub table_type: Option<String>,
+    pub weekday: Option<u8>, // 1 = Monday … 7 = Sunday
+    pub starts_at: String,   // "HH:MM"
+    pub ends_at: String,     // "HH:MM"; not after starts_at = past midnight
+    pub customer_category: Option<String>,
+    pub hourly_rate_cents: u32,
+    pub priority: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PricingRuleResponse {
+    pub id: u32,
+    pub name: String,
+    pub calendar_id: Option<u32>,
+    pub table_type: Option<String>,
+    pub weekday: Option<u8>,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub customer_category: Option<String>,
+    pub hourly_rate_cents: u32,
+    pub priority: i32,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct QuoteQuery {
+    pub calendar_id: u32,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuoteLineResponse {
+    pub rule_id: u32,
+    pub description: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub minutes: u32,
+    pub hourly_rate_cents: u32,
+    pub amount_cents: u32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuoteResponse {
+    pub calendar_id: u32,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub currency: &'static str,
+    pub customer_category: Option<String>,
+    pub lines: Vec<QuoteLineResponse>,
+    pub subtotal_cents: u32,
+    pub minimum_charge_applied: bool,
+    pub rounding_adjustment_cents: i64,
+    pub total_cents: u32,
+}
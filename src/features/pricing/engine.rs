@@ -0,0 +1,355 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+
+use super::model::PricingRuleRow;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PricingSettings {
+    /// Totals below this are raised to it.
+    pub minimum_charge_cents: u32,
+    /// Totals are rounded to the nearest multiple; 0 disables rounding.
+    pub rounding_cents: u32,
+}
+
+/// What is being priced.
+pub struct Slot<'a> {
+    pub calendar_id: u32,
+    pub table_type: Option<&'a str>,
+    pub customer_category: Option<&'a str>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Consecutive minutes charged under the same rule.
+#[derive(Debug, Clone)]
+pub struct QuoteLine {
+    pub rule_id: u32,
+    pub rule_name: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub minutes: u32,
+    pub hourly_rate_cents: u32,
+    pub amount_cents: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Quote {
+    pub lines: Vec<QuoteLine>,
+    pub subtotal_cents: u32,
+    pub minimum_charge_applied: bool,
+    /// Change made by rounding; may be negative.
+    pub rounding_adjustment_cents: i64,
+    pub total_cents: u32,
+}
+
+/// Prices the slot minute by minute in venue time. `None` when some minute
+/// is not covered by any rule.
+pub fn quote(
+    rules: &[PricingRuleRow],
+    slot: &Slot,
+    timezone: Tz,
+    settings: PricingSettings,
+) -> Option<Quote> {
+    let minutes = (slot.end - slot.start).num_minutes();
+    let mut lines: Vec<QuoteLine> = Vec::new();
+
+    for i in 0..minutes {
+        let at = slot.start + Duration::minutes(i);
+        let local = at.with_timezone(&timezone).naive_local();
+        let rule = best_rule(rules, slot, local)?;
+
+        match lines.last_mut() {
+            Some(line) if line.rule_id == rule.id => {
+                line.end = at + Duration::minutes(1);
+                line.minutes += 1;
+            }
+            _ => lines.push(QuoteLine {
+                rule_id: rule.id,
+                rule_name: rule.name.clone(),
+                start: at,
+                end: at + Duration::minutes(1),
+                minutes: 1,
+                hourly_rate_cents: rule.hourly_rate_cents,
+                amount_cents: 0,
+            }),
+        }
+    }
+
+    for line in &mut lines {
+        let cents = u64::from(line.hourly_rate_cents) * u64::from(line.minutes);
+        line.amount_cents = ((cents + 30) / 60) as u32;
+    }
+
+    let subtotal_cents: u32 = lines.iter().map(|line| line.amount_cents).sum();
+    let minimum_charge_applied = subtotal_cents < settings.minimum_charge_cents;
+    let charged = subtotal_cents.max(settings.minimum_charge_cents);
+    let total_cents = round_to(charged, settings.rounding_cents);
+
+    Some(Quote {
+        lines,
+        subtotal_cents,
+        minimum_charge_applied,
+        rounding_adjustment_cents: i64::from(total_cents) - i64::from(charged),
+        total_cents,
+    })
+}
+
+fn best_rule<'r>(
+    rules: &'r [PricingRuleRow],
+    slot: &Slot,
+    local: NaiveDateTime,
+) -> Option<&'r PricingRuleRow> {
+    rules
+        .iter()
+        .filter(|rule| matches(rule, slot, local))
+        .max_by(|a, b| {
+            (a.priority, specificity(a))
+                .cmp(&(b.priority, specificity(b)))
+                // Older rules win ties.
+                .then(b.id.cmp(&a.id))
+        })
+}
+
+fn matches(rule: &PricingRuleRow, slot: &Slot, local: NaiveDateTime) -> bool {
+    rule.calendar_id.is_none_or(|id| id == slot.calendar_id)
+        && rule
+            .table_type
+            .as_deref()
+            .is_none_or(|t| Some(t) == slot.table_type)
+        && rule
+            .customer_category
+            .as_deref()
+            .is_none_or(|c| Some(c) == slot.customer_category)
+        && band_weekday(rule.starts_at, rule.ends_at, local)
+            .is_some_and(|weekday| rule.weekday.is_none_or(|w| u32::from(w) == weekday))
+}
+
+/// Weekday (1 = Monday) the minute counts towards when it falls inside the
+/// band. The part of a band after midnight belongs to the day it started on.
+fn band_weekday(starts_at: NaiveTime, ends_at: NaiveTime, local: NaiveDateTime) -> Option<u32> {
+    let time = local.time();
+    let weekday = local.weekday().number_from_monday();
+    if starts_at < ends_at {
+        (starts_at <= time && time < ends_at).then_some(weekday)
+    } else if time >= starts_at {
+        Some(weekday)
+    } else if time < ends_at {
+        Some(local.weekday().pred().number_from_monday())
+    } else {
+        None
+    }
+}
+
+fn specificity(rule: &PricingRuleRow) -> u8 {
+    u8::from(rule.calendar_id.is_some()) * 8
+        + u8::from(rule.table_type.is_some()) * 4
+        + u8::from(rule.customer_category.is_some()) * 2
+        + u8::from(rule.weekday.is_some())
+}
+
+/// Nearest multiple of `step`, halves rounded up.
+fn round_to(cents: u32, step: u32) -> u32 {
+    if step == 0 {
+        return cents;
+    }
+    (cents + step / 2) / step * step
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone};
+
+    use super::*;
+
+    const NO_ADJUSTMENTS: PricingSettings = PricingSettings {
+        minimum_charge_cents: 0,
+        rounding_cents: 0,
+    };
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn local(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // June 2025 starts on a Sunday, so the 2nd is a Monday.
+        NaiveDate::from_ymd_opt(2025, 6, day)
+            .unwrap()
+            .and_time(time(hour, minute))
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn rule(id: u32, starts_at: NaiveTime, ends_at: NaiveTime, rate: u32) -> PricingRuleRow {
+        PricingRuleRow {
+            id,
+            name: format!("rule {id}"),
+            calendar_id: None,
+            table_type: None,
+            weekday: None,
+            starts_at,
+            ends_at,
+            customer_category: None,
+            hourly_rate_cents: rate,
+            priority: 0,
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+        }
+    }
+
+    fn slot(start: DateTime<Utc>, end: DateTime<Utc>) -> Slot<'static> {
+        Slot {
+            calendar_id: 1,
+            table_type: None,
+            customer_category: None,
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn band_across_midnight_counts_towards_the_day_it_started() {
+        let (starts_at, ends_at) = (time(22, 0), time(2, 0));
+
+        assert_eq!(band_weekday(starts_at, ends_at, local(3, 22, 0)), Some(2));
+        assert_eq!(band_weekday(starts_at, ends_at, local(3, 23, 59)), Some(2));
+        assert_eq!(band_weekday(starts_at, ends_at, local(4, 1, 59)), Some(2));
+        assert_eq!(band_weekday(starts_at, ends_at, local(4, 2, 0)), None);
+        assert_eq!(band_weekday(starts_at, ends_at, local(4, 12, 0)), None);
+        // Sunday night runs into Monday morning.
+        assert_eq!(band_weekday(starts_at, ends_at, local(2, 0, 30)), Some(7));
+    }
+
+    #[test]
+    fn band_within_a_day() {
+        let (starts_at, ends_at) = (time(9, 0), time(17, 0));
+
+        assert_eq!(band_weekday(starts_at, ends_at, local(2, 9, 0)), Some(1));
+        assert_eq!(band_weekday(starts_at, ends_at, local(2, 16, 59)), Some(1));
+        assert_eq!(band_weekday(starts_at, ends_at, local(2, 17, 0)), None);
+        assert_eq!(band_weekday(starts_at, ends_at, local(2, 8, 59)), None);
+    }
+
+    #[test]
+    fn best_rule_prefers_priority_then_specificity_then_older_rules() {
+        let all_day = (time(0, 0), time(0, 0));
+        let generic = rule(1, all_day.0, all_day.1, 1000);
+        let for_calendar = PricingRuleRow {
+            calendar_id: Some(1),
+            ..rule(2, all_day.0, all_day.1, 1000)
+        };
+        let prioritised = PricingRuleRow {
+            priority: 1,
+            ..rule(3, all_day.0, all_day.1, 1000)
+        };
+        let slot = slot(utc(2025, 6, 2, 12, 0), utc(2025, 6, 2, 13, 0));
+        let at = local(2, 12, 0);
+        let best = |rules: &[PricingRuleRow]| best_rule(rules, &slot, at).map(|rule| rule.id);
+
+        assert_eq!(
+            best(&[generic.clone(), for_calendar.clone(), prioritised]),
+            Some(3)
+        );
+        assert_eq!(best(&[generic.clone(), for_calendar]), Some(2));
+        let newer = rule(4, all_day.0, all_day.1, 1000);
+        assert_eq!(best(&[newer, generic]), Some(1));
+    }
+
+    #[test]
+    fn rules_for_other_calendars_do_not_match() {
+        let other = PricingRuleRow {
+            calendar_id: Some(2),
+            ..rule(1, time(0, 0), time(0, 0), 1000)
+        };
+        let slot = slot(utc(2025, 6, 2, 12, 0), utc(2025, 6, 2, 13, 0));
+
+        assert!(best_rule(&[other], &slot, local(2, 12, 0)).is_none());
+    }
+
+    #[test]
+    fn uncovered_minute_leaves_the_slot_unpriced() {
+        let rules = [rule(1, time(10, 0), time(11, 0), 1000)];
+        let slot = slot(utc(2025, 6, 2, 10, 0), utc(2025, 6, 2, 11, 1));
+
+        assert!(quote(&rules, &slot, chrono_tz::UTC, NO_ADJUSTMENTS).is_none());
+    }
+
+    #[test]
+    fn each_line_is_rounded_to_the_nearest_cent() {
+        let rules = [
+            rule(1, time(10, 0), time(10, 30), 1001),
+            rule(2, time(10, 30), time(11, 0), 1001),
+        ];
+        let slot = slot(utc(2025, 6, 2, 10, 0), utc(2025, 6, 2, 11, 0));
+
+        let quote = quote(&rules, &slot, chrono_tz::UTC, NO_ADJUSTMENTS).unwrap();
+
+        // 1001 * 30 / 60 = 500.5 per line, rounded up on each.
+        let amounts: Vec<u32> = quote.lines.iter().map(|line| line.amount_cents).collect();
+        assert_eq!(amounts, [501, 501]);
+        assert_eq!(quote.subtotal_cents, 1002);
+        assert_eq!(quote.total_cents, 1002);
+    }
+
+    #[test]
+    fn minimum_charge_raises_small_totals() {
+        let rules = [rule(1, time(0, 0), time(0, 0), 600)];
+        let slot = slot(utc(2025, 6, 2, 10, 0), utc(2025, 6, 2, 10, 30));
+        let settings = PricingSettings {
+            minimum_charge_cents: 1000,
+            rounding_cents: 0,
+        };
+
+        let quote = quote(&rules, &slot, chrono_tz::UTC, settings).unwrap();
+
+        assert_eq!(quote.subtotal_cents, 300);
+        assert!(quote.minimum_charge_applied);
+        assert_eq!(quote.total_cents, 1000);
+    }
+
+    #[test]
+    fn rounding_is_reported_as_an_adjustment() {
+        let rules = [rule(1, time(0, 0), time(0, 0), 1234)];
+        let slot = slot(utc(2025, 6, 2, 10, 0), utc(2025, 6, 2, 11, 0));
+        let settings = PricingSettings {
+            minimum_charge_cents: 0,
+            rounding_cents: 50,
+        };
+
+        let quote = quote(&rules, &slot, chrono_tz::UTC, settings).unwrap();
+
+        assert_eq!(quote.total_cents, 1250);
+        assert_eq!(quote.rounding_adjustment_cents, 16);
+    }
+
+    #[test]
+    fn round_to_rounds_halves_up() {
+        assert_eq!(round_to(1224, 50), 1200);
+        assert_eq!(round_to(1225, 50), 1250);
+        assert_eq!(round_to(1234, 50), 1250);
+        assert_eq!(round_to(1250, 50), 1250);
+        assert_eq!(round_to(1234, 0), 1234);
+    }
+
+    #[test]
+    fn dst_change_charges_the_minutes_actually_booked() {
+        let rules = [
+            rule(1, time(0, 0), time(3, 0), 600),
+            rule(2, time(3, 0), time(0, 0), 1200),
+        ];
+        // 01:00 to 04:00 in Berlin on the night clocks skip 02:00 to 03:00.
+        let slot = slot(utc(2025, 3, 30, 0, 0), utc(2025, 3, 30, 2, 0));
+
+        let quote = quote(&rules, &slot, chrono_tz::Europe::Berlin, NO_ADJUSTMENTS).unwrap();
+
+        let lines: Vec<(u32, u32)> = quote
+            .lines
+            .iter()
+            .map(|line| (line.rule_id, line.minutes))
+            .collect();
+        assert_eq!(lines, [(1, 60), (2, 60)]);
+        assert_eq!(quote.total_cents, 600 + 1200);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod data_transfer_objects;
+pub mod engine;
+pub mod model;
+pub mod repository;
+pub mod routes;
+pub mod service;
+
+pub use routes::{ApiDoc, routes};
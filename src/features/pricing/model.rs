@@ -0,0 +1,36 @@
+use chrono::{NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// Hourly rate for a time band. Empty filters match everything; when several
+/// rules match, the highest `priority` wins, then the most specific rule.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PricingRuleRow {
+    pub id: u32,
+    pub name: String,
+    pub calendar_id: Option<u32>,
+    pub table_type: Option<String>,
+    /// 1 = Monday … 7 = Sunday.
+    pub weekday: Option<u8>,
+    /// A band whose end is not after its start runs past midnight.
+    pub starts_at: NaiveTime,
+    pub ends_at: NaiveTime,
+    pub customer_category: Option<String>,
+    pub hourly_rate_cents: u32,
+    pub priority: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Rule ready to be written; times and filters are already validated.
+#[derive(Debug, Clone)]
+pub struct NewPricingRule {
+    pub name: String,
+    pub calendar_id: Option<u32>,
+    pub table_type: Option<String>,
+    pub weekday: Option<u8>,
+    pub starts_at: NaiveTime,
+    pub ends_at: NaiveTime,
+    pub customer_category: Option<String>,
+    pub hourly_rate_cents: u32,
+    pub priority: i32,
+}
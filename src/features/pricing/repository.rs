@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use sqlx::{MySql, Pool};
+
+use super::model::{NewPricingRule, PricingRuleRow};
+
+#[async_trait]
+pub trait PricingRulesRepository: Send + Sync {
+    async fn list(&self) -> sqlx::Result<Vec<PricingRuleRow>>;
+    async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<PricingRuleRow>>;
+    async fn insert(&self, rule: &NewPricingRule) -> sqlx::Result<u32>;
+    async fn update(&self, id: u32, rule: &NewPricingRule) -> sqlx::Result<bool>;
+    async fn delete(&self, id: u32) -> sqlx::Result<bool>;
+}
+
+pub type DynamicPricingRulesRepository = std::sync::Arc<dyn PricingRulesRepository>;
+
+#[derive(Clone)]
+pub struct MySqlPricingRulesRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlPricingRulesRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PricingRulesRepository for MySqlPricingRulesRepository {
+    async fn list(&self) -> sqlx::Result<Vec<PricingRuleRow>> {
+        sqlx::query_as!(
+            PricingRuleRow,
+            r#"
+            SELECT
+                id, name, calendar_id, table_type, weekday as `weekday?: u8`,
+                starts_at, ends_at, customer_category, hourly_rate_cents, priority,
+                created_at, updated_at
+            FROM pricing_rules
+            ORDER BY priority DESC, id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<PricingRuleRow>> {
+        sqlx::query_as!(
+            PricingRuleRow,
+            r#"
+            SELECT
+                id, name, calendar_id, table_type, weekday as `weekday?: u8`,
+                starts_at, ends_at, customer_category, hourly_rate_cents, priority,
+                created_at, updated_at
+            FROM pricing_rules
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn insert(&self, rule: &NewPricingRule) -> sqlx::Result<u32> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO pricing_rules
+                (name, calendar_id, table_type, weekday, starts_at, ends_at, customer_category, hourly_rate_cents, priority)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            rule.name,
+            rule.calendar_id,
+            rule.table_type,
+            rule.weekday,
+            rule.starts_at,
+            rule.ends_at,
+            rule.customer_category,
+            rule.hourly_rate_cents,
+            rule.priority
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.last_insert_id() as u32)
+    }
+
+    async fn update(&self, id: u32, rule: &NewPricingRule) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE pricing_rules
+            SET name = ?, calendar_id = ?, table_type = ?, weekday = ?, starts_at = ?, ends_at = ?,
+                customer_category = ?, hourly_rate_cents = ?, priority = ?
+            WHERE id = ?
+            "#,
+            rule.name,
+            rule.calendar_id,
+            rule.table_type,
+            rule.weekday,
+            rule.starts_at,
+            rule.ends_at,
+            rule.customer_category,
+            rule.hourly_rate_cents,
+            rule.priority,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, id: u32) -> sqlx::Result<bool> {
+        let result = sqlx::query!(r#"DELETE FROM pricing_rules WHERE id = ?"#, id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,176 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    routing::{get, put},
+};
+use utoipa::OpenApi;
+
+use super::{
+    data_transfer_objects::{
+        PricingRuleResponse, QuoteLineResponse, QuoteQuery, QuoteResponse, UpsertPricingRuleRequest,
+    },
+    model::PricingRuleRow,
+};
+use crate::{
+    auth::{AdminUser, CurrentCustomer},
+    error::AppError,
+    response::{Created, NoContent},
+    state::AppState,
+};
+
+#[derive(OpenApi)]
+#[openapi(paths(quote, list_rules, create_rule, update_rule, delete_rule))]
+pub struct ApiDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/bookings/quote", get(quote))
+        .route(
+            "/api/v1/admin/pricing-rules",
+            get(list_rules).post(create_rule),
+        )
+        .route(
+            "/api/v1/admin/pricing-rules/{id}",
+            put(update_rule).delete(delete_rule),
+        )
+}
+
+/// Logged-in customers are quoted their category's rates.
+#[utoipa::path(
+    get,
+    path = "/api/v1/bookings/quote",
+    tag = "pricing",
+    params(QuoteQuery),
+    responses((status = 200, body = QuoteResponse))
+)]
+async fn quote(
+    State(state): State<AppState>,
+    customer: Option<CurrentCustomer>,
+    Query(query): Query<QuoteQuery>,
+) -> Result<Json<QuoteResponse>, AppError> {
+    let category = customer.and_then(|CurrentCustomer(c)| c.category);
+    let quote = state
+        .pricing
+        .quote(
+            query.calendar_id,
+            query.start,
+            query.end,
+            category.as_deref(),
+        )
+        .await?;
+
+    Ok(Json(QuoteResponse {
+        calendar_id: query.calendar_id,
+        start: query.start,
+        end: query.end,
+        currency: "EUR",
+        customer_category: category,
+        lines: quote
+            .lines
+            .into_iter()
+            .map(|line| QuoteLineResponse {
+                rule_id: line.rule_id,
+                description: line.rule_name,
+                start: line.start,
+                end: line.end,
+                minutes: line.minutes,
+                hourly_rate_cents: line.hourly_rate_cents,
+                amount_cents: line.amount_cents,
+            })
+            .collect(),
+        subtotal_cents: quote.subtotal_cents,
+        minimum_charge_applied: quote.minimum_charge_applied,
+        rounding_adjustment_cents: quote.rounding_adjustment_cents,
+        total_cents: quote.total_cents,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/pricing-rules",
+    tag = "pricing",
+    security(("admin" = [])),
+    responses((status = 200, body = Vec<PricingRuleResponse>))
+)]
+async fn list_rules(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<PricingRuleResponse>>, AppError> {
+    let rows = state.pricing.list_rules().await?;
+    Ok(Json(rows.into_iter().map(row_to_response).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/pricing-rules",
+    tag = "pricing",
+    security(("admin" = [])),
+    request_body = UpsertPricingRuleRequest,
+    responses((status = 201, body = PricingRuleResponse))
+)]
+async fn create_rule(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Json(body): Json<UpsertPricingRuleRequest>,
+) -> Result<Created<PricingRuleResponse>, AppError> {
+    let row = state.pricing.create_rule(body).await?;
+    tracing::info!(admin = %admin.name, id = row.id, "pricing rule created");
+
+    Ok(Created {
+        location: format!("/api/v1/admin/pricing-rules/{}", row.id),
+        body: row_to_response(row),
+    })
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/pricing-rules/{id}",
+    tag = "pricing",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    request_body = UpsertPricingRuleRequest,
+    responses((status = 200, body = PricingRuleResponse))
+)]
+async fn update_rule(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+    Json(body): Json<UpsertPricingRuleRequest>,
+) -> Result<Json<PricingRuleResponse>, AppError> {
+    let row = state.pricing.update_rule(id, body).await?;
+    tracing::info!(admin = %admin.name, id, "pricing rule updated");
+    Ok(Json(row_to_response(row)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/pricing-rules/{id}",
+    tag = "pricing",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    responses((status = 204))
+)]
+async fn delete_rule(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<NoContent, AppError> {
+    state.pricing.delete_rule(id).await?;
+    tracing::info!(admin = %admin.name, id, "pricing rule deleted");
+    Ok(NoContent)
+}
+
+fn row_to_response(row: PricingRuleRow) -> PricingRuleResponse {
+    PricingRuleResponse {
+        id: row.id,
+        name: row.name,
+        calendar_id: row.calendar_id,
+        table_type: row.table_type,
+        weekday: row.weekday,
+        starts_at: row.starts_at.format("%H:%M").to_string(),
+        ends_at: row.ends_at.format("%H:%M").to_string(),
+        customer_category: row.customer_category,
+        hourly_rate_cents: row.hourly_rate_cents,
+        priority: row.priority,
+    }
+}
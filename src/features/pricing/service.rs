@@ -0,0 +1,171 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+
+use super::{
+    data_transfer_objects::UpsertPricingRuleRequest,
+    engine::{self, PricingSettings, Quote, Slot},
+    model::{NewPricingRule, PricingRuleRow},
+    repository::DynamicPricingRulesRepository,
+};
+use crate::{
+    error::AppError,
+    features::calendars::{model::CalendarRow, repository::DynamicCalendarsRepository},
+};
+
+/// Longest slot that can be quoted; pricing walks the slot minute by minute.
+const MAX_QUOTE_HOURS: i64 = 24;
+
+#[derive(Clone)]
+pub struct PricingService {
+    repository: DynamicPricingRulesRepository,
+    calendars: DynamicCalendarsRepository,
+    timezone: Tz,
+    settings: PricingSettings,
+}
+
+impl PricingService {
+    pub fn new(
+        repository: DynamicPricingRulesRepository,
+        calendars: DynamicCalendarsRepository,
+        timezone: Tz,
+        settings: PricingSettings,
+    ) -> Self {
+        Self {
+            repository,
+            calendars,
+            timezone,
+            settings,
+        }
+    }
+
+    pub async fn list_rules(&self) -> Result<Vec<PricingRuleRow>, AppError> {
+        Ok(self.repository.list().await?)
+    }
+
+    pub async fn create_rule(
+        &self,
+        request: UpsertPricingRuleRequest,
+    ) -> Result<PricingRuleRow, AppError> {
+        let rule = self.validate(request).await?;
+        let id = self.repository.insert(&rule).await?;
+        self.repository
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound(
+                "Failed to fetch newly created pricing rule",
+            ))
+    }
+
+    pub async fn update_rule(
+        &self,
+        id: u32,
+        request: UpsertPricingRuleRequest,
+    ) -> Result<PricingRuleRow, AppError> {
+        let rule = self.validate(request).await?;
+        if !self.repository.update(id, &rule).await? {
+            return Err(AppError::NotFound("Pricing rule not found"));
+        }
+        self.repository
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Pricing rule not found"))
+    }
+
+    pub async fn delete_rule(&self, id: u32) -> Result<(), AppError> {
+        if !self.repository.delete(id).await? {
+            return Err(AppError::NotFound("Pricing rule not found"));
+        }
+        Ok(())
+    }
+
+    /// Itemised price of a slot for a customer category (`None` for guests
+    /// and customers without one).
+    pub async fn quote(
+        &self,
+        calendar_id: u32,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        customer_category: Option<&str>,
+    ) -> Result<Quote, AppError> {
+        let calendar = self
+            .calendars
+            .get_by_id(calendar_id)
+            .await?
+            .ok_or(AppError::NotFound("Calendar not found"))?;
+        self.price(&calendar, start, end, customer_category)
+            .await?
+            .ok_or(AppError::BadRequest(
+                "No price is defined for the whole slot",
+            ))
+    }
+
+    /// Like `quote`, but `None` when the rules don't cover the slot, so a
+    /// booking can still be made without a price.
+    pub async fn price(
+        &self,
+        calendar: &CalendarRow,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        customer_category: Option<&str>,
+    ) -> Result<Option<Quote>, AppError> {
+        if end <= start {
+            return Err(AppError::BadRequest("end must be after start"));
+        }
+        if end - start > chrono::Duration::hours(MAX_QUOTE_HOURS) {
+            return Err(AppError::BadRequest("slot can be at most 24 hours long"));
+        }
+
+        let rules = self.repository.list().await?;
+        let slot = Slot {
+            calendar_id: calendar.id,
+            table_type: calendar.table_type.as_deref(),
+            customer_category,
+            start,
+            end,
+        };
+        Ok(engine::quote(&rules, &slot, self.timezone, self.settings))
+    }
+
+    async fn validate(
+        &self,
+        request: UpsertPricingRuleRequest,
+    ) -> Result<NewPricingRule, AppError> {
+        let name = request.name.trim();
+        if name.is_empty() {
+            return Err(AppError::BadRequest("name is required"));
+        }
+        if request.weekday.is_some_and(|w| !(1..=7).contains(&w)) {
+            return Err(AppError::BadRequest("weekday must be 1..=7"));
+        }
+        if let Some(calendar_id) = request.calendar_id {
+            self.calendars
+                .get_by_id(calendar_id)
+                .await?
+                .ok_or(AppError::NotFound("Calendar not found"))?;
+        }
+
+        Ok(NewPricingRule {
+            name: name.to_string(),
+            calendar_id: request.calendar_id,
+            table_type: normalize(request.table_type),
+            weekday: request.weekday,
+            starts_at: parse_time(&request.starts_at)?,
+            ends_at: parse_time(&request.ends_at)?,
+            customer_category: normalize(request.customer_category),
+            hourly_rate_cents: request.hourly_rate_cents,
+            priority: request.priority.unwrap_or(0),
+        })
+    }
+}
+
+fn normalize(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_lowercase())
+        .filter(|v| !v.is_empty())
+}
+
+fn parse_time(s: &str) -> Result<NaiveTime, AppError> {
+    NaiveTime::parse_from_str(s, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
+        .map_err(|_| AppError::BadRequest("time must be HH:MM[:SS]"))
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::model::VoucherKind;
+
+/// `code` may be left out to generate one, which suits gift cards sold
+/// over the counter.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpsertVoucherRequest {
+    pub code: Option<String>,
+    pub kind: VoucherKind,
+    pub description: Option<String>,
+    pub percent_off: Option<u8>,
+    pub amount_cents: Option<u32>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub max_uses: Option<u32>,
+    pub calendar_id: Option<u32>,
+    pub table_type: Option<String>,
+    pub weekday: Option<u8>, // 1 = Monday … 7 = Sunday
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VoucherResponse {
+    pub id: u32,
+    pub code: String,
+    pub kind: VoucherKind,
+    pub description: Option<String>,
+    pub percent_off: Option<u8>,
+    pub amount_cents: Option<u32>,
+    pub balance_cents: Option<u32>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub max_uses: Option<u32>,
+    pub times_used: u32,
+    pub calendar_id: Option<u32>,
+    pub table_type: Option<String>,
+    pub weekday: Option<u8>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a customer may see about a code, e.g. a gift card's balance.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicVoucherResponse {
+    pub code: String,
+    pub kind: VoucherKind,
+    pub description: Option<String>,
+    pub percent_off: Option<u8>,
+    pub amount_cents: Option<u32>,
+    pub balance_cents: Option<u32>,
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VoucherRedemptionResponse {
+    pub id: u32,
+    pub booking_id: u32,
+    pub amount_cents: u32,
+    pub reversed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
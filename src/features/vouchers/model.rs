@@ -0,0 +1,122 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    MySql,
+    encode::IsNull,
+    error::BoxDynError,
+    mysql::{MySqlTypeInfo, MySqlValueRef},
+};
+use utoipa::ToSchema;
+
+/// Discount code or gift card. Empty restrictions match every booking.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct VoucherRow {
+    pub id: u32,
+    /// Stored upper-case; customers may type it in any case.
+    pub code: String,
+    pub kind: VoucherKind,
+    pub description: Option<String>,
+    /// Only for `Percentage`.
+    pub percent_off: Option<u8>,
+    /// Amount taken off for `Fixed`, initial value for `GiftCard`.
+    pub amount_cents: Option<u32>,
+    /// What is left on a `GiftCard`.
+    pub balance_cents: Option<u32>,
+    pub valid_from: Option<NaiveDateTime>,
+    pub valid_until: Option<NaiveDateTime>,
+    pub max_uses: Option<u32>,
+    pub times_used: u32,
+    pub calendar_id: Option<u32>,
+    pub table_type: Option<String>,
+    /// 1 = Monday … 7 = Sunday, of the booking's start in venue time.
+    pub weekday: Option<u8>,
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoucherKind {
+    Percentage,
+    Fixed,
+    GiftCard,
+}
+
+impl VoucherKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VoucherKind::Percentage => "percentage",
+            VoucherKind::Fixed => "fixed",
+            VoucherKind::GiftCard => "gift_card",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "percentage" => Some(VoucherKind::Percentage),
+            "fixed" => Some(VoucherKind::Fixed),
+            "gift_card" => Some(VoucherKind::GiftCard),
+            _ => None,
+        }
+    }
+}
+
+// Stored as VARCHAR, like `BookingStatus`.
+impl sqlx::Type<MySql> for VoucherKind {
+    fn type_info() -> MySqlTypeInfo {
+        <str as sqlx::Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        <str as sqlx::Type<MySql>>::compatible(ty)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, MySql> for VoucherKind {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let s = <&str as sqlx::Decode<MySql>>::decode(value)?;
+        VoucherKind::parse(s).ok_or_else(|| format!("unknown voucher kind {s:?}").into())
+    }
+}
+
+impl sqlx::Encode<'_, MySql> for VoucherKind {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, BoxDynError> {
+        <&str as sqlx::Encode<MySql>>::encode(self.as_str(), buf)
+    }
+}
+
+/// Voucher ready to be written; the code and restrictions are validated.
+/// A gift card's balance is only set on insert.
+#[derive(Debug, Clone)]
+pub struct NewVoucher {
+    pub code: String,
+    pub kind: VoucherKind,
+    pub description: Option<String>,
+    pub percent_off: Option<u8>,
+    pub amount_cents: Option<u32>,
+    pub valid_from: Option<NaiveDateTime>,
+    pub valid_until: Option<NaiveDateTime>,
+    pub max_uses: Option<u32>,
+    pub calendar_id: Option<u32>,
+    pub table_type: Option<String>,
+    pub weekday: Option<u8>,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct VoucherRedemptionRow {
+    pub id: u32,
+    pub booking_id: u32,
+    pub amount_cents: u32,
+    /// Set when the booking was cancelled and the use given back.
+    pub reversed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// Discount worked out for a booking; redeemed in the same transaction as
+/// the booking is written.
+#[derive(Debug, Clone, Copy)]
+pub struct AppliedVoucher {
+    pub voucher_id: u32,
+    pub discount_cents: u32,
+}
@@ -0,0 +1,226 @@
+use async_trait::async_trait;
+use sqlx::{MySql, MySqlConnection, Pool};
+
+use super::model::{AppliedVoucher, NewVoucher, VoucherKind, VoucherRedemptionRow, VoucherRow};
+
+/// Uses the voucher for a booking: counts the use, takes the discount off a
+/// gift card's balance and records the redemption. `false` when the
+/// voucher was used up or deactivated since it was checked.
+pub async fn redeem(
+    conn: &mut MySqlConnection,
+    voucher: &AppliedVoucher,
+    booking_id: u32,
+) -> sqlx::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE vouchers
+        SET times_used = times_used + 1,
+            balance_cents = IF(kind = 'gift_card', balance_cents - ?, balance_cents)
+        WHERE id = ? AND active
+            AND (max_uses IS NULL OR times_used < max_uses)
+            AND (kind <> 'gift_card' OR balance_cents >= ?)
+        "#,
+        voucher.discount_cents,
+        voucher.voucher_id,
+        voucher.discount_cents
+    )
+    .execute(&mut *conn)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    sqlx::query!(
+        r#"INSERT INTO voucher_redemptions (voucher_id, booking_id, amount_cents) VALUES (?, ?, ?)"#,
+        voucher.voucher_id,
+        booking_id,
+        voucher.discount_cents
+    )
+    .execute(conn)
+    .await?;
+    Ok(true)
+}
+
+/// Gives back the uses and gift card balance redeemed for a cancelled
+/// booking. The redemptions stay, marked reversed.
+pub async fn reverse_redemptions(conn: &mut MySqlConnection, booking_id: u32) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE vouchers v
+        JOIN voucher_redemptions r ON r.voucher_id = v.id
+        SET v.times_used = v.times_used - 1,
+            v.balance_cents = IF(v.kind = 'gift_card', v.balance_cents + r.amount_cents, v.balance_cents),
+            r.reversed_at = CURRENT_TIMESTAMP(6)
+        WHERE r.booking_id = ? AND r.reversed_at IS NULL
+        "#,
+        booking_id
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+#[async_trait]
+pub trait VouchersRepository: Send + Sync {
+    async fn list(&self) -> sqlx::Result<Vec<VoucherRow>>;
+    async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<VoucherRow>>;
+    async fn get_by_code(&self, code: &str) -> sqlx::Result<Option<VoucherRow>>;
+    async fn insert(&self, voucher: &NewVoucher) -> sqlx::Result<u32>;
+    async fn update(&self, id: u32, voucher: &NewVoucher) -> sqlx::Result<bool>;
+    /// Only vouchers that were never redeemed can be deleted.
+    async fn delete_unused(&self, id: u32) -> sqlx::Result<bool>;
+    async fn list_redemptions(&self, voucher_id: u32) -> sqlx::Result<Vec<VoucherRedemptionRow>>;
+}
+
+pub type DynamicVouchersRepository = std::sync::Arc<dyn VouchersRepository>;
+
+#[derive(Clone)]
+pub struct MySqlVouchersRepository {
+    pool: Pool<MySql>,
+}
+
+impl MySqlVouchersRepository {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl VouchersRepository for MySqlVouchersRepository {
+    async fn list(&self) -> sqlx::Result<Vec<VoucherRow>> {
+        sqlx::query_as!(
+            VoucherRow,
+            r#"
+            SELECT
+                id, code, kind as `kind: VoucherKind`, description,
+                percent_off as `percent_off?: u8`, amount_cents, balance_cents,
+                valid_from, valid_until, max_uses, times_used, calendar_id, table_type,
+                weekday as `weekday?: u8`, active as `active: bool`, created_at
+            FROM vouchers
+            ORDER BY id DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn get_by_id(&self, id: u32) -> sqlx::Result<Option<VoucherRow>> {
+        sqlx::query_as!(
+            VoucherRow,
+            r#"
+            SELECT
+                id, code, kind as `kind: VoucherKind`, description,
+                percent_off as `percent_off?: u8`, amount_cents, balance_cents,
+                valid_from, valid_until, max_uses, times_used, calendar_id, table_type,
+                weekday as `weekday?: u8`, active as `active: bool`, created_at
+            FROM vouchers
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn get_by_code(&self, code: &str) -> sqlx::Result<Option<VoucherRow>> {
+        sqlx::query_as!(
+            VoucherRow,
+            r#"
+            SELECT
+                id, code, kind as `kind: VoucherKind`, description,
+                percent_off as `percent_off?: u8`, amount_cents, balance_cents,
+                valid_from, valid_until, max_uses, times_used, calendar_id, table_type,
+                weekday as `weekday?: u8`, active as `active: bool`, created_at
+            FROM vouchers
+            WHERE code = ?
+            "#,
+            code
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn insert(&self, voucher: &NewVoucher) -> sqlx::Result<u32> {
+        let balance_cents = match voucher.kind {
+            VoucherKind::GiftCard => voucher.amount_cents,
+            _ => None,
+        };
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO vouchers (
+                code, kind, description, percent_off, amount_cents, balance_cents,
+                valid_from, valid_until, max_uses, calendar_id, table_type, weekday, active
+            ) VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?)
+            "#,
+            voucher.code,
+            voucher.kind,
+            voucher.description,
+            voucher.percent_off,
+            voucher.amount_cents,
+            balance_cents,
+            voucher.valid_from,
+            voucher.valid_until,
+            voucher.max_uses,
+            voucher.calendar_id,
+            voucher.table_type,
+            voucher.weekday,
+            voucher.active
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.last_insert_id() as u32)
+    }
+
+    async fn update(&self, id: u32, voucher: &NewVoucher) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE vouchers SET
+                code = ?, description = ?, percent_off = ?, amount_cents = ?,
+                valid_from = ?, valid_until = ?, max_uses = ?, calendar_id = ?,
+                table_type = ?, weekday = ?, active = ?
+            WHERE id = ?
+            "#,
+            voucher.code,
+            voucher.description,
+            voucher.percent_off,
+            voucher.amount_cents,
+            voucher.valid_from,
+            voucher.valid_until,
+            voucher.max_uses,
+            voucher.calendar_id,
+            voucher.table_type,
+            voucher.weekday,
+            voucher.active,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_unused(&self, id: u32) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"DELETE FROM vouchers WHERE id = ? AND NOT EXISTS (SELECT 1 FROM voucher_redemptions WHERE voucher_id = ?)"#,
+            id,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_redemptions(&self, voucher_id: u32) -> sqlx::Result<Vec<VoucherRedemptionRow>> {
+        sqlx::query_as!(
+            VoucherRedemptionRow,
+            r#"
+            SELECT id, booking_id, amount_cents, reversed_at, created_at
+            FROM voucher_redemptions
+            WHERE voucher_id = ?
+            ORDER BY id DESC
+            "#,
+            voucher_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
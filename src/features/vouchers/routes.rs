@@ -0,0 +1,199 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing::get,
+};
+use utoipa::OpenApi;
+
+use super::{
+    data_transfer_objects::{
+        PublicVoucherResponse, UpsertVoucherRequest, VoucherRedemptionResponse, VoucherResponse,
+    },
+    model::VoucherRow,
+};
+use crate::{
+    auth::AdminUser,
+    error::AppError,
+    openapi::ErrorResponse,
+    response::{Created, NoContent},
+    state::AppState,
+};
+
+#[derive(OpenApi)]
+#[openapi(paths(lookup, list, create, get_one, update, delete, redemptions))]
+pub struct ApiDoc;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/v1/vouchers/{code}", get(lookup))
+        .route("/api/v1/admin/vouchers", get(list).post(create))
+        .route(
+            "/api/v1/admin/vouchers/{id}",
+            get(get_one).put(update).delete(delete),
+        )
+        .route("/api/v1/admin/vouchers/{id}/redemptions", get(redemptions))
+}
+
+/// Lets customers check a code and a gift card's remaining balance.
+#[utoipa::path(
+    get,
+    path = "/api/v1/vouchers/{code}",
+    tag = "vouchers",
+    params(("code" = String, Path)),
+    responses((status = 200, body = PublicVoucherResponse), (status = 404, body = ErrorResponse))
+)]
+async fn lookup(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> Result<Json<PublicVoucherResponse>, AppError> {
+    let row = state.vouchers.lookup(&code).await?;
+    Ok(Json(PublicVoucherResponse {
+        code: row.code,
+        kind: row.kind,
+        description: row.description,
+        percent_off: row.percent_off,
+        amount_cents: row.amount_cents,
+        balance_cents: row.balance_cents,
+        valid_until: row.valid_until.map(|at| at.and_utc()),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/vouchers",
+    tag = "vouchers",
+    security(("admin" = [])),
+    responses((status = 200, body = Vec<VoucherResponse>))
+)]
+async fn list(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<VoucherResponse>>, AppError> {
+    let rows = state.vouchers.list().await?;
+    Ok(Json(rows.into_iter().map(row_to_response).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/vouchers/{id}",
+    tag = "vouchers",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    responses((status = 200, body = VoucherResponse), (status = 404, body = ErrorResponse))
+)]
+async fn get_one(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<Json<VoucherResponse>, AppError> {
+    let row = state.vouchers.get(id).await?;
+    Ok(Json(row_to_response(row)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/vouchers",
+    tag = "vouchers",
+    security(("admin" = [])),
+    request_body = UpsertVoucherRequest,
+    responses((status = 201, body = VoucherResponse))
+)]
+async fn create(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Json(body): Json<UpsertVoucherRequest>,
+) -> Result<Created<VoucherResponse>, AppError> {
+    let row = state.vouchers.create(body).await?;
+    tracing::info!(admin = %admin.name, id = row.id, "voucher created");
+
+    Ok(Created {
+        location: format!("/api/v1/admin/vouchers/{}", row.id),
+        body: row_to_response(row),
+    })
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/vouchers/{id}",
+    tag = "vouchers",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    request_body = UpsertVoucherRequest,
+    responses((status = 200, body = VoucherResponse))
+)]
+async fn update(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+    Json(body): Json<UpsertVoucherRequest>,
+) -> Result<Json<VoucherResponse>, AppError> {
+    let row = state.vouchers.update(id, body).await?;
+    tracing::info!(admin = %admin.name, id, "voucher updated");
+    Ok(Json(row_to_response(row)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/vouchers/{id}",
+    tag = "vouchers",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    responses((status = 204))
+)]
+async fn delete(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<NoContent, AppError> {
+    state.vouchers.delete(id).await?;
+    tracing::info!(admin = %admin.name, id, "voucher deleted");
+    Ok(NoContent)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/vouchers/{id}/redemptions",
+    tag = "vouchers",
+    security(("admin" = [])),
+    params(("id" = u32, Path)),
+    responses((status = 200, body = Vec<VoucherRedemptionResponse>))
+)]
+async fn redemptions(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<Json<Vec<VoucherRedemptionResponse>>, AppError> {
+    let rows = state.vouchers.redemptions(id).await?;
+    Ok(Json(
+        rows.into_iter()
+            .map(|row| VoucherRedemptionResponse {
+                id: row.id,
+                booking_id: row.booking_id,
+                amount_cents: row.amount_cents,
+                reversed_at: row.reversed_at.map(|at| at.and_utc()),
+                created_at: row.created_at.and_utc(),
+            })
+            .collect(),
+    ))
+}
+
+fn row_to_response(row: VoucherRow) -> VoucherResponse {
+    VoucherResponse {
+        id: row.id,
+        code: row.code,
+        kind: row.kind,
+        description: row.description,
+        percent_off: row.percent_off,
+        amount_cents: row.amount_cents,
+        balance_cents: row.balance_cents,
+        valid_from: row.valid_from.map(|at| at.and_utc()),
+        valid_until: row.valid_until.map(|at| at.and_utc()),
+        max_uses: row.max_uses,
+        times_used: row.times_used,
+        calendar_id: row.calendar_id,
+        table_type: row.table_type,
+        weekday: row.weekday,
+        active: row.active,
+        created_at: row.created_at.and_utc(),
+    }
+}
@@ -0,0 +1,274 @@
+use chrono::{DateTime, Datelike, Utc};
+use chrono_tz::Tz;
+use rand::Rng;
+
+use super::{
+    data_transfer_objects::UpsertVoucherRequest,
+    model::{AppliedVoucher, NewVoucher, VoucherKind, VoucherRedemptionRow, VoucherRow},
+    repository::DynamicVouchersRepository,
+};
+use crate::{
+    error::AppError,
+    features::calendars::{model::CalendarRow, repository::DynamicCalendarsRepository},
+};
+
+/// Generated codes avoid characters that are easy to misread.
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const GENERATED_CODE_LENGTH: usize = 12;
+
+#[derive(Clone)]
+pub struct VouchersService {
+    repository: DynamicVouchersRepository,
+    calendars: DynamicCalendarsRepository,
+    timezone: Tz,
+}
+
+impl VouchersService {
+    pub fn new(
+        repository: DynamicVouchersRepository,
+        calendars: DynamicCalendarsRepository,
+        timezone: Tz,
+    ) -> Self {
+        Self {
+            repository,
+            calendars,
+            timezone,
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<VoucherRow>, AppError> {
+        Ok(self.repository.list().await?)
+    }
+
+    pub async fn get(&self, id: u32) -> Result<VoucherRow, AppError> {
+        self.repository
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Voucher not found"))
+    }
+
+    pub async fn create(&self, request: UpsertVoucherRequest) -> Result<VoucherRow, AppError> {
+        let voucher = self.validate(request, None).await?;
+        if self.repository.get_by_code(&voucher.code).await?.is_some() {
+            return Err(AppError::Conflict(
+                "A voucher with this code already exists",
+            ));
+        }
+        let id = self.repository.insert(&voucher).await?;
+        self.repository
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Failed to fetch newly created voucher"))
+    }
+
+    /// The kind can't change, and a gift card's balance only moves through
+    /// redemptions.
+    pub async fn update(
+        &self,
+        id: u32,
+        request: UpsertVoucherRequest,
+    ) -> Result<VoucherRow, AppError> {
+        let existing = self
+            .repository
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Voucher not found"))?;
+        let voucher = self.validate(request, Some(&existing)).await?;
+        if self
+            .repository
+            .get_by_code(&voucher.code)
+            .await?
+            .is_some_and(|other| other.id != id)
+        {
+            return Err(AppError::Conflict(
+                "A voucher with this code already exists",
+            ));
+        }
+        self.repository.update(id, &voucher).await?;
+        self.repository
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Voucher not found"))
+    }
+
+    pub async fn delete(&self, id: u32) -> Result<(), AppError> {
+        if self.repository.delete_unused(id).await? {
+            return Ok(());
+        }
+        match self.repository.get_by_id(id).await? {
+            Some(_) => Err(AppError::Conflict(
+                "Voucher has been redeemed; deactivate it instead",
+            )),
+            None => Err(AppError::NotFound("Voucher not found")),
+        }
+    }
+
+    pub async fn redemptions(&self, id: u32) -> Result<Vec<VoucherRedemptionRow>, AppError> {
+        self.repository
+            .get_by_id(id)
+            .await?
+            .ok_or(AppError::NotFound("Voucher not found"))?;
+        Ok(self.repository.list_redemptions(id).await?)
+    }
+
+    /// Active voucher by the code a customer typed.
+    pub async fn lookup(&self, code: &str) -> Result<VoucherRow, AppError> {
+        self.repository
+            .get_by_code(&normalize_code(code))
+            .await?
+            .filter(|voucher| voucher.active)
+            .ok_or(AppError::NotFound("Voucher not found"))
+    }
+
+    /// Checks that the voucher can be used for the booking and works out
+    /// the discount, which never exceeds the price. The use itself is only
+    /// recorded when the booking is written.
+    pub async fn apply(
+        &self,
+        code: &str,
+        calendar: &CalendarRow,
+        start: DateTime<Utc>,
+        price_cents: Option<u32>,
+        now: DateTime<Utc>,
+    ) -> Result<AppliedVoucher, AppError> {
+        let voucher = self
+            .lookup(code)
+            .await
+            .map_err(|_| AppError::BadRequest("Unknown voucher code"))?;
+
+        let now = now.naive_utc();
+        if voucher.valid_from.is_some_and(|from| now < from)
+            || voucher.valid_until.is_some_and(|until| now >= until)
+        {
+            return Err(AppError::BadRequest(
+                "The voucher is not valid at this time",
+            ));
+        }
+        if voucher
+            .max_uses
+            .is_some_and(|max| voucher.times_used >= max)
+            || voucher.balance_cents == Some(0)
+        {
+            return Err(AppError::BadRequest("The voucher has been used up"));
+        }
+
+        let weekday = start
+            .with_timezone(&self.timezone)
+            .weekday()
+            .number_from_monday() as u8;
+        if voucher.calendar_id.is_some_and(|id| id != calendar.id)
+            || voucher
+                .table_type
+                .as_ref()
+                .is_some_and(|table_type| calendar.table_type.as_ref() != Some(table_type))
+            || voucher.weekday.is_some_and(|day| day != weekday)
+        {
+            return Err(AppError::BadRequest(
+                "The voucher does not apply to this booking",
+            ));
+        }
+
+        let price =
+            price_cents.ok_or(AppError::BadRequest("The booking has no price to discount"))?;
+        let discount_cents = match voucher.kind {
+            VoucherKind::Percentage => {
+                let percent = u32::from(voucher.percent_off.unwrap_or(0));
+                // Rounded to the nearest cent.
+                ((u64::from(price) * u64::from(percent) + 50) / 100) as u32
+            }
+            VoucherKind::Fixed => voucher.amount_cents.unwrap_or(0),
+            VoucherKind::GiftCard => voucher.balance_cents.unwrap_or(0),
+        }
+        .min(price);
+
+        Ok(AppliedVoucher {
+            voucher_id: voucher.id,
+            discount_cents,
+        })
+    }
+
+    async fn validate(
+        &self,
+        request: UpsertVoucherRequest,
+        existing: Option<&VoucherRow>,
+    ) -> Result<NewVoucher, AppError> {
+        if existing.is_some_and(|voucher| voucher.kind != request.kind) {
+            return Err(AppError::BadRequest("kind can't be changed"));
+        }
+
+        let mut errors = Vec::new();
+        let code = match request.code.as_deref().map(normalize_code) {
+            Some(code) if code.is_empty() || code.len() > 64 => {
+                errors.push("code must be 1-64 characters".to_string());
+                code
+            }
+            Some(code) => code,
+            None => existing
+                .map(|voucher| voucher.code.clone())
+                .unwrap_or_else(generate_code),
+        };
+        let (percent_off, amount_cents) = match request.kind {
+            VoucherKind::Percentage => {
+                if !request.percent_off.is_some_and(|p| (1..=100).contains(&p)) {
+                    errors.push("percent_off must be 1-100 for percentage vouchers".to_string());
+                }
+                (request.percent_off, None)
+            }
+            VoucherKind::Fixed | VoucherKind::GiftCard => {
+                if !request.amount_cents.is_some_and(|amount| amount > 0) {
+                    errors.push("amount_cents must be positive".to_string());
+                }
+                (None, request.amount_cents)
+            }
+        };
+        if let (Some(from), Some(until)) = (request.valid_from, request.valid_until)
+            && until <= from
+        {
+            errors.push("valid_until must be after valid_from".to_string());
+        }
+        if request.weekday.is_some_and(|w| !(1..=7).contains(&w)) {
+            errors.push("weekday must be 1..=7".to_string());
+        }
+        if !errors.is_empty() {
+            return Err(AppError::Validation(errors));
+        }
+        if let Some(calendar_id) = request.calendar_id {
+            self.calendars
+                .get_by_id(calendar_id)
+                .await?
+                .ok_or(AppError::NotFound("Calendar not found"))?;
+        }
+
+        Ok(NewVoucher {
+            code,
+            kind: request.kind,
+            description: request
+                .description
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty()),
+            percent_off,
+            amount_cents,
+            valid_from: request.valid_from.map(|at| at.naive_utc()),
+            valid_until: request.valid_until.map(|at| at.naive_utc()),
+            max_uses: request.max_uses,
+            calendar_id: request.calendar_id,
+            table_type: request
+                .table_type
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| !t.is_empty()),
+            weekday: request.weekday,
+            active: request.active.unwrap_or(true),
+        })
+    }
+}
+
+fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+fn generate_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..GENERATED_CODE_LENGTH)
+        .map(|_| CODE_ALPHABET[rng.gen_range(0..CODE_ALPHABET.len())] as char)
+        .collect()
+}
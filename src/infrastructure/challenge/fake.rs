@@ -0,0 +1,27 @@
+use std::net::IpAddr;
+
+use async_trait::async_trait;
+
+use super::{ChallengeError, ChallengeVerifier};
+
+/// Passes exactly one response value, for local development and tests.
+pub struct FakeChallengeVerifier {
+    expected: String,
+}
+
+impl FakeChallengeVerifier {
+    pub fn new(expected: String) -> Self {
+        Self { expected }
+    }
+}
+
+#[async_trait]
+impl ChallengeVerifier for FakeChallengeVerifier {
+    async fn verify(
+        &self,
+        response: &str,
+        _remote_ip: Option<IpAddr>,
+    ) -> Result<bool, ChallengeError> {
+        Ok(response == self.expected)
+    }
+}
@@ -0,0 +1,69 @@
+use std::net::IpAddr;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use super::{ChallengeError, ChallengeVerifier};
+
+pub const HCAPTCHA_VERIFY_URL: &str = "https://api.hcaptcha.com/siteverify";
+pub const TURNSTILE_VERIFY_URL: &str = "https://challenges.cloudflare.com/turnstile/v0/siteverify";
+
+/// hCaptcha and Cloudflare Turnstile share the `siteverify` protocol: the
+/// secret, response and client IP are posted as a form and the answer says
+/// whether the response was valid.
+pub struct HttpChallengeVerifier {
+    client: reqwest::Client,
+    verify_url: String,
+    secret: String,
+}
+
+impl HttpChallengeVerifier {
+    pub fn new(verify_url: String, secret: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            verify_url,
+            secret,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+#[async_trait]
+impl ChallengeVerifier for HttpChallengeVerifier {
+    async fn verify(
+        &self,
+        response: &str,
+        remote_ip: Option<IpAddr>,
+    ) -> Result<bool, ChallengeError> {
+        let mut form = vec![
+            ("secret", self.secret.clone()),
+            ("response", response.to_string()),
+        ];
+        if let Some(ip) = remote_ip {
+            form.push(("remoteip", ip.to_string()));
+        }
+
+        let answer: SiteVerifyResponse = self
+            .client
+            .post(&self.verify_url)
+            .form(&form)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| ChallengeError::Provider(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| ChallengeError::Provider(e.to_string()))?;
+
+        if !answer.success {
+            tracing::debug!(errors = ?answer.error_codes, "challenge response rejected");
+        }
+        Ok(answer.success)
+    }
+}
@@ -0,0 +1,56 @@
+mod fake;
+#[cfg(feature = "captcha")]
+mod http;
+
+use std::net::IpAddr;
+
+use async_trait::async_trait;
+use thiserror::Error;
+
+use crate::config::ChallengeConfig;
+
+pub use fake::FakeChallengeVerifier;
+#[cfg(feature = "captcha")]
+pub use http::HttpChallengeVerifier;
+
+#[derive(Debug, Error)]
+pub enum ChallengeError {
+    #[error("{0}")]
+    Provider(String),
+}
+
+/// Checks the response a client got from solving a challenge widget.
+#[async_trait]
+pub trait ChallengeVerifier: Send + Sync {
+    async fn verify(
+        &self,
+        response: &str,
+        remote_ip: Option<IpAddr>,
+    ) -> Result<bool, ChallengeError>;
+}
+
+pub type DynamicChallengeVerifier = std::sync::Arc<dyn ChallengeVerifier>;
+
+/// Verifier named by `CHALLENGE_PROVIDER`.
+pub fn from_config(config: &ChallengeConfig) -> DynamicChallengeVerifier {
+    match config.provider.as_str() {
+        "fake" => std::sync::Arc::new(FakeChallengeVerifier::new(config.secret.clone())),
+        #[cfg(feature = "captcha")]
+        "hcaptcha" => std::sync::Arc::new(HttpChallengeVerifier::new(
+            config
+                .verify_url
+                .clone()
+                .unwrap_or_else(|| http::HCAPTCHA_VERIFY_URL.to_string()),
+            config.secret.clone(),
+        )),
+        #[cfg(feature = "captcha")]
+        "turnstile" => std::sync::Arc::new(HttpChallengeVerifier::new(
+            config
+                .verify_url
+                .clone()
+                .unwrap_or_else(|| http::TURNSTILE_VERIFY_URL.to_string()),
+            config.secret.clone(),
+        )),
+        other => panic!("unsupported CHALLENGE_PROVIDER {other:?}"),
+    }
+}
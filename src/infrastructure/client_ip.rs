@@ -0,0 +1,65 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{HeaderMap, request::Parts},
+};
+
+use crate::state::AppState;
+
+/// Works out the client's address behind the proxies we trust. Their
+/// `X-Forwarded-For` is read right to left, skipping our own proxies; from
+/// anyone else the header is ignored, as it can be forged.
+#[derive(Debug, Clone, Default)]
+pub struct ClientIpResolver {
+    trusted_proxies: Vec<IpAddr>,
+}
+
+impl ClientIpResolver {
+    pub fn new(trusted_proxies: Vec<IpAddr>) -> Self {
+        Self { trusted_proxies }
+    }
+
+    pub fn resolve(&self, headers: &HeaderMap, peer: Option<SocketAddr>) -> Option<IpAddr> {
+        let peer = peer?.ip();
+        if !self.trusted_proxies.contains(&peer) {
+            return Some(peer);
+        }
+
+        let forwarded: Vec<IpAddr> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|part| part.trim().parse().ok())
+            .collect();
+        forwarded
+            .iter()
+            .rev()
+            .find(|ip| !self.trusted_proxies.contains(ip))
+            .or(forwarded.first())
+            .copied()
+            .or(Some(peer))
+    }
+}
+
+/// The requesting client's address, `None` when it can't be told.
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl FromRequestParts<AppState> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| *address);
+        Ok(ClientIp(state.client_ip.resolve(&parts.headers, peer)))
+    }
+}
@@ -0,0 +1,264 @@
+mod store;
+
+use std::{
+    convert::Infallible,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use axum::{
+    Json,
+    body::{Body, to_bytes},
+    extract::{ConnectInfo, Request},
+    http::{HeaderName, HeaderValue, Method, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use tower::{Layer, Service};
+
+use super::{client_ip::ClientIpResolver, security::hash_token};
+
+pub use store::{Claim, DynamicIdempotencyStore, MySqlIdempotencyStore, StoredResponse};
+
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+/// Set on responses served from the store instead of the handler.
+pub const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+const MAX_KEY_LEN: usize = 255;
+/// Bodies are buffered to fingerprint and store them.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Response headers kept for replays; the rest are regenerated.
+const REPLAYED_HEADERS: [HeaderName; 3] = [header::CONTENT_TYPE, header::LOCATION, header::ETAG];
+
+/// Makes POSTs sent with an `Idempotency-Key` header safe to retry: the
+/// first response is stored for `ttl` and replayed for the same key, and
+/// reusing the key for a different request is refused with 422.
+#[derive(Clone)]
+pub struct IdempotencyLayer {
+    store: DynamicIdempotencyStore,
+    ttl: Duration,
+    client_ip: Arc<ClientIpResolver>,
+}
+
+impl IdempotencyLayer {
+    pub fn new(
+        store: DynamicIdempotencyStore,
+        ttl: Duration,
+        client_ip: Arc<ClientIpResolver>,
+    ) -> Self {
+        Self {
+            store,
+            ttl,
+            client_ip,
+        }
+    }
+}
+
+impl<S> Layer<S> for IdempotencyLayer {
+    type Service = Idempotency<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Idempotency {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Idempotency<S> {
+    inner: S,
+    layer: IdempotencyLayer,
+}
+
+impl<S> Service<Request> for Idempotency<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // The clone may not be ready; keep the one `poll_ready` was called on.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let layer = self.layer.clone();
+
+        Box::pin(async move {
+            if request.method() != Method::POST {
+                return inner.call(request).await;
+            }
+            let Some(key) = request.headers().get(&IDEMPOTENCY_KEY) else {
+                return inner.call(request).await;
+            };
+            let Some(key) = key
+                .to_str()
+                .ok()
+                .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LEN)
+            else {
+                return Ok(error(
+                    StatusCode::BAD_REQUEST,
+                    "Idempotency-Key must be 1-255 visible characters",
+                ));
+            };
+            // Keys are per credential, or per client IP without one, so a
+            // client can't replay another's response by guessing its key.
+            let caller = match request
+                .headers()
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+            {
+                Some(credential) => format!("auth:{credential}"),
+                None => {
+                    let peer = request
+                        .extensions()
+                        .get::<ConnectInfo<SocketAddr>>()
+                        .map(|ConnectInfo(address)| *address);
+                    match layer.client_ip.resolve(request.headers(), peer) {
+                        Some(ip) => format!("ip:{ip}"),
+                        None => "ip:unknown".to_string(),
+                    }
+                }
+            };
+            let key_hash = hash_token(&format!("{caller}\n{key}"));
+
+            let (parts, body) = request.into_parts();
+            let Ok(body) = to_bytes(body, MAX_BODY_BYTES).await else {
+                return Ok(error(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "Request body too large",
+                ));
+            };
+            let fingerprint = {
+                let mut hasher = Sha256::new();
+                hasher.update(parts.method.as_str());
+                hasher.update(b"\n");
+                hasher.update(parts.uri.to_string());
+                hasher.update(b"\n");
+                hasher.update(&body);
+                hex::encode(hasher.finalize())
+            };
+
+            let now = Utc::now().naive_utc();
+            let expires_at = now + layer.ttl;
+            match layer
+                .store
+                .claim(&key_hash, &fingerprint, expires_at, now)
+                .await
+            {
+                Ok(Claim::Claimed) => {}
+                Ok(Claim::Completed(stored)) => return Ok(replay(stored)),
+                Ok(Claim::InProgress) => {
+                    return Ok(error(
+                        StatusCode::CONFLICT,
+                        "A request with this Idempotency-Key is still in progress",
+                    ));
+                }
+                Ok(Claim::Mismatch) => {
+                    return Ok(error(
+                        StatusCode::UNPROCESSABLE_ENTITY,
+                        "Idempotency-Key was already used for a different request",
+                    ));
+                }
+                Err(error) => {
+                    tracing::error!(%error, "claiming idempotency key failed");
+                    return Ok(self::error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Internal error",
+                    ));
+                }
+            }
+
+            let response = inner
+                .call(Request::from_parts(parts, Body::from(body)))
+                .await?;
+
+            // Failures that may pass on a retry aren't kept, so the retry
+            // actually runs.
+            let status = response.status();
+            if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+                if let Err(error) = layer.store.release(&key_hash).await {
+                    tracing::error!(%error, "releasing idempotency key failed");
+                }
+                return Ok(response);
+            }
+
+            let (parts, body) = response.into_parts();
+            let body = match to_bytes(body, usize::MAX).await {
+                Ok(body) => body,
+                Err(error) => {
+                    tracing::error!(%error, "buffering response failed");
+                    if let Err(error) = layer.store.release(&key_hash).await {
+                        tracing::error!(%error, "releasing idempotency key failed");
+                    }
+                    return Ok(self::error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Internal error",
+                    ));
+                }
+            };
+            let stored = StoredResponse {
+                status: status.as_u16(),
+                headers: REPLAYED_HEADERS
+                    .iter()
+                    .filter_map(|name| {
+                        let value = parts.headers.get(name)?.to_str().ok()?;
+                        Some((name.to_string(), value.to_string()))
+                    })
+                    .collect(),
+                body: body.to_vec(),
+            };
+            if let Err(error) = layer.store.complete(&key_hash, &stored).await {
+                tracing::error!(%error, "storing idempotent response failed");
+            }
+            Ok(Response::from_parts(parts, Body::from(body)))
+        })
+    }
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let headers = response.headers_mut();
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            headers.insert(name, value);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+    response
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+/// Deletes expired keys every `interval`. Expired keys are already ignored
+/// when claiming; this only keeps the table small.
+pub fn spawn_sweeper(
+    store: DynamicIdempotencyStore,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            match store.delete_expired(Utc::now().naive_utc()).await {
+                Ok(0) => {}
+                Ok(count) => tracing::debug!(count, "expired idempotency keys removed"),
+                Err(error) => tracing::error!(%error, "sweeping idempotency keys failed"),
+            }
+        }
+    })
+}
@@ -0,0 +1,155 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::{MySql, Pool};
+
+/// A response kept for replaying.
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum Claim {
+    /// The key is new; the caller runs the request and completes it.
+    Claimed,
+    /// Another request with the key hasn't finished yet.
+    InProgress,
+    Completed(StoredResponse),
+    /// The key was used for a different request.
+    Mismatch,
+}
+
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// Reserves `key_hash` for a request with `fingerprint` until
+    /// `expires_at`, unless an unexpired entry already has it.
+    async fn claim(
+        &self,
+        key_hash: &str,
+        fingerprint: &str,
+        expires_at: NaiveDateTime,
+        now: NaiveDateTime,
+    ) -> sqlx::Result<Claim>;
+    async fn complete(&self, key_hash: &str, response: &StoredResponse) -> sqlx::Result<()>;
+    /// Forgets a claim so the request can be retried with the same key.
+    async fn release(&self, key_hash: &str) -> sqlx::Result<()>;
+    async fn delete_expired(&self, now: NaiveDateTime) -> sqlx::Result<u64>;
+}
+
+pub type DynamicIdempotencyStore = std::sync::Arc<dyn IdempotencyStore>;
+
+#[derive(Clone)]
+pub struct MySqlIdempotencyStore {
+    pool: Pool<MySql>,
+}
+
+impl MySqlIdempotencyStore {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MySqlIdempotencyStore {
+    async fn claim(
+        &self,
+        key_hash: &str,
+        fingerprint: &str,
+        expires_at: NaiveDateTime,
+        now: NaiveDateTime,
+    ) -> sqlx::Result<Claim> {
+        sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE key_hash = ? AND expires_at <= ?",
+            key_hash,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT IGNORE INTO idempotency_keys (key_hash, fingerprint, expires_at)
+            VALUES (?, ?, ?)
+            "#,
+            key_hash,
+            fingerprint,
+            expires_at
+        )
+        .execute(&self.pool)
+        .await?;
+        if inserted.rows_affected() > 0 {
+            return Ok(Claim::Claimed);
+        }
+
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT
+                fingerprint,
+                status_code AS `status_code: u16`,
+                response_headers,
+                response_body
+            FROM idempotency_keys
+            WHERE key_hash = ?
+            "#,
+            key_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        else {
+            // Released between the insert and the select.
+            return Ok(Claim::InProgress);
+        };
+
+        if row.fingerprint != fingerprint {
+            return Ok(Claim::Mismatch);
+        }
+        Ok(match row.status_code {
+            Some(status) => Claim::Completed(StoredResponse {
+                status,
+                headers: row
+                    .response_headers
+                    .and_then(|headers: String| serde_json::from_str(&headers).ok())
+                    .unwrap_or_default(),
+                body: row.response_body.unwrap_or_default(),
+            }),
+            None => Claim::InProgress,
+        })
+    }
+
+    async fn complete(&self, key_hash: &str, response: &StoredResponse) -> sqlx::Result<()> {
+        let headers = serde_json::to_string(&response.headers).unwrap_or_default();
+        sqlx::query!(
+            r#"
+            UPDATE idempotency_keys
+            SET status_code = ?, response_headers = ?, response_body = ?
+            WHERE key_hash = ?
+            "#,
+            response.status,
+            headers,
+            response.body,
+            key_hash
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn release(&self, key_hash: &str) -> sqlx::Result<()> {
+        sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE key_hash = ? AND status_code IS NULL",
+            key_hash
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_expired(&self, now: NaiveDateTime) -> sqlx::Result<u64> {
+        let result = sqlx::query!("DELETE FROM idempotency_keys WHERE expires_at <= ?", now)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue, Uri, header},
+    response::Response,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use tower::{Layer, Service};
+
+pub const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+pub const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// When the unversioned `/api/...` paths were deprecated in favour of
+/// `/api/v1`.
+const DEPRECATED_ON: NaiveDate = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+
+/// Serves the paths from before `/api/v1` by rewriting them to their
+/// `/api/v1` path, and marks the responses deprecated (RFC 9745) with the
+/// date they stop working (RFC 8594) and a link to the new path.
+///
+/// Wraps the whole router: a layer added with `Router::layer` runs after
+/// routing, too late to change the path.
+#[derive(Clone)]
+pub struct LegacyApiLayer {
+    deprecation: HeaderValue,
+    sunset: HeaderValue,
+}
+
+impl LegacyApiLayer {
+    pub fn new(sunset: NaiveDate) -> Self {
+        Self {
+            deprecation: HeaderValue::from_str(&format!(
+                "@{}",
+                midnight(DEPRECATED_ON).and_utc().timestamp()
+            ))
+            .unwrap(),
+            sunset: HeaderValue::from_str(
+                &midnight(sunset)
+                    .format("%a, %d %b %Y %H:%M:%S GMT")
+                    .to_string(),
+            )
+            .unwrap(),
+        }
+    }
+}
+
+impl<S> Layer<S> for LegacyApiLayer {
+    type Service = LegacyApi<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LegacyApi {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LegacyApi<S> {
+    inner: S,
+    layer: LegacyApiLayer,
+}
+
+impl<S> Service<Request> for LegacyApi<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        // The clone may not be ready; keep the one `poll_ready` was called on.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let layer = self.layer.clone();
+
+        let successor = current_path(request.uri().path()).and_then(|path| {
+            let uri = match request.uri().query() {
+                Some(query) => format!("{path}?{query}"),
+                None => path.clone(),
+            };
+            let uri: Uri = uri.parse().ok()?;
+            *request.uri_mut() = uri;
+            Some(path)
+        });
+
+        Box::pin(async move {
+            let mut response = inner.call(request).await?;
+            if let Some(successor) = successor {
+                let headers = response.headers_mut();
+                headers.insert(DEPRECATION, layer.deprecation);
+                headers.insert(SUNSET, layer.sunset);
+                if let Ok(link) =
+                    HeaderValue::from_str(&format!("<{successor}>; rel=\"successor-version\""))
+                {
+                    headers.append(header::LINK, link);
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// The `/api/v1` path of an unversioned `/api/...` path.
+fn current_path(path: &str) -> Option<String> {
+    let rest = path.strip_prefix("/api/")?;
+    if rest == "v1" || rest.starts_with("v1/") {
+        return None;
+    }
+    // The only path that named a collection in the singular.
+    if let Some(rest) = rest.strip_prefix("calendar/") {
+        return Some(format!("/api/v1/calendars/{rest}"));
+    }
+    Some(format!("/api/v1/{rest}"))
+}
+
+fn midnight(date: NaiveDate) -> NaiveDateTime {
+    date.and_hms_opt(0, 0, 0).unwrap()
+}
@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Attachment, Mailbox, MultiPart, SinglePart, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::config::SmtpConfig;
+
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    pub attachments: Vec<EmailAttachment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailAttachment {
+    pub filename: String,
+    pub content_type: String,
+    pub content: String,
+}
+
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct MailerError(pub String);
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailerError>;
+}
+
+pub type DynamicMailer = std::sync::Arc<dyn Mailer>;
+
+/// Writes messages to the log instead of sending them; the default for
+/// local development.
+#[derive(Clone, Default)]
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailerError> {
+        let attachments: Vec<&str> = message
+            .attachments
+            .iter()
+            .map(|a| a.filename.as_str())
+            .collect();
+        tracing::info!(
+            to = %message.to,
+            subject = %message.subject,
+            ?attachments,
+            "email not sent (log mailer):\n{}",
+            message.body
+        );
+        Ok(())
+    }
+}
+
+/// Delivers over SMTP. With `tls = false` it talks plain SMTP, which is what
+/// MailHog-style local catchers expect.
+#[derive(Clone)]
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &SmtpConfig) -> Result<Self, MailerError> {
+        let builder = if config.tls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                .map_err(|e| MailerError(e.to_string()))?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+        };
+        let builder = builder.port(config.port);
+        let builder = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username.clone(), password.clone()))
+            }
+            _ => builder,
+        };
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config
+                .from
+                .parse()
+                .map_err(|e: lettre::address::AddressError| MailerError(e.to_string()))?,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailerError> {
+        let to: Mailbox = message
+            .to
+            .parse()
+            .map_err(|e: lettre::address::AddressError| MailerError(e.to_string()))?;
+
+        let mut body = MultiPart::mixed().singlepart(SinglePart::plain(message.body.clone()));
+        for attachment in &message.attachments {
+            let content_type = ContentType::parse(&attachment.content_type)
+                .map_err(|e| MailerError(e.to_string()))?;
+            body = body.singlepart(
+                Attachment::new(attachment.filename.clone())
+                    .body(attachment.content.clone().into_bytes(), content_type),
+            );
+        }
+
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(message.subject.clone())
+            .multipart(body)
+            .map_err(|e| MailerError(e.to_string()))?;
+
+        self.transport
+            .send(email)
+            .await
+            .map(|_| ())
+            .map_err(|e| MailerError(e.to_string()))
+    }
+}
@@ -1 +1,11 @@
+pub mod challenge;
+pub mod client_ip;
 pub mod database;
+pub mod idempotency;
+pub mod legacy_api;
+pub mod mailer;
+pub mod payments;
+pub mod rate_limit;
+pub mod response_cache;
+pub mod security;
+pub mod sms;
@@ -0,0 +1,101 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use serde::Deserialize;
+
+use super::{
+    Checkout, CheckoutRequest, PaymentError, PaymentEvent, PaymentOutcome, PaymentProvider,
+};
+use crate::infrastructure::security::generate_token;
+
+/// Keeps payments in memory, for local development and tests. The checkout
+/// URL points at the frontend; a payment is completed by posting
+/// `{"payment_id": "...", "status": "succeeded" | "failed"}` to the webhook
+/// with the secret in `X-Fake-Signature`.
+pub struct FakePaymentProvider {
+    webhook_secret: String,
+    checkout_base_url: String,
+    /// Amount and refunded flag per payment id.
+    payments: Mutex<HashMap<String, (u32, bool)>>,
+}
+
+impl FakePaymentProvider {
+    pub fn new(webhook_secret: String, checkout_base_url: String) -> Self {
+        Self {
+            webhook_secret,
+            checkout_base_url,
+            payments: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FakeWebhook {
+    payment_id: String,
+    status: String,
+}
+
+#[async_trait]
+impl PaymentProvider for FakePaymentProvider {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn create_checkout(&self, request: &CheckoutRequest) -> Result<Checkout, PaymentError> {
+        let id = format!("fake_{}", generate_token());
+        self.payments
+            .lock()
+            .unwrap()
+            .insert(id.clone(), (request.amount_cents, false));
+        Ok(Checkout {
+            checkout_url: format!("{}/fake-checkout/{id}", self.checkout_base_url),
+            provider_payment_id: id,
+        })
+    }
+
+    fn parse_webhook(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<Option<PaymentEvent>, PaymentError> {
+        let signature = headers
+            .get("x-fake-signature")
+            .and_then(|value| value.to_str().ok());
+        if signature != Some(self.webhook_secret.as_str()) {
+            return Err(PaymentError::InvalidSignature);
+        }
+
+        let webhook: FakeWebhook = serde_json::from_slice(body)
+            .map_err(|e| PaymentError::InvalidPayload(e.to_string()))?;
+        let outcome = match webhook.status.as_str() {
+            "succeeded" => PaymentOutcome::Succeeded,
+            "failed" => PaymentOutcome::Failed,
+            _ => return Ok(None),
+        };
+        Ok(Some(PaymentEvent {
+            provider_payment_id: webhook.payment_id,
+            outcome,
+            charge_reference: None,
+        }))
+    }
+
+    async fn refund(
+        &self,
+        provider_payment_id: &str,
+        _charge_reference: Option<&str>,
+        amount_cents: u32,
+    ) -> Result<String, PaymentError> {
+        let mut payments = self.payments.lock().unwrap();
+        match payments.get_mut(provider_payment_id) {
+            Some((amount, refunded)) if !*refunded && amount_cents <= *amount => {
+                *refunded = true;
+                Ok(format!("fake_refund_{}", generate_token()))
+            }
+            Some(_) => Err(PaymentError::Provider(
+                "payment already refunded or amount too large".to_string(),
+            )),
+            None => Err(PaymentError::Provider("unknown payment".to_string())),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+mod fake;
+#[cfg(feature = "stripe")]
+mod stripe;
+
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::config::PaymentsConfig;
+
+pub use fake::FakePaymentProvider;
+#[cfg(feature = "stripe")]
+pub use stripe::StripePaymentProvider;
+
+#[derive(Debug, Clone)]
+pub struct CheckoutRequest {
+    pub booking_id: u32,
+    pub amount_cents: u32,
+    pub currency: &'static str,
+    pub description: String,
+    pub customer_email: String,
+    /// The checkout should stop accepting payment after this.
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Checkout {
+    pub provider_payment_id: String,
+    /// Page the customer is sent to for paying.
+    pub checkout_url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentOutcome {
+    Succeeded,
+    Failed,
+}
+
+/// Verified payment notification from the provider.
+#[derive(Debug, Clone)]
+pub struct PaymentEvent {
+    pub provider_payment_id: String,
+    pub outcome: PaymentOutcome,
+    /// Provider's id for the captured money, when refunds need it.
+    pub charge_reference: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum PaymentError {
+    #[error("invalid webhook signature")]
+    InvalidSignature,
+    #[error("invalid webhook payload: {0}")]
+    InvalidPayload(String),
+    #[error("{0}")]
+    Provider(String),
+}
+
+#[async_trait]
+pub trait PaymentProvider: Send + Sync {
+    /// Stored with each payment.
+    fn name(&self) -> &'static str;
+    async fn create_checkout(&self, request: &CheckoutRequest) -> Result<Checkout, PaymentError>;
+    /// Checks the webhook signature and extracts the payment outcome.
+    /// `Ok(None)` for event types we don't act on.
+    fn parse_webhook(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<Option<PaymentEvent>, PaymentError>;
+    /// Returns the provider's refund id.
+    async fn refund(
+        &self,
+        provider_payment_id: &str,
+        charge_reference: Option<&str>,
+        amount_cents: u32,
+    ) -> Result<String, PaymentError>;
+}
+
+pub type DynamicPaymentProvider = std::sync::Arc<dyn PaymentProvider>;
+
+/// Provider named by `PAYMENT_PROVIDER`. Customers come back to
+/// `{frontend_url}/booking-payment` after paying.
+pub fn from_config(config: &PaymentsConfig, frontend_url: &str) -> DynamicPaymentProvider {
+    let return_url = format!("{frontend_url}/booking-payment");
+    match config.provider.as_str() {
+        "fake" => std::sync::Arc::new(FakePaymentProvider::new(
+            config.webhook_secret.clone(),
+            return_url,
+        )),
+        #[cfg(feature = "stripe")]
+        "stripe" => std::sync::Arc::new(StripePaymentProvider::new(
+            config
+                .stripe_secret_key
+                .clone()
+                .expect("STRIPE_SECRET_KEY not set"),
+            config.webhook_secret.clone(),
+            return_url,
+        )),
+        other => panic!("unsupported PAYMENT_PROVIDER {other:?}"),
+    }
+}
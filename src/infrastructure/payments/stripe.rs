@@ -0,0 +1,222 @@
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+
+use super::{
+    Checkout, CheckoutRequest, PaymentError, PaymentEvent, PaymentOutcome, PaymentProvider,
+};
+
+const API_BASE: &str = "https://api.stripe.com/v1";
+/// Webhooks signed longer ago than this are rejected as replays.
+const SIGNATURE_TOLERANCE_SECS: i64 = 5 * 60;
+/// Stripe won't expire a Checkout Session sooner than this.
+const MIN_SESSION_MINUTES: i64 = 30;
+
+/// Stripe Checkout. Each payment is a Checkout Session; the webhook listens
+/// for `checkout.session.completed`, `checkout.session.async_payment_*` and
+/// `checkout.session.expired`.
+pub struct StripePaymentProvider {
+    client: reqwest::Client,
+    secret_key: String,
+    webhook_secret: String,
+    /// Frontend page the customer returns to after paying.
+    return_url: String,
+}
+
+impl StripePaymentProvider {
+    pub fn new(secret_key: String, webhook_secret: String, return_url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            secret_key,
+            webhook_secret,
+            return_url,
+        }
+    }
+
+    fn verify_signature(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), PaymentError> {
+        let header = headers
+            .get("stripe-signature")
+            .and_then(|value| value.to_str().ok())
+            .ok_or(PaymentError::InvalidSignature)?;
+
+        let mut timestamp = None;
+        let mut signatures = Vec::new();
+        for part in header.split(',') {
+            match part.split_once('=') {
+                Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+                Some(("v1", value)) => signatures.push(value),
+                _ => {}
+            }
+        }
+        let timestamp = timestamp.ok_or(PaymentError::InvalidSignature)?;
+        if (Utc::now().timestamp() - timestamp).abs() > SIGNATURE_TOLERANCE_SECS {
+            return Err(PaymentError::InvalidSignature);
+        }
+
+        let valid = signatures.iter().any(|signature| {
+            let Ok(expected) = hex::decode(signature) else {
+                return false;
+            };
+            let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(self.webhook_secret.as_bytes()) else {
+                return false;
+            };
+            mac.update(timestamp.to_string().as_bytes());
+            mac.update(b".");
+            mac.update(body);
+            mac.verify_slice(&expected).is_ok()
+        });
+        if valid {
+            Ok(())
+        } else {
+            Err(PaymentError::InvalidSignature)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct StripeObject {
+    id: String,
+    url: Option<String>,
+    payment_status: Option<String>,
+    payment_intent: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StripeEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    data: StripeEventData,
+}
+
+#[derive(Deserialize)]
+struct StripeEventData {
+    object: StripeObject,
+}
+
+#[derive(Deserialize)]
+struct StripeErrorBody {
+    error: StripeErrorDetail,
+}
+
+#[derive(Deserialize)]
+struct StripeErrorDetail {
+    message: String,
+}
+
+impl StripePaymentProvider {
+    async fn post(
+        &self,
+        path: &str,
+        form: &[(&str, String)],
+    ) -> Result<StripeObject, PaymentError> {
+        let response = self
+            .client
+            .post(format!("{API_BASE}{path}"))
+            .basic_auth(&self.secret_key, None::<&str>)
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| PaymentError::Provider(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let message = response
+                .json::<StripeErrorBody>()
+                .await
+                .map(|body| body.error.message)
+                .unwrap_or_else(|e| e.to_string());
+            return Err(PaymentError::Provider(message));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| PaymentError::Provider(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl PaymentProvider for StripePaymentProvider {
+    fn name(&self) -> &'static str {
+        "stripe"
+    }
+
+    async fn create_checkout(&self, request: &CheckoutRequest) -> Result<Checkout, PaymentError> {
+        let expires_at = request
+            .expires_at
+            .max(Utc::now() + Duration::minutes(MIN_SESSION_MINUTES));
+        let return_url = format!("{}?booking={}", self.return_url, request.booking_id);
+        let form = [
+            ("mode", "payment".to_string()),
+            ("success_url", return_url.clone()),
+            ("cancel_url", return_url),
+            ("customer_email", request.customer_email.clone()),
+            ("expires_at", expires_at.timestamp().to_string()),
+            ("client_reference_id", request.booking_id.to_string()),
+            ("line_items[0][quantity]", "1".to_string()),
+            (
+                "line_items[0][price_data][currency]",
+                request.currency.to_lowercase(),
+            ),
+            (
+                "line_items[0][price_data][unit_amount]",
+                request.amount_cents.to_string(),
+            ),
+            (
+                "line_items[0][price_data][product_data][name]",
+                request.description.clone(),
+            ),
+        ];
+
+        let session = self.post("/checkout/sessions", &form).await?;
+        Ok(Checkout {
+            checkout_url: session
+                .url
+                .ok_or_else(|| PaymentError::Provider("session has no url".to_string()))?,
+            provider_payment_id: session.id,
+        })
+    }
+
+    fn parse_webhook(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<Option<PaymentEvent>, PaymentError> {
+        self.verify_signature(headers, body)?;
+        let event: StripeEvent = serde_json::from_slice(body)
+            .map_err(|e| PaymentError::InvalidPayload(e.to_string()))?;
+        let session = event.data.object;
+
+        let outcome = match event.kind.as_str() {
+            "checkout.session.completed" if session.payment_status.as_deref() == Some("paid") => {
+                PaymentOutcome::Succeeded
+            }
+            "checkout.session.async_payment_succeeded" => PaymentOutcome::Succeeded,
+            "checkout.session.async_payment_failed" | "checkout.session.expired" => {
+                PaymentOutcome::Failed
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(PaymentEvent {
+            provider_payment_id: session.id,
+            outcome,
+            charge_reference: session.payment_intent,
+        }))
+    }
+
+    async fn refund(
+        &self,
+        _provider_payment_id: &str,
+        charge_reference: Option<&str>,
+        amount_cents: u32,
+    ) -> Result<String, PaymentError> {
+        let payment_intent = charge_reference
+            .ok_or_else(|| PaymentError::Provider("payment has no payment intent".to_string()))?;
+        let form = [
+            ("payment_intent", payment_intent.to_string()),
+            ("amount", amount_cents.to_string()),
+        ];
+        Ok(self.post("/refunds", &form).await?.id)
+    }
+}
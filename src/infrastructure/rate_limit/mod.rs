@@ -0,0 +1,179 @@
+mod store;
+
+use std::{
+    convert::Infallible,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, Request},
+    http::{HeaderValue, Method, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use tower::{Layer, Service};
+
+use super::client_ip::ClientIpResolver;
+
+pub use store::{DynamicRateLimitStore, InMemoryRateLimitStore, RateLimitStore};
+
+/// Token bucket size and how long it takes to refill completely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    pub capacity: u32,
+    pub period: Duration,
+}
+
+impl Quota {
+    /// `10/min`, `100/h` or `5/30s`. `off` or `0` disables the limit.
+    pub fn parse(s: &str) -> Result<Option<Self>, String> {
+        let s = s.trim();
+        if s == "off" || s == "0" {
+            return Ok(None);
+        }
+        let (capacity, period) = s
+            .split_once('/')
+            .ok_or_else(|| format!("{s:?} is not like 10/min"))?;
+        let capacity: u32 = capacity
+            .trim()
+            .parse()
+            .map_err(|_| format!("{capacity:?} is not a number"))?;
+        let (count, unit) = match period.find(|c: char| !c.is_ascii_digit()) {
+            Some(0) => (1, period),
+            Some(i) => (
+                period[..i]
+                    .parse()
+                    .map_err(|_| format!("{period:?} is not a period"))?,
+                &period[i..],
+            ),
+            None => return Err(format!("{period:?} has no unit")),
+        };
+        let unit_secs = match unit {
+            "s" => 1,
+            "min" => 60,
+            "h" => 60 * 60,
+            _ => return Err(format!("unknown unit {unit:?}, use s, min or h")),
+        };
+        if capacity == 0 || count == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            capacity,
+            period: Duration::from_secs(count * unit_secs),
+        }))
+    }
+}
+
+/// Limits requests per client IP for the routes it wraps. Each group has
+/// its own buckets, so one client can use several groups' quotas.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    group: &'static str,
+    quota: Quota,
+    store: DynamicRateLimitStore,
+    client_ip: Arc<ClientIpResolver>,
+    writes_only: bool,
+}
+
+impl RateLimitLayer {
+    pub fn new(
+        group: &'static str,
+        quota: Quota,
+        store: DynamicRateLimitStore,
+        client_ip: Arc<ClientIpResolver>,
+    ) -> Self {
+        Self {
+            group,
+            quota,
+            store,
+            client_ip,
+            writes_only: false,
+        }
+    }
+
+    /// Lets GET, HEAD and OPTIONS through without counting them.
+    pub fn writes_only(mut self) -> Self {
+        self.writes_only = true;
+        self
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    layer: RateLimitLayer,
+}
+
+impl<S> Service<Request> for RateLimit<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // The clone may not be ready; keep the one `poll_ready` was called on.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let layer = self.layer.clone();
+
+        Box::pin(async move {
+            let exempt = layer.writes_only
+                && matches!(
+                    *request.method(),
+                    Method::GET | Method::HEAD | Method::OPTIONS
+                );
+            if !exempt {
+                let peer = request
+                    .extensions()
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|ConnectInfo(address)| *address);
+                let key = match layer.client_ip.resolve(request.headers(), peer) {
+                    Some(ip) => format!("{}:{ip}", layer.group),
+                    None => format!("{}:unknown", layer.group),
+                };
+                if let Err(retry_after) = layer.store.acquire(&key, layer.quota).await {
+                    tracing::debug!(key, "rate limited");
+                    return Ok(too_many_requests(retry_after));
+                }
+            }
+            inner.call(request).await
+        })
+    }
+}
+
+fn too_many_requests(retry_after: Duration) -> Response {
+    // Whole seconds, rounded up so clients don't retry too early.
+    let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(serde_json::json!({ "error": "Too many requests" })),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(seconds.max(1)));
+    response
+}
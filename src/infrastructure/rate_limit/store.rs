@@ -0,0 +1,73 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+
+use super::Quota;
+
+/// Where token buckets live. The in-memory store is per process; a shared
+/// backend would let several instances enforce one limit.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Takes a token from the bucket `key`, or returns how long until one
+    /// is available.
+    async fn acquire(&self, key: &str, quota: Quota) -> Result<(), Duration>;
+}
+
+pub type DynamicRateLimitStore = std::sync::Arc<dyn RateLimitStore>;
+
+/// Full buckets are dropped every this many calls to bound memory.
+const PRUNE_EVERY: u64 = 1024;
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    /// An idle bucket is full again after its quota's period.
+    period: Duration,
+}
+
+#[derive(Default)]
+pub struct InMemoryRateLimitStore {
+    state: Mutex<(HashMap<String, Bucket>, u64)>,
+}
+
+impl InMemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for InMemoryRateLimitStore {
+    async fn acquire(&self, key: &str, quota: Quota) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let (buckets, calls) = &mut *state;
+
+        *calls += 1;
+        if *calls % PRUNE_EVERY == 0 {
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated_at) < bucket.period);
+        }
+
+        let capacity = f64::from(quota.capacity);
+        let per_second = capacity / quota.period.as_secs_f64();
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+            period: quota.period,
+        });
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    body::Bytes,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+
+/// Browsers and proxies may reuse a response this long without asking;
+/// after that they revalidate with `If-None-Match` / `If-Modified-Since`.
+const CACHE_CONTROL: &str = "public, max-age=60";
+
+/// Public read endpoints whose responses are cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CachedResource {
+    Calendars,
+    Notices,
+    OpeningHours,
+    ContactInfo,
+}
+
+/// A serialized JSON response with its validators.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    body: Bytes,
+    etag: HeaderValue,
+    last_modified: DateTime<Utc>,
+}
+
+impl CachedResponse {
+    /// The ETag is a hash of the body.
+    pub fn json<T: Serialize>(value: &T) -> Result<Self, AppError> {
+        let body = serde_json::to_vec(value)
+            .map_err(|_| AppError::Internal("Failed to serialize response"))?;
+        let digest = hex::encode(Sha256::digest(&body));
+        let etag = HeaderValue::from_str(&format!("\"{}\"", &digest[..32])).unwrap();
+        Ok(Self {
+            body: body.into(),
+            etag,
+            last_modified: DateTime::UNIX_EPOCH,
+        })
+    }
+
+    /// Keeps a resource's own ETag, e.g. one `If-Match` is checked against.
+    pub fn with_etag(mut self, etag: HeaderValue) -> Self {
+        self.etag = etag;
+        self
+    }
+
+    /// 304 when the client's copy is still current, otherwise the body.
+    /// `If-None-Match` wins over `If-Modified-Since` when both are sent.
+    pub fn respond(&self, request_headers: &HeaderMap) -> Response {
+        let not_modified = match request_headers.get(header::IF_NONE_MATCH) {
+            Some(value) => value.to_str().is_ok_and(|value| self.etag_matches(value)),
+            None => request_headers
+                .get(header::IF_MODIFIED_SINCE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+                .is_some_and(|since| self.last_modified <= since),
+        };
+
+        let mut response = if not_modified {
+            StatusCode::NOT_MODIFIED.into_response()
+        } else {
+            (
+                [(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static("application/json"),
+                )],
+                self.body.clone(),
+            )
+                .into_response()
+        };
+        let headers = response.headers_mut();
+        headers.insert(header::ETAG, self.etag.clone());
+        headers.insert(
+            header::LAST_MODIFIED,
+            HeaderValue::from_str(
+                &self
+                    .last_modified
+                    .format("%a, %d %b %Y %H:%M:%S GMT")
+                    .to_string(),
+            )
+            .unwrap(),
+        );
+        headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(CACHE_CONTROL),
+        );
+        response
+    }
+
+    /// Weak comparison, as `If-None-Match` asks for.
+    fn etag_matches(&self, if_none_match: &str) -> bool {
+        let ours = self.etag.to_str().unwrap_or_default();
+        if_none_match
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == ours)
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    response: Option<CachedResponse>,
+    /// Bumped by `invalidate`, so a load that started before a change
+    /// doesn't store what it read.
+    generation: u64,
+    /// When the data last changed, as far as this process knows.
+    modified_at: Option<DateTime<Utc>>,
+}
+
+/// Serialized responses of public read endpoints, kept until the service
+/// owning the data changes it. Per process: with several instances each
+/// keeps its own copy and only sees its own writes.
+#[derive(Clone)]
+pub struct ResponseCache {
+    slots: Arc<Mutex<HashMap<CachedResource, Slot>>>,
+    /// Stands in for the modification time of data not changed since.
+    started_at: DateTime<Utc>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self {
+            slots: Arc::new(Mutex::new(HashMap::new())),
+            started_at: Utc::now().trunc_subsecs(0),
+        }
+    }
+
+    pub async fn get_or_load<F>(
+        &self,
+        resource: CachedResource,
+        load: F,
+    ) -> Result<CachedResponse, AppError>
+    where
+        F: Future<Output = Result<CachedResponse, AppError>>,
+    {
+        let generation = {
+            let slots = self.slots.lock().unwrap();
+            let slot = slots.get(&resource);
+            if let Some(response) = slot.and_then(|slot| slot.response.clone()) {
+                return Ok(response);
+            }
+            slot.map_or(0, |slot| slot.generation)
+        };
+
+        let mut response = load.await?;
+
+        let mut slots = self.slots.lock().unwrap();
+        let slot = slots.entry(resource).or_default();
+        response.last_modified = slot.modified_at.unwrap_or(self.started_at);
+        if slot.generation == generation {
+            slot.response = Some(response.clone());
+        }
+        Ok(response)
+    }
+
+    /// Called by services after they change the data behind `resource`.
+    pub fn invalidate(&self, resource: CachedResource) {
+        let mut slots = self.slots.lock().unwrap();
+        let slot = slots.entry(resource).or_default();
+        slot.response = None;
+        slot.generation += 1;
+        // Whole seconds, as `Last-Modified` has no finer precision.
+        slot.modified_at = Some(Utc::now().trunc_subsecs(0));
+    }
+}
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use thiserror::Error;
+
+#[derive(Debug, Clone)]
+pub struct SmsMessage {
+    pub to: String,
+    pub body: String,
+}
+
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct SmsError(pub String);
+
+/// Sends text messages. Gateways are plugged in by implementing this trait.
+#[async_trait]
+pub trait SmsProvider: Send + Sync {
+    async fn send(&self, message: &SmsMessage) -> Result<(), SmsError>;
+}
+
+pub type DynamicSmsProvider = std::sync::Arc<dyn SmsProvider>;
+
+/// Writes messages to the log instead of sending them; used until a real
+/// gateway is configured and in tests.
+#[derive(Clone, Default)]
+pub struct LogSmsProvider;
+
+#[async_trait]
+impl SmsProvider for LogSmsProvider {
+    async fn send(&self, message: &SmsMessage) -> Result<(), SmsError> {
+        tracing::info!(to = %message.to, "sms not sent (log provider): {}", message.body);
+        Ok(())
+    }
+}
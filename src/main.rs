@@ -1,16 +1,44 @@
+mod auth;
 mod config;
 mod error;
+mod etag;
 mod features;
 mod infrastructure;
+mod openapi;
 mod response;
 mod state;
 
-use axum::{Router, http::HeaderValue};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::{
+    Router, ServiceExt,
+    http::{HeaderValue, header},
+};
 use config::AppConfig;
 use infrastructure::database::connect;
+use tower::Layer;
 use tower_http::cors::{Any, CorsLayer};
 
-use crate::{infrastructure::database::run_migrations, state::AppState};
+use crate::{
+    features::{
+        bookings::{form_tokens, holds},
+        calendars::repository::MySqlCalendarsRepository,
+        notifications::{
+            dispatcher,
+            reminders::ReminderScheduler,
+            repository::{MySqlOutboxRepository, MySqlRemindersRepository},
+        },
+        payments::expiry,
+    },
+    infrastructure::{
+        database::run_migrations,
+        idempotency::{self, DynamicIdempotencyStore, IdempotencyLayer, MySqlIdempotencyStore},
+        legacy_api::{self, LegacyApiLayer},
+        rate_limit::{DynamicRateLimitStore, InMemoryRateLimitStore, Quota, RateLimitLayer},
+        sms::LogSmsProvider,
+    },
+    state::AppState,
+};
 
 #[tokio::main]
 async fn main() {
@@ -20,25 +48,106 @@ async fn main() {
     let pool = connect(&config.database_url).await;
     run_migrations(&pool).await;
 
-    let app_state = AppState::new(config.clone(), pool);
+    let app_state = AppState::new(config.clone(), pool.clone());
+
+    dispatcher::spawn(
+        Arc::new(MySqlOutboxRepository::new(pool.clone())),
+        app_state.mailer.clone(),
+        Duration::from_secs(config.outbox_poll_interval_secs),
+    );
+    holds::spawn_sweeper(app_state.bookings.clone(), Duration::from_secs(60));
+    form_tokens::spawn_sweeper(
+        Arc::new(form_tokens::MySqlUsedFormTokens::new(pool.clone())),
+        Duration::from_secs(60 * 60),
+    );
+    let idempotency_store: DynamicIdempotencyStore =
+        Arc::new(MySqlIdempotencyStore::new(pool.clone()));
+    idempotency::spawn_sweeper(idempotency_store.clone(), Duration::from_secs(60 * 60));
+    if let Some(payments) = app_state.payments.clone() {
+        expiry::spawn_sweeper(payments, Duration::from_secs(60));
+    }
+    ReminderScheduler {
+        repository: Arc::new(MySqlRemindersRepository::new(pool.clone())),
+        calendars: Arc::new(MySqlCalendarsRepository::new(pool)),
+        sms: Arc::new(LogSmsProvider),
+        timezone: config.venue_timezone,
+        offsets_minutes: config.reminder_offsets_minutes.clone(),
+    }
+    .spawn(Duration::from_secs(config.reminder_poll_interval_secs));
 
     let cors = CorsLayer::new()
         .allow_origin("http://localhost:3000".parse::<HeaderValue>().unwrap())
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([
+            header::ETAG,
+            header::LAST_MODIFIED,
+            idempotency::IDEMPOTENT_REPLAYED,
+            legacy_api::DEPRECATION,
+            legacy_api::SUNSET,
+            header::LINK,
+        ]);
+
+    let rate_limit_store: DynamicRateLimitStore = Arc::new(InMemoryRateLimitStore::new());
+    let rate_limit = |group: &'static str, quota: Option<Quota>| {
+        quota.map(|quota| {
+            RateLimitLayer::new(
+                group,
+                quota,
+                rate_limit_store.clone(),
+                app_state.client_ip.clone(),
+            )
+        })
+    };
+    let limits = &config.rate_limits;
+    let bookings_limit = rate_limit("bookings", limits.bookings).map(RateLimitLayer::writes_only);
+    let auth_limit = rate_limit("auth", limits.auth).map(RateLimitLayer::writes_only);
+    let default_limit = rate_limit("default", limits.default);
+    let idempotency = IdempotencyLayer::new(
+        idempotency_store,
+        Duration::from_secs(config.idempotency_ttl_secs),
+        app_state.client_ip.clone(),
+    );
 
     let app = Router::new()
         .merge(features::calendars::routes())
-        .merge(features::bookings::routes())
+        .merge(limited(features::bookings::routes(), bookings_limit))
+        .merge(features::booking_policies::routes())
+        .merge(features::booking_limits::routes())
+        .merge(features::audit::routes())
         .merge(features::notices::routes())
         .merge(features::opening_hours::routes())
         .merge(features::contact_info::routes())
+        .merge(limited(features::customers::routes(), auth_limit))
+        .merge(features::calendar_feeds::routes())
+        .merge(features::events::routes())
+        .merge(features::pricing::routes())
+        .merge(features::payments::routes())
+        .merge(features::vouchers::routes())
+        .merge(openapi::routes())
         .with_state(app_state)
-        .layer(cors);
+        .route_layer(idempotency);
+    let app = limited(app, default_limit).layer(cors);
+    let app = LegacyApiLayer::new(config.legacy_api_sunset).layer(app);
 
-    let address = std::net::SocketAddr::from(([0, 0, 0, 0], config.port));
+    let address = SocketAddr::from(([0, 0, 0, 0], config.port));
     println!("Listening on http://{address}");
 
     let listener = tokio::net::TcpListener::bind(address).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
+}
+
+fn limited<S>(router: Router<S>, layer: Option<RateLimitLayer>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    match layer {
+        Some(layer) => router.route_layer(layer),
+        None => router,
+    }
 }
@@ -0,0 +1,177 @@
+use axum::Router;
+use serde::Serialize;
+use utoipa::{
+    Modify, OpenApi, ToSchema,
+    openapi::{
+        self,
+        security::{Http, HttpAuthScheme, SecurityScheme},
+    },
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::{features, state::AppState};
+
+/// Body of every error response; `code` and `details` only appear on some.
+#[allow(dead_code)]
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+    /// Which anti-abuse rule refused the request.
+    pub code: Option<String>,
+    /// One message per failed validation.
+    pub details: Option<Vec<String>>,
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Kukkilan Biljardi API"),
+    modifiers(&SecurityAddon),
+    components(schemas(ErrorResponse))
+)]
+struct ApiDoc;
+
+/// `admin`: a staff token from `ADMIN_API_TOKENS`. `customer`: a session
+/// token from `POST /api/v1/customers/login`.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        for name in ["admin", "customer"] {
+            components.add_security_scheme(
+                name,
+                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+            );
+        }
+    }
+}
+
+/// The whole API, merged from each feature's `ApiDoc`.
+pub fn openapi() -> openapi::OpenApi {
+    ApiDoc::openapi()
+        .merge_from(features::audit::ApiDoc::openapi())
+        .merge_from(features::booking_limits::ApiDoc::openapi())
+        .merge_from(features::booking_policies::ApiDoc::openapi())
+        .merge_from(features::bookings::ApiDoc::openapi())
+        .merge_from(features::calendar_feeds::ApiDoc::openapi())
+        .merge_from(features::calendars::ApiDoc::openapi())
+        .merge_from(features::contact_info::ApiDoc::openapi())
+        .merge_from(features::customers::ApiDoc::openapi())
+        .merge_from(features::events::ApiDoc::openapi())
+        .merge_from(features::notices::ApiDoc::openapi())
+        .merge_from(features::opening_hours::ApiDoc::openapi())
+        .merge_from(features::payments::ApiDoc::openapi())
+        .merge_from(features::pricing::ApiDoc::openapi())
+        .merge_from(features::vouchers::ApiDoc::openapi())
+}
+
+/// Serves the document at `/api/v1/openapi.json`, and Swagger UI reading it
+/// at `/api/v1/docs`.
+pub fn routes() -> Router<AppState> {
+    SwaggerUi::new("/api/v1/docs")
+        .url("/api/v1/openapi.json", openapi())
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, fs, path::Path};
+
+    use super::openapi;
+
+    type Route = (&'static str, String);
+
+    const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+    /// Method and path of every `.route(...)` call in the features'
+    /// `routes.rs`, read from the source as axum can't list a router's routes.
+    fn registered_routes() -> BTreeSet<Route> {
+        let features = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/features");
+        let mut routes = BTreeSet::new();
+        for entry in fs::read_dir(features).unwrap() {
+            let Ok(source) = fs::read_to_string(entry.unwrap().path().join("routes.rs")) else {
+                continue;
+            };
+            let mut rest = source.as_str();
+            while let Some(start) = rest.find(".route(") {
+                rest = &rest[start + ".route(".len()..];
+                let call = &rest[..closing_paren(rest)];
+                let path = call
+                    .split('"')
+                    .nth(1)
+                    .expect("route path is a string literal");
+                for method in METHODS {
+                    if calls(call, method) {
+                        routes.insert((method, path.to_string()));
+                    }
+                }
+            }
+        }
+        routes
+    }
+
+    fn documented_routes() -> BTreeSet<Route> {
+        let mut routes = BTreeSet::new();
+        for (path, item) in openapi().paths.paths {
+            let operations = [&item.get, &item.post, &item.put, &item.patch, &item.delete];
+            for (method, operation) in METHODS.into_iter().zip(operations) {
+                if operation.is_some() {
+                    routes.insert((method, path.clone()));
+                }
+            }
+        }
+        routes
+    }
+
+    /// Index of the `)` closing a call whose arguments start `s`.
+    fn closing_paren(s: &str) -> usize {
+        let mut depth = 1;
+        for (i, c) in s.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return i;
+                    }
+                }
+                _ => {}
+            }
+        }
+        panic!("unbalanced .route( call");
+    }
+
+    /// Whether `method(` appears as a call, not as the end of a longer name.
+    fn calls(s: &str, method: &str) -> bool {
+        let needle = format!("{method}(");
+        s.match_indices(&needle).any(|(i, _)| {
+            !s[..i]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_')
+        })
+    }
+
+    #[test]
+    fn every_route_is_documented() {
+        let registered = registered_routes();
+        assert!(!registered.is_empty(), "no routes found");
+        let documented = documented_routes();
+        let undocumented: Vec<_> = registered.difference(&documented).collect();
+        assert!(
+            undocumented.is_empty(),
+            "routes without #[utoipa::path]: {undocumented:?}"
+        );
+    }
+
+    #[test]
+    fn every_documented_route_exists() {
+        let documented = documented_routes();
+        let registered = registered_routes();
+        let unknown: Vec<_> = documented.difference(&registered).collect();
+        assert!(
+            unknown.is_empty(),
+            "documented routes not served: {unknown:?}"
+        );
+    }
+}
@@ -1,16 +1,48 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use crate::{
     config::AppConfig,
     features::{
-        bookings::{repository::MySqlBookingsRepository, service::BookingsService},
+        audit::{repository::MySqlAuditRepository, service::AuditService},
+        booking_limits::{repository::MySqlBookingLimitsRepository, service::BookingLimitsService},
+        booking_policies::{
+            repository::MySqlBookingPoliciesRepository, service::BookingPoliciesService,
+        },
+        bookings::{
+            bot_protection::BotProtection, form_tokens::MySqlUsedFormTokens,
+            repository::MySqlBookingsRepository, service::BookingsService,
+        },
+        calendar_feeds::{repository::MySqlFeedTokensRepository, service::CalendarFeedsService},
         calendars::{repository::MySqlCalendarsRepository, service::CalendarsService},
         contact_info::{repository::MySqlContactInfoRepository, service::ContactInfoService},
+        customers::{repository::MySqlCustomersRepository, service::CustomersService},
+        events::bus::EventBus,
         notices::{repository::MySqlNoticesRepository, service::NoticesService},
         opening_hours::{
-            repository::{MySqlOpeningExceptionsRepository, MySqlOpeningHoursRepository},
-            service::{OpeningExceptionsService, OpeningHoursService},
+            repository::{
+                MySqlOpeningExceptionsRepository, MySqlOpeningHolidaysRepository,
+                MySqlOpeningHoursRepository, MySqlOpeningSchedulesRepository,
+            },
+            service::{
+                EffectiveHoursService, OpeningExceptionsService, OpeningHolidaysService,
+                OpeningHoursService, OpeningSchedulesService,
+            },
+        },
+        payments::{
+            repository::MySqlPaymentsRepository,
+            service::{DepositPolicy, PaymentsService},
+        },
+        pricing::{
+            engine::PricingSettings, repository::MySqlPricingRulesRepository,
+            service::PricingService,
         },
+        vouchers::{repository::MySqlVouchersRepository, service::VouchersService},
+    },
+    infrastructure::{
+        client_ip::ClientIpResolver,
+        mailer::{DynamicMailer, LogMailer, SmtpMailer},
+        payments,
+        response_cache::ResponseCache,
     },
 };
 use sqlx::{MySql, Pool};
@@ -20,12 +52,30 @@ use sqlx::{MySql, Pool};
 pub struct AppState {
     pub config: AppConfig,
     pub pool: Pool<MySql>,
+    pub mailer: DynamicMailer,
+    pub events: EventBus,
+    pub client_ip: Arc<ClientIpResolver>,
+    /// Serialized responses of the public read endpoints.
+    pub response_cache: ResponseCache,
     pub calendars: CalendarsService,
     pub bookings: BookingsService,
+    pub bot_protection: BotProtection,
+    pub booking_policies: BookingPoliciesService,
+    pub booking_limits: BookingLimitsService,
+    pub audit: AuditService,
     pub notices: NoticesService,
     pub opening_hours: OpeningHoursService,
     pub opening_exceptions: OpeningExceptionsService,
+    pub opening_holidays: OpeningHolidaysService,
+    pub opening_schedules: OpeningSchedulesService,
+    pub effective_hours: EffectiveHoursService,
     pub contact_info: ContactInfoService,
+    pub customers: CustomersService,
+    pub calendar_feeds: CalendarFeedsService,
+    pub pricing: PricingService,
+    pub vouchers: VouchersService,
+    /// `None` when online payments are disabled.
+    pub payments: Option<PaymentsService>,
 }
 
 impl AppState {
@@ -36,17 +86,145 @@ impl AppState {
         let opening_hours_repository = Arc::new(MySqlOpeningHoursRepository::new(pool.clone()));
         let opening_exceptions_repository =
             Arc::new(MySqlOpeningExceptionsRepository::new(pool.clone()));
+        let opening_holidays_repository =
+            Arc::new(MySqlOpeningHolidaysRepository::new(pool.clone()));
+        let opening_schedules_repository =
+            Arc::new(MySqlOpeningSchedulesRepository::new(pool.clone()));
         let contact_info_repository = Arc::new(MySqlContactInfoRepository::new(pool.clone()));
+        let customers_repository = Arc::new(MySqlCustomersRepository::new(pool.clone()));
+        let feed_tokens_repository = Arc::new(MySqlFeedTokensRepository::new(pool.clone()));
+        let pricing_rules_repository = Arc::new(MySqlPricingRulesRepository::new(pool.clone()));
+        let booking_policies_repository =
+            Arc::new(MySqlBookingPoliciesRepository::new(pool.clone()));
+        let booking_limits_repository = Arc::new(MySqlBookingLimitsRepository::new(pool.clone()));
+        let audit_repository = Arc::new(MySqlAuditRepository::new(pool.clone()));
+        let vouchers_repository = Arc::new(MySqlVouchersRepository::new(pool.clone()));
+
+        let mailer: DynamicMailer = match &config.smtp {
+            Some(smtp) => Arc::new(SmtpMailer::new(smtp).expect("invalid SMTP configuration")),
+            None => Arc::new(LogMailer),
+        };
+
+        let venue_timezone = config.venue_timezone;
+        let hold_ttl_secs = config.hold_ttl_secs;
+        let max_holds_per_ip = config.max_holds_per_ip;
+        let events = EventBus::new();
+        let pricing = PricingService::new(
+            pricing_rules_repository,
+            calendars_repository.clone(),
+            venue_timezone,
+            PricingSettings {
+                minimum_charge_cents: config.pricing_minimum_charge_cents,
+                rounding_cents: config.pricing_rounding_cents,
+            },
+        );
+
+        let booking_policies = BookingPoliciesService::new(
+            booking_policies_repository,
+            calendars_repository.clone(),
+            venue_timezone,
+        );
+        let audit = AuditService::new(audit_repository);
+        let response_cache = ResponseCache::new();
+        let booking_limits = BookingLimitsService::new(
+            booking_limits_repository,
+            audit.clone(),
+            config.booking_limits.clone(),
+            venue_timezone,
+        );
+        let vouchers = VouchersService::new(
+            vouchers_repository,
+            calendars_repository.clone(),
+            venue_timezone,
+        );
+        let payments = config.payments.as_ref().map(|payments_config| {
+            PaymentsService::new(
+                payments::from_config(payments_config, &config.frontend_url),
+                DepositPolicy::from_config(payments_config),
+                Arc::new(MySqlPaymentsRepository::new(pool.clone())),
+                bookings_repository.clone(),
+                calendars_repository.clone(),
+                venue_timezone,
+                events.clone(),
+            )
+        });
 
         Self {
+            customers: CustomersService::new(
+                customers_repository.clone(),
+                config.frontend_url.clone(),
+            ),
+            bot_protection: BotProtection::new(
+                &config.bot_protection,
+                Arc::new(MySqlUsedFormTokens::new(pool.clone())),
+            ),
+            client_ip: Arc::new(ClientIpResolver::new(config.trusted_proxies.clone())),
             config,
             pool,
-            calendars: CalendarsService::new(calendars_repository),
-            bookings: BookingsService::new(bookings_repository),
-            notices: NoticesService::new(notices_repository),
-            opening_hours: OpeningHoursService::new(opening_hours_repository),
-            opening_exceptions: OpeningExceptionsService::new(opening_exceptions_repository),
-            contact_info: ContactInfoService::new(contact_info_repository),
+            mailer,
+            calendar_feeds: CalendarFeedsService::new(
+                feed_tokens_repository,
+                calendars_repository.clone(),
+                bookings_repository.clone(),
+            ),
+            bookings: BookingsService::new(
+                bookings_repository,
+                calendars_repository.clone(),
+                customers_repository,
+                pricing.clone(),
+                booking_policies.clone(),
+                booking_limits.clone(),
+                vouchers.clone(),
+                payments.clone(),
+                venue_timezone,
+                events.clone(),
+                audit.clone(),
+                Duration::from_secs(hold_ttl_secs),
+                max_holds_per_ip,
+            ),
+            calendars: CalendarsService::new(
+                calendars_repository,
+                events.clone(),
+                audit.clone(),
+                response_cache.clone(),
+            ),
+            events,
+            pricing,
+            booking_policies,
+            booking_limits,
+            vouchers,
+            payments,
+            notices: NoticesService::new(notices_repository, audit.clone(), response_cache.clone()),
+            effective_hours: EffectiveHoursService::new(
+                opening_hours_repository.clone(),
+                opening_exceptions_repository.clone(),
+                opening_holidays_repository.clone(),
+                opening_schedules_repository.clone(),
+            ),
+            opening_hours: OpeningHoursService::new(
+                opening_hours_repository,
+                audit.clone(),
+                response_cache.clone(),
+            ),
+            opening_exceptions: OpeningExceptionsService::new(
+                opening_exceptions_repository,
+                audit.clone(),
+            ),
+            opening_holidays: OpeningHolidaysService::new(
+                opening_holidays_repository,
+                audit.clone(),
+            ),
+            opening_schedules: OpeningSchedulesService::new(
+                opening_schedules_repository,
+                audit.clone(),
+            ),
+            contact_info: ContactInfoService::new(
+                contact_info_repository,
+                audit.clone(),
+                response_cache.clone(),
+            ),
+            response_cache,
+            audit,
         }
     }
 }